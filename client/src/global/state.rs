@@ -1,11 +1,11 @@
 //! Global state for the Tichu app
 
 use common::{
-    clean_up_display_name, clean_up_game_code, get_card_combination,
-    get_user_can_play_wished_for_card, next_combo_beats_prev, sort_cards_for_hand,
-    validate_display_name, validate_game_code, Card, CardValue, Deck, OtherPlayerOption,
-    PublicGameStage, PublicGameState, TeamCategories, TichuCallStatus, ValidCardCombo, DRAGON,
-    NO_USER_ID,
+    clean_up_display_name, clean_up_game_code, get_card_combination, get_play_respects_wish,
+    get_user_can_pass, next_combo_beats_prev, sort_cards_by_combos, sort_cards_for_hand,
+    validate_display_name, validate_game_code, CTSMsgKind, Card, CardValue, Deck,
+    OtherPlayerOption, PublicGameStage, PublicGameState, TeamCategories, TichuCallStatus,
+    TichuError, ValidCardCombo, NO_USER_ID,
 };
 use gloo::{
     storage::{LocalStorage, Storage},
@@ -25,6 +25,9 @@ use super::{
 };
 
 pub const USER_ID_STORAGE_KEY: &str = "yew.tichu.user_id";
+pub const SESSION_TOKEN_STORAGE_KEY: &str = "yew.tichu.session_token";
+/// Never written by the client: set it by hand to the server's admin token to send admin commands
+pub const ADMIN_TOKEN_STORAGE_KEY: &str = "yew.tichu.admin_token";
 pub const DISPLAY_NAME_STORAGE_KEY: &str = "yew.tichu.display_name";
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -34,13 +37,43 @@ pub enum WSConnectionStatus {
     Closed,
 }
 
+/// How the cards in the user's hand are laid out
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum HandSortOrder {
+    /// From lowest to highest value
+    ByValue,
+
+    /// Grouped into the combos of the fewest combos the hand can be played out in
+    ByCombos,
+}
+
+impl HandSortOrder {
+    pub fn sort(&self, cards: &mut Vec<Card>) {
+        match self {
+            HandSortOrder::ByValue => sort_cards_for_hand(cards),
+            HandSortOrder::ByCombos => sort_cards_by_combos(cards),
+        }
+    }
+}
+
+/// A request that the server refused to carry out
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActionRejected {
+    pub request: Option<CTSMsgKind>,
+    pub reason: TichuError,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AppReducerAction {
     WebsocketOpen,
     WebsocketError,
     WebsocketClosed,
+    ServerShuttingDown,
     SetGameState(Option<PublicGameState>),
-    SetUserId(String),
+    SetUserId {
+        user_id: String,
+        session_token: String,
+    },
     SetDisplayName(String),
     SetDisplayNameInput(String),
     SetDisplayNameInputError(Option<String>),
@@ -55,14 +88,21 @@ pub enum AppReducerAction {
     AddSelectedPlayCard(usize),
     RemoveSelectedPlayCard(usize),
     SetUserIdToGiveDragonTo(Option<String>),
-    SetShowUserIdToGiveDragonToForm(bool),
     SetWishedForCard(usize),
+    SetActionRejected(Option<ActionRejected>),
+    SetHandSortOrder(HandSortOrder),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AppState {
     pub ws_connection_status: WSConnectionStatus,
+    /// the server said it's restarting, and the websocket hasn't reopened since
+    pub server_restarting: bool,
     pub user_id: String,
+    /// secret that proves to the server that this client is `user_id` when reconnecting
+    pub session_token: String,
+    /// empty unless this client was given the server's admin token
+    pub admin_token: String,
     pub display_name: String,
     pub game_state: Option<PublicGameState>,
 
@@ -84,7 +124,11 @@ pub struct AppState {
     pub selected_play_cards: Vec<Card>,
     pub wished_for_card_value: CardValue,
     pub user_id_to_give_dragon_to: Option<String>,
-    pub show_user_id_to_give_dragon_to_form: bool,
+
+    /// most recent request rejected by the server, shown next to the control that sent it
+    pub action_rejected: Option<ActionRejected>,
+
+    pub hand_sort_order: HandSortOrder,
 }
 
 /// Wraps information about state updates for serializing events as a single JS Object
@@ -132,11 +176,9 @@ impl Reducible for AppState {
                     // CardValue::noop() is equivalent to None
                     next_state.wished_for_card_value = CardValue::noop();
                 }
-                AppReducerAction::SetShowUserIdToGiveDragonToForm(bool) => {
-                    next_state.show_user_id_to_give_dragon_to_form = bool;
-                }
                 AppReducerAction::WebsocketOpen => {
                     next_state.ws_connection_status = WSConnectionStatus::Open;
+                    next_state.server_restarting = false;
                 }
                 AppReducerAction::WebsocketError => {
                     next_state.ws_connection_status = WSConnectionStatus::Error;
@@ -144,10 +186,19 @@ impl Reducible for AppState {
                 AppReducerAction::WebsocketClosed => {
                     next_state.ws_connection_status = WSConnectionStatus::Closed;
                 }
-                AppReducerAction::SetUserId(s) => {
-                    LocalStorage::set(USER_ID_STORAGE_KEY, &s)
+                AppReducerAction::ServerShuttingDown => {
+                    next_state.server_restarting = true;
+                }
+                AppReducerAction::SetUserId {
+                    user_id,
+                    session_token,
+                } => {
+                    LocalStorage::set(USER_ID_STORAGE_KEY, &user_id)
                         .expect("failed to save user_id to local storage");
-                    next_state.user_id = s;
+                    LocalStorage::set(SESSION_TOKEN_STORAGE_KEY, &session_token)
+                        .expect("failed to save session_token to local storage");
+                    next_state.user_id = user_id;
+                    next_state.session_token = session_token;
                 }
                 AppReducerAction::SetDisplayName(s) => {
                     let s = clean_up_display_name(&s);
@@ -217,7 +268,9 @@ impl Reducible for AppState {
                                     }
                                 }
                                 if let Some(game_state) = &mut next_state.game_state {
-                                    sort_cards_for_hand(&mut game_state.current_user.hand);
+                                    next_state
+                                        .hand_sort_order
+                                        .sort(&mut game_state.current_user.hand);
                                 }
                             } else {
                                 warn!(
@@ -244,7 +297,9 @@ impl Reducible for AppState {
                     }
 
                     if let Some(game_state) = &mut next_state.game_state {
-                        sort_cards_for_hand(&mut game_state.current_user.hand);
+                        next_state
+                            .hand_sort_order
+                            .sort(&mut game_state.current_user.hand);
                     }
                 }
                 AppReducerAction::AddSelectedPlayCard(i) => {
@@ -286,6 +341,15 @@ impl Reducible for AppState {
                     next_state.wished_for_card_value =
                         wished_for_card_value.unwrap_or_else(|| CardValue::noop());
                 }
+                AppReducerAction::SetActionRejected(action_rejected) => {
+                    next_state.action_rejected = action_rejected;
+                }
+                AppReducerAction::SetHandSortOrder(hand_sort_order) => {
+                    if let Some(game_state) = &mut next_state.game_state {
+                        hand_sort_order.sort(&mut game_state.current_user.hand);
+                    }
+                    next_state.hand_sort_order = hand_sort_order;
+                }
             }
         }
 
@@ -296,9 +360,13 @@ impl Reducible for AppState {
 
 impl Default for AppState {
     fn default() -> Self {
-        // retrieve user_id and display name from local storage
+        // retrieve user_id, session token, admin token, and display name from local storage
         let user_id =
             LocalStorage::get(USER_ID_STORAGE_KEY).unwrap_or_else(|_| String::from(NO_USER_ID));
+        let session_token =
+            LocalStorage::get(SESSION_TOKEN_STORAGE_KEY).unwrap_or_else(|_| String::from(""));
+        let admin_token =
+            LocalStorage::get(ADMIN_TOKEN_STORAGE_KEY).unwrap_or_else(|_| String::from(""));
         let display_name =
             LocalStorage::get(DISPLAY_NAME_STORAGE_KEY).unwrap_or_else(|_| String::from(""));
 
@@ -310,7 +378,10 @@ impl Default for AppState {
 
         AppState {
             ws_connection_status: WSConnectionStatus::Closed,
+            server_restarting: false,
             user_id,
+            session_token,
+            admin_token,
             display_name: display_name.clone(),
             display_name_input: display_name,
             display_name_input_error: None,
@@ -324,14 +395,30 @@ impl Default for AppState {
             trade_to_opponent2: None,
             selected_play_cards: Vec::new(),
             user_id_to_give_dragon_to: None,
-            show_user_id_to_give_dragon_to_form: false,
             // CardValue::noop() is equivalent to None
             wished_for_card_value: CardValue::noop(),
+            action_rejected: None,
+            hand_sort_order: HandSortOrder::ByValue,
         }
     }
 }
 
 impl AppState {
+    /// Why the server rejected the user's last request, if it was one of the given kinds
+    pub fn get_action_rejected_message(&self, requests: &[CTSMsgKind]) -> Option<String> {
+        let action_rejected = self.action_rejected.as_ref()?;
+        if !requests.contains(&action_rejected.request?) {
+            return None;
+        }
+
+        // capitalize the reason for display
+        let reason = action_rejected.reason.to_string();
+        let mut chars = reason.chars();
+        chars
+            .next()
+            .map(|first_char| first_char.to_uppercase().chain(chars).collect())
+    }
+
     pub fn get_prev_played_combo(&self) -> Option<&ValidCardCombo> {
         return if let Some(game_state) = &self.game_state {
             if let PublicGameStage::Play(play_stage) = &game_state.stage {
@@ -369,6 +456,38 @@ impl AppState {
             )
     }
 
+    /// Games with bots in them are practice games, where hand-strength advice is offered
+    pub fn is_practice_game(&self) -> bool {
+        self.game_state.as_ref().is_some_and(|game_state| {
+            game_state
+                .participants
+                .iter()
+                .any(|participant| participant.bot_difficulty.is_some())
+        })
+    }
+
+    /// Only the owner can change the house rules, and only before the game starts
+    pub fn can_update_rules(&self) -> bool {
+        self.ws_connection_status == WSConnectionStatus::Open
+            && self.is_current_user_owner()
+            && self.game_state.as_ref().is_some_and(|game_state| {
+                matches!(
+                    game_state.stage,
+                    PublicGameStage::Lobby | PublicGameStage::Teams(_)
+                )
+            })
+    }
+
+    /// Only the owner can fill empty seats with bots, and only in the lobby
+    pub fn can_add_bot(&self) -> bool {
+        self.ws_connection_status == WSConnectionStatus::Open
+            && self.is_current_user_owner()
+            && self.game_state.as_ref().is_some_and(|game_state| {
+                matches!(game_state.stage, PublicGameStage::Lobby)
+                    && game_state.participants.len() < 4
+            })
+    }
+
     pub fn can_play_cards(&self) -> bool {
         let game_state = if let Some(game_state) = &self.game_state {
             game_state
@@ -383,46 +502,23 @@ impl AppState {
             &self.user_id,
         );
 
-        let combo_contains_dragon = self.selected_play_cards.contains(&DRAGON);
-        let user_has_chosen_a_user_to_given_dragon_to = self.user_id_to_give_dragon_to.is_some();
-
         let wished_for_card_value = game_state.get_wished_for_card_value();
 
-        // CardValue::noop() is equivalent to None
-        let some_card_has_been_wished_for = wished_for_card_value.is_some()
-            && *wished_for_card_value.as_ref().unwrap() != CardValue::noop();
-
         if let Some(combo) = combo {
-            let user_can_play_wished_for_card = if some_card_has_been_wished_for {
-                let wished_for_card_value = wished_for_card_value.as_ref().unwrap();
-                get_user_can_play_wished_for_card(
-                    self.get_prev_played_combo(),
-                    &game_state.current_user.hand,
-                    wished_for_card_value,
-                )
-            } else {
-                false
-            };
-            let combo_contains_wished_for_card = if some_card_has_been_wished_for {
-                let wished_for_card_value = wished_for_card_value.as_ref().unwrap();
-                combo
-                    .cards()
-                    .iter()
-                    .any(|card| card.value == *wished_for_card_value)
-            } else {
-                false
-            };
+            // if there is a wish and the user can play it, the combo must contain it
+            let play_respects_wish = get_play_respects_wish(
+                self.get_prev_played_combo(),
+                &game_state.current_user.hand,
+                &combo,
+                wished_for_card_value.as_ref(),
+                self.is_current_users_turn(),
+            );
 
             self.stage_is_play()
                 && (self.is_current_users_turn() || combo.is_bomb())
                 && self.hand_beats_combo_on_table(&combo)
-                && (!combo_contains_dragon
-                    || combo_contains_dragon && user_has_chosen_a_user_to_given_dragon_to)
-                && (!some_card_has_been_wished_for
-                    || (some_card_has_been_wished_for && !user_can_play_wished_for_card)
-                    || (some_card_has_been_wished_for
-                        && user_can_play_wished_for_card
-                        && combo_contains_wished_for_card))
+                && !self.get_is_waiting_for_dragon_to_be_given()
+                && play_respects_wish
         } else {
             // cards are not a valid combo
             false
@@ -655,30 +751,14 @@ impl AppState {
                     // if a card has been wished for and the user can play it, they can't pass
                     let wished_for_card_value = game_state.get_wished_for_card_value();
 
-                    // CardValue::noop() is equivalent to None
-                    let some_card_has_been_wished_for = wished_for_card_value.is_some()
-                        && *wished_for_card_value.as_ref().unwrap() != CardValue::noop();
-
-                    let user_can_play_wished_for_card = if let Some(prev_combo) = prev_combo {
-                        if some_card_has_been_wished_for {
-                            let wished_for_card_value = wished_for_card_value.as_ref().unwrap();
-                            get_user_can_play_wished_for_card(
-                                Some(prev_combo),
-                                &game_state.current_user.hand,
-                                wished_for_card_value,
-                            )
-                        } else {
-                            false
-                        }
-                    } else {
-                        true
-                    };
-
-                    // user doesn't have to choose an opponent OR does have to choose an opponent and has done so
-                    return (!self.get_user_must_select_user_id_to_give_dragon_to()
-                        || self.get_user_has_selected_user_id_to_give_dragon_to())
-                        && !some_card_has_been_wished_for
-                        || (some_card_has_been_wished_for && !user_can_play_wished_for_card);
+                    // nobody can pass until the Dragon has been given away
+                    return !self.get_is_waiting_for_dragon_to_be_given()
+                        && get_user_can_pass(
+                            &self.user_id,
+                            prev_combo,
+                            &game_state.current_user.hand,
+                            wished_for_card_value.as_ref(),
+                        );
                 }
             }
         }
@@ -686,37 +766,22 @@ impl AppState {
         false
     }
 
+    /// Play is paused while the winner of a Dragon trick chooses an opponent to give it to
+    pub fn get_is_waiting_for_dragon_to_be_given(&self) -> bool {
+        if let Some(game_state) = &self.game_state {
+            if let PublicGameStage::Play(play_state) = &game_state.stage {
+                return play_state.dragon_winner_user_id.is_some();
+            }
+        }
+
+        false
+    }
+
+    /// The current user won a trick with the Dragon and must choose an opponent to give it to
     pub fn get_user_must_select_user_id_to_give_dragon_to(&self) -> bool {
-        let opponent_ids = self.get_opponent_ids();
         if let Some(game_state) = &self.game_state {
-            // game stage is Play
             if let PublicGameStage::Play(play_state) = &game_state.stage {
-                // this is the last user to pass
-
-                if play_state.passes.iter().filter(|pass| pass.passed).count() == 3 {
-                    // last trick has a dragon in it
-                    if play_state
-                        .table
-                        .last()
-                        .expect("There should be cards on the table")
-                        .cards()
-                        .contains(&DRAGON)
-                    {
-                        let (opponent_id_0, opponent_id_1) =
-                            opponent_ids.expect("Opponents should be found in state");
-                        // user has chosen SOME user to give the dragon to
-                        return if let Some(user_id_to_give_dragon_to) =
-                            &self.user_id_to_give_dragon_to
-                        {
-                            // user has chosen an opponent to give the dragon to
-                            !(user_id_to_give_dragon_to == &opponent_id_0
-                                || user_id_to_give_dragon_to == &opponent_id_1)
-                        } else {
-                            // user has NOT chosen an opponent to give the dragon to
-                            true
-                        };
-                    }
-                }
+                return play_state.dragon_winner_user_id.as_ref() == Some(&self.user_id);
             }
         }
 
@@ -9,15 +9,18 @@
 //! However, if a ping_interval HAS been scheduled (i.e. the websocket got setup and then it closed),
 //! then the ping will automatically try to reopen the websocket if it is closed/not working,
 //! so no need to schedule a manual retry timeout.
+//!
+//! When the server announces that it's shutting down, pinging stops, and the retry timeout
+//! waits for as long as the server asked before reconnecting.
 
 use crate::global::{
     js_functions::js_log_with_styling,
-    state::{AppReducerAction, AppState},
+    state::{ActionRejected, AppReducerAction, AppState},
 };
 use anyhow::Error;
 use common::{
-    sort_cards_for_hand, validate_team_name, CTSMsg, CallGrandTichuRequest, CardTrade, CardValue,
-    OtherPlayerOption, PublicGameStage, STCMsg, TeamOption,
+    validate_team_name, AdminCommand, BotDifficulty, CTSMsg, CallGrandTichuRequest, CardTrade,
+    CardValue, GameRules, OtherPlayerOption, PublicGameStage, STCMsg, TeamOption,
 };
 use gloo::{
     timers::callback::{Interval, Timeout},
@@ -50,6 +53,8 @@ pub struct WSState {
     ws_callbacks: Option<WSCallbacks>,
     ping_interval: Option<Interval>,
     reschedule_timeout: Option<Timeout>,
+    /// how long the server asked us to wait before reconnecting, if it's shutting down
+    reconnect_after_ms: Option<u32>,
 }
 
 /// Connects to server websocket and assigns listeners for all websocket events.
@@ -62,8 +67,10 @@ pub fn connect_to_ws(
     if ws_is_none {
         info!("Opening websocket...");
         let url = format!(
-            "ws://localhost:8080/ws?user_id={}",
-            (*app_reducer_handle).user_id
+            "ws://localhost:8080/ws?user_id={}&session_token={}&admin_token={}",
+            (*app_reducer_handle).user_id,
+            (*app_reducer_handle).session_token,
+            (*app_reducer_handle).admin_token
         );
         let ws = WebSocket::new(&url);
         let ws = match ws {
@@ -219,13 +226,16 @@ pub enum CTSMsgInternal {
     RenameTeam(TeamOption),
     CreateGame,
     LeaveGame,
+    AddBot(BotDifficulty),
+    UpdateRules(GameRules),
     StartGrandTichu,
     SubmitTrade,
     PlayCards,
     Pass,
+    GiveDragon,
     CallGrandTichu(CallGrandTichuRequest),
     CallSmallTichu,
-    __AdminSkipToPlay,
+    Admin(AdminCommand),
 
     Ping,
     Pong,
@@ -241,6 +251,12 @@ fn schedule_ws_reconnect(
         warn!("Timeout already scheduled to reopen websocket. Ignoring request.");
         return;
     }
+    // the server knows better than us how long it will take to come back up
+    let interval = (*ws_mut_ref)
+        .borrow_mut()
+        .reconnect_after_ms
+        .take()
+        .unwrap_or(interval);
     info!("Will try to reopen websocket in {}ms", interval);
     let new_timeout = {
         let ws_mut_ref = ws_mut_ref.clone();
@@ -296,6 +312,13 @@ fn send_ws_message(
         };
         (ws_state.ws.is_none(), ws_state.is_alive, ws_is_closed)
     };
+
+    // a new request replaces any error shown for the previous one
+    let is_heartbeat = matches!(msg_type, CTSMsgInternal::Ping | CTSMsgInternal::Pong);
+    if !is_heartbeat && (*app_reducer_handle).action_rejected.is_some() {
+        app_reducer_handle.dispatch(AppReducerAction::SetActionRejected(None));
+    }
+
     match msg_type {
         CTSMsgInternal::Test => {
             _send_ws_message(
@@ -375,6 +398,17 @@ fn send_ws_message(
             _send_ws_message(ws_mut_ref.clone(), CTSMsg::LeaveGame);
             false
         }
+        CTSMsgInternal::AddBot(difficulty) => {
+            if !(*app_reducer_handle).can_add_bot() {
+                warn!(
+                    "State is not ready to add a bot. Ignoring request to send websocket message."
+                );
+                return false;
+            }
+
+            _send_ws_message(ws_mut_ref.clone(), CTSMsg::AddBot { difficulty });
+            false
+        }
         CTSMsgInternal::MoveToTeam(team_option) => {
             _send_ws_message(ws_mut_ref.clone(), CTSMsg::MoveToTeam(team_option));
             false
@@ -419,6 +453,16 @@ fn send_ws_message(
 
             false
         }
+        CTSMsgInternal::UpdateRules(rules) => {
+            if !(*app_reducer_handle).can_update_rules() {
+                warn!(
+                    "State is not ready to update the rules. Ignoring request to send websocket message."
+                );
+                return false;
+            }
+            _send_ws_message(ws_mut_ref.clone(), CTSMsg::UpdateRules(rules));
+            false
+        }
         CTSMsgInternal::StartGrandTichu => {
             if !(*app_reducer_handle).can_start_game() {
                 warn!(
@@ -542,7 +586,6 @@ fn send_ws_message(
                 } else {
                     Some((*app_reducer_handle).wished_for_card_value.clone())
                 };
-            let user_id_to_give_dragon_to = (*app_reducer_handle).user_id_to_give_dragon_to.clone();
 
             // reset state
             app_reducer_handle.dispatch(AppReducerAction::ResetAfterPlayCards);
@@ -552,30 +595,36 @@ fn send_ws_message(
                 CTSMsg::PlayCards {
                     cards,
                     wished_for_card_value,
-                    user_id_to_give_dragon_to,
                 },
             );
             false
         }
         CTSMsgInternal::Pass => {
             if !(*app_reducer_handle).get_can_pass() {
-                // todo!() -- rethink this flow. Is it necessary?
-                // user must select an opponent to give the dragon to
-                if (*app_reducer_handle).get_user_must_select_user_id_to_give_dragon_to()
-                    && !(*app_reducer_handle).get_user_has_selected_user_id_to_give_dragon_to()
-                {
-                    app_reducer_handle
-                        .dispatch(AppReducerAction::SetShowUserIdToGiveDragonToForm(true));
-                }
                 return true;
             }
             _send_ws_message(ws_mut_ref.clone(), CTSMsg::Pass);
-            app_reducer_handle.dispatch(AppReducerAction::SetUserIdToGiveDragonTo(None));
-            app_reducer_handle.dispatch(AppReducerAction::SetShowUserIdToGiveDragonToForm(false));
             true
         }
-        CTSMsgInternal::__AdminSkipToPlay => {
-            _send_ws_message(ws_mut_ref.clone(), CTSMsg::__AdminSkipToPlay);
+        CTSMsgInternal::GiveDragon => {
+            if !(*app_reducer_handle).get_user_must_select_user_id_to_give_dragon_to()
+                || !(*app_reducer_handle).get_user_has_selected_user_id_to_give_dragon_to()
+            {
+                warn!("Can't give away the Dragon until an opponent has been chosen");
+                return false;
+            }
+
+            let user_id = (*app_reducer_handle)
+                .user_id_to_give_dragon_to
+                .clone()
+                .expect("User should have chosen an opponent to give the Dragon to");
+            app_reducer_handle.dispatch(AppReducerAction::SetUserIdToGiveDragonTo(None));
+
+            _send_ws_message(ws_mut_ref.clone(), CTSMsg::GiveDragon { user_id });
+            false
+        }
+        CTSMsgInternal::Admin(admin_command) => {
+            _send_ws_message(ws_mut_ref.clone(), CTSMsg::Admin(admin_command));
             false
         }
     }
@@ -663,8 +712,14 @@ fn handle_ws_message_received(
             STCMsg::Ping => {
                 send_ws_message(app_reducer_handle, ws_mut_ref, CTSMsgInternal::Pong);
             }
-            STCMsg::UserIdAssigned(s) => {
-                app_reducer_handle.dispatch(AppReducerAction::SetUserId(s));
+            STCMsg::UserIdAssigned {
+                user_id,
+                session_token,
+            } => {
+                app_reducer_handle.dispatch(AppReducerAction::SetUserId {
+                    user_id,
+                    session_token,
+                });
             }
             STCMsg::GameState(new_game_state) => {
                 let new_game_state = *new_game_state;
@@ -693,7 +748,9 @@ fn handle_ws_message_received(
                     ));
 
                     // sort current user's hand
-                    sort_cards_for_hand(&mut new_game_state.current_user.hand);
+                    (*app_reducer_handle)
+                        .hand_sort_order
+                        .sort(&mut new_game_state.current_user.hand);
 
                     Box::new(Some(new_game_state))
                 } else {
@@ -725,6 +782,7 @@ fn handle_ws_message_received(
             STCMsg::OwnerReassigned(_) => {}
             STCMsg::UserMovedToTeamA(_) => {}
             STCMsg::UserMovedToTeamB(_) => {}
+            STCMsg::RulesUpdated(_) => {}
             STCMsg::GameStageChanged(_) => {}
             STCMsg::GrandTichuCalled(_, _) => {}
             STCMsg::SmallTichuCalled(_) => {}
@@ -732,10 +790,30 @@ fn handle_ws_message_received(
             STCMsg::CardsPlayed => {}
             STCMsg::FirstCardsDealt => {}
             STCMsg::LastCardsDealt => {}
-            STCMsg::PlayerReceivedDragon => {}
+            STCMsg::DragonTrickWon(_) => {}
+            STCMsg::PlayerReceivedDragon { .. } => {}
             STCMsg::GameEnded => {}
             STCMsg::GameEndedFinal => {}
             STCMsg::UserPassed(_) => {}
+            STCMsg::ServerShuttingDown { reconnect_after_ms } => {
+                warn!(
+                    "Server is shutting down. Will reconnect in {}ms",
+                    reconnect_after_ms
+                );
+                let mut ws_state = (*ws_mut_ref).borrow_mut();
+                ws_state.reconnect_after_ms =
+                    Some(u32::try_from(reconnect_after_ms).unwrap_or(u32::MAX));
+                // stop pinging, so the reconnect waits for the hint once the websocket closes
+                drop(ws_state.ping_interval.take());
+                drop(ws_state);
+                app_reducer_handle.dispatch(AppReducerAction::ServerShuttingDown);
+            }
+            STCMsg::ActionRejected { request, reason } => {
+                warn!("Server rejected request {:?}: {}", request, reason);
+                app_reducer_handle.dispatch(AppReducerAction::SetActionRejected(Some(
+                    ActionRejected { request, reason },
+                )));
+            }
         },
     }
 
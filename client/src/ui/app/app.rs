@@ -1,11 +1,13 @@
 use crate::global::state::AppContext;
 use crate::global::state::AppState;
 use crate::global::ws::use_setup_app_ws;
+use crate::ui::common::server_restarting_banner::ServerRestartingBanner;
 use crate::ui::debug::debug::Debug;
 use crate::ui::grand_tichu::grand_tichu::GrandTichu;
 use crate::ui::join::join::Join;
 use crate::ui::lobby::lobby::Lobby;
 use crate::ui::play::play::Play;
+use crate::ui::score::score::Score;
 use crate::ui::teams::teams::Teams;
 use crate::ui::trade::trade::Trade;
 use common::PublicGameStage;
@@ -31,6 +33,7 @@ pub fn app() -> Html {
     html! {
         <ContextProvider<AppContext> {context}>
             <div>
+            <ServerRestartingBanner />
             {match &app_state.game_state {
                 None => html! { <Join /> },
                 Some(game_state) =>{
@@ -40,7 +43,7 @@ pub fn app() -> Html {
                         PublicGameStage::GrandTichu(_) => html!{ <GrandTichu /> },
                         PublicGameStage::Trade(_) => html!{ <Trade/> },
                         PublicGameStage::Play(_) => html! { <Play /> },
-                        _ => html!{<> </>}
+                        PublicGameStage::Score(_) => html! { <Score /> },
                     }
                 }
             }}
@@ -0,0 +1,24 @@
+use crate::global::state::AppContext;
+use common::CTSMsgKind;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ActionRejectedErrorProps {
+    /// requests whose rejection should be shown here
+    pub requests: Vec<CTSMsgKind>,
+}
+
+/// Shows why the server rejected the user's last request, if it was one of `requests`
+#[function_component(ActionRejectedError)]
+pub fn action_rejected_error(props: &ActionRejectedErrorProps) -> Html {
+    let app_context = use_context::<AppContext>().expect("AppContext not found");
+    let app_state = &*app_context.app_reducer_handle;
+
+    if let Some(message) = app_state.get_action_rejected_message(&props.requests) {
+        html! {
+            <p class="error">{message}</p>
+        }
+    } else {
+        html! {}
+    }
+}
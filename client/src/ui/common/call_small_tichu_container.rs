@@ -1,4 +1,7 @@
 use super::call_small_tichu_button::CallSmallTichuButton;
+use super::tichu_advisor::TichuAdvisor;
+use crate::global::state::AppContext;
+use common::{GameRules, MAX_CARDS_IN_HAND};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
@@ -10,10 +13,20 @@ pub struct CallSmallTichuContainerProps {
 /// Wrapper around CallSmallTichu Button with info about what the button does
 #[function_component(CallSmallTichuContainer)]
 pub fn call_small_tichu_container(props: &CallSmallTichuContainerProps) -> Html {
+    let app_context = use_context::<AppContext>().expect("AppContext not found");
+    let small_tichu_value = app_context
+        .app_reducer_handle
+        .game_state
+        .as_ref()
+        .map_or(GameRules::default().small_tichu_value, |game_state| {
+            game_state.rules.small_tichu_value
+        });
+
     html! {
       <div class="call-small-tichu-container">
           <CallSmallTichuButton show_optional={props.show_optional} />
-          <p>{"± 100 points for going out first"}</p>
+          <p>{format!("± {} points for going out first", small_tichu_value)}</p>
+          <TichuAdvisor hand_size={MAX_CARDS_IN_HAND} />
       </div>
     }
 }
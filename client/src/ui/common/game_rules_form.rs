@@ -0,0 +1,163 @@
+use crate::global::{state::AppContext, ws::CTSMsgInternal};
+use crate::ui::common::action_rejected_error::ActionRejectedError;
+use common::{CTSMsgKind, GameEnd, GameRules};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+/// A house rule chosen from a list of options: selecting an option sends the updated rules
+fn view_rule_select<T: PartialEq + Clone + 'static>(
+    label: &str,
+    mut options: Vec<(String, T)>,
+    current_value: T,
+    rules: &GameRules,
+    set_rule: fn(&mut GameRules, T),
+    send_ws_message: &Callback<CTSMsgInternal>,
+    disabled: bool,
+) -> Html {
+    let id = format!("rule-{}", label.to_lowercase().replace(' ', "-"));
+
+    // rules can be set to values outside of the usual options, so always show the current one
+    if !options.iter().any(|(_, value)| *value == current_value) {
+        options.push(("Custom".into(), current_value.clone()));
+    }
+
+    let handle_select_rule = {
+        let send_ws_message = send_ws_message.clone();
+        let rules = rules.clone();
+        let options = options.clone();
+        Callback::from(move |e: InputEvent| {
+            let target = e.target();
+            let select = target.and_then(|t| t.dyn_into::<HtmlSelectElement>().ok());
+            if let Some((_, value)) =
+                select.and_then(|select| options.get(select.selected_index() as usize))
+            {
+                let mut new_rules = rules.clone();
+                set_rule(&mut new_rules, value.clone());
+                send_ws_message.emit(CTSMsgInternal::UpdateRules(new_rules));
+            }
+        })
+    };
+
+    html! {
+        <div class="game-rule">
+            <label for={id.clone()}>{label}</label>
+            <select
+                name={id.clone()}
+                id={id}
+                oninput={handle_select_rule}
+                disabled={disabled}
+            >
+                {for options.iter().map(|(option_label, value)| {
+                    html!{
+                        <option selected={*value == current_value}>
+                            {option_label}
+                        </option>
+                    }
+                })}
+            </select>
+        </div>
+    }
+}
+
+fn points_options(values: &[i32]) -> Vec<(String, i32)> {
+    values
+        .iter()
+        .map(|value| (format!("{} points", value), *value))
+        .collect()
+}
+
+/// House rules for the game: only the owner can change them, and only before the game starts
+#[function_component(GameRulesForm)]
+pub fn game_rules_form() -> Html {
+    let app_context = use_context::<AppContext>().expect("AppContext not found");
+    let app_state = &*app_context.app_reducer_handle;
+    let rules = if let Some(game_state) = &app_state.game_state {
+        &game_state.rules
+    } else {
+        return html! {};
+    };
+    let send_ws_message = &app_context.send_ws_message;
+    let disabled = !app_state.can_update_rules();
+
+    html! {
+        <div class="game-rules-form">
+            <h2>{"House Rules"}</h2>
+            {view_rule_select(
+                "Game ends",
+                vec![
+                    ("Over 500 points".into(), GameEnd::TargetScore(500)),
+                    ("Over 1000 points".into(), GameEnd::TargetScore(1000)),
+                    ("Over 1500 points".into(), GameEnd::TargetScore(1500)),
+                    ("After 4 rounds".into(), GameEnd::Rounds(4)),
+                    ("After 8 rounds".into(), GameEnd::Rounds(8)),
+                ],
+                rules.game_end.clone(),
+                rules,
+                |rules, game_end| rules.game_end = game_end,
+                send_ws_message,
+                disabled,
+            )}
+            {view_rule_select(
+                "Grand Tichu",
+                vec![("On".into(), true), ("Off".into(), false)],
+                rules.grand_tichu_enabled,
+                rules,
+                |rules, grand_tichu_enabled| rules.grand_tichu_enabled = grand_tichu_enabled,
+                send_ws_message,
+                disabled,
+            )}
+            {view_rule_select(
+                "Cards before Grand Tichu",
+                (6..=10).map(|number| (number.to_string(), number)).collect(),
+                rules.num_cards_before_grand_tichu,
+                rules,
+                |rules, number| rules.num_cards_before_grand_tichu = number,
+                send_ws_message,
+                disabled || !rules.grand_tichu_enabled,
+            )}
+            {view_rule_select(
+                "Small Tichu points",
+                points_options(&[50, 100, 200]),
+                rules.small_tichu_value,
+                rules,
+                |rules, value| rules.small_tichu_value = value,
+                send_ws_message,
+                disabled,
+            )}
+            {view_rule_select(
+                "Grand Tichu points",
+                points_options(&[100, 200, 300, 400]),
+                rules.grand_tichu_value,
+                rules,
+                |rules, value| rules.grand_tichu_value = value,
+                send_ws_message,
+                disabled || !rules.grand_tichu_enabled,
+            )}
+            {view_rule_select(
+                "Turn time limit",
+                vec![
+                    ("None".into(), None),
+                    ("30 seconds".into(), Some(30)),
+                    ("1 minute".into(), Some(60)),
+                    ("2 minutes".into(), Some(120)),
+                ],
+                rules.turn_time_limit_secs,
+                rules,
+                |rules, turn_time_limit_secs| rules.turn_time_limit_secs = turn_time_limit_secs,
+                send_ws_message,
+                disabled,
+            )}
+            {view_rule_select(
+                "Double victory points",
+                points_options(&[100, 200, 300]),
+                rules.double_victory_value,
+                rules,
+                |rules, value| rules.double_victory_value = value,
+                send_ws_message,
+                disabled,
+            )}
+            <ActionRejectedError requests={vec![CTSMsgKind::UpdateRules]} />
+        </div>
+    }
+}
@@ -1,6 +1,12 @@
+pub mod action_rejected_error;
 pub mod button;
 pub mod call_small_tichu_button;
 pub mod call_small_tichu_container;
+pub mod game_rules_form;
 pub mod input;
 pub mod layout;
 pub mod pre_play_hand;
+pub mod round_history;
+pub mod server_restarting_banner;
+pub mod tichu_advisor;
+pub mod turn_timer;
@@ -0,0 +1,121 @@
+use crate::global::state::AppContext;
+use common::{ImmutableTeams, RoundSummary, TichuCallStatus};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct RoundHistoryProps {
+    pub round_summaries: Vec<RoundSummary>,
+    pub teams: ImmutableTeams,
+}
+
+/// Table of how every finished round was scored, one row per team per round
+#[function_component(RoundHistory)]
+pub fn round_history(props: &RoundHistoryProps) -> Html {
+    let app_context = use_context::<AppContext>().expect("AppContext not found");
+    let app_state = &*app_context.app_reducer_handle;
+
+    if props.round_summaries.is_empty() {
+        return html! {};
+    }
+
+    let get_display_name = |user_id: &Option<String>| -> String {
+        let user_id = if let Some(user_id) = user_id {
+            user_id
+        } else {
+            return "-".to_string();
+        };
+        app_state
+            .game_state
+            .as_ref()
+            .and_then(|game_state| {
+                game_state
+                    .participants
+                    .iter()
+                    .find(|participant| participant.user_id == *user_id)
+            })
+            .map(|participant| participant.display_name.clone())
+            .unwrap_or_else(|| user_id.clone())
+    };
+
+    let get_team_name = |team_id: &str| -> String {
+        props
+            .teams
+            .iter()
+            .find(|team| team.id == team_id)
+            .map(|team| team.team_name.clone())
+            .unwrap_or_else(|| team_id.to_string())
+    };
+
+    let get_tichu_results = |round_summary: &RoundSummary| -> String {
+        let mut results = Vec::new();
+        for (calls, call_name) in [
+            (&round_summary.small_tichus, "Tichu"),
+            (&round_summary.grand_tichus, "Grand Tichu"),
+        ] {
+            for call in calls.iter() {
+                let result = match call.tichu_call_status {
+                    TichuCallStatus::Achieved => "made",
+                    TichuCallStatus::Failed => "failed",
+                    _ => continue,
+                };
+                results.push(format!(
+                    "{} {} {}",
+                    get_display_name(&Some(call.user_id.clone())),
+                    result,
+                    call_name
+                ));
+            }
+        }
+        results.join(", ")
+    };
+
+    html! {
+      <div class="round-history">
+        <h2>{"Round History"}</h2>
+        <table>
+          <thead>
+            <tr>
+              <th>{"Round"}</th>
+              <th>{"Team"}</th>
+              <th>{"Cards"}</th>
+              <th>{"Double Victory"}</th>
+              <th>{"Tichus"}</th>
+              <th>{"Round Total"}</th>
+              <th>{"Score"}</th>
+            </tr>
+          </thead>
+          <tbody>
+            {for props.round_summaries.iter().map(|round_summary| {
+              html!{
+                <>
+                  {for round_summary.teams.iter().map(|team_round_score| {
+                    html!{
+                      <tr>
+                        <td>{round_summary.round_number}</td>
+                        <td>{get_team_name(&team_round_score.team_id)}</td>
+                        <td>{team_round_score.card_points}</td>
+                        <td>{team_round_score.double_victory_points}</td>
+                        <td>{team_round_score.tichu_points}</td>
+                        <td>{team_round_score.round_total()}</td>
+                        <td>{team_round_score.running_total}</td>
+                      </tr>
+                    }
+                  })}
+                  <tr class="round-details">
+                    <td colspan="7">
+                      {format!(
+                        "First out: {}. Last out: {}. {}",
+                        get_display_name(&round_summary.first_user_out),
+                        get_display_name(&round_summary.last_user_out),
+                        get_tichu_results(round_summary),
+                      )}
+                    </td>
+                  </tr>
+                </>
+              }
+            })}
+          </tbody>
+        </table>
+      </div>
+    }
+}
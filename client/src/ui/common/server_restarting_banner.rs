@@ -0,0 +1,19 @@
+use crate::global::state::AppContext;
+use yew::prelude::*;
+
+/// Lets the user know that the server is restarting and that they'll be reconnected.
+/// Shown from the server's shutdown notice until the websocket reopens.
+#[function_component(ServerRestartingBanner)]
+pub fn server_restarting_banner() -> Html {
+    let app_context = use_context::<AppContext>().expect("AppContext not found");
+
+    if app_context.app_reducer_handle.server_restarting {
+        html! {
+            <p class="server-restarting-banner">
+                {"The server is restarting. You'll be reconnected to your game in a moment."}
+            </p>
+        }
+    } else {
+        html! {}
+    }
+}
@@ -0,0 +1,75 @@
+use crate::global::state::AppContext;
+use crate::ui::common::button::Button;
+use common::{get_tichu_advice, Card, SearchBudget, TichuAdvice};
+use js_sys::Date;
+use yew::prelude::*;
+
+/// How long to simulate rounds for: the page can't respond in the meantime
+const ADVICE_SEARCH_BUDGET: SearchBudget = SearchBudget::Milliseconds(300);
+
+#[derive(Properties, PartialEq)]
+pub struct TichuAdvisorProps {
+    /// Only advises while the user's hand has exactly this many cards
+    pub hand_size: usize,
+}
+
+/// Opt-in estimate of how likely the user's hand is to go out first, only offered in practice games
+#[function_component(TichuAdvisor)]
+pub fn tichu_advisor(props: &TichuAdvisorProps) -> Html {
+    let app_context = use_context::<AppContext>().expect("AppContext not found");
+    let app_state = &*app_context.app_reducer_handle;
+    // advice is kept along with the hand it was given for, so that it's never shown for another hand
+    let advice_state = use_state(|| None::<(Vec<Card>, TichuAdvice)>);
+
+    let (hand, rules) = if let Some(game_state) = &app_state.game_state {
+        (
+            game_state.current_user.hand.clone(),
+            game_state.rules.clone(),
+        )
+    } else {
+        return html! {};
+    };
+    if !app_state.is_practice_game() || hand.len() != props.hand_size {
+        return html! {};
+    }
+
+    let handle_get_advice = {
+        let advice_state = advice_state.clone();
+        let hand = hand.clone();
+        Callback::from(move |_: MouseEvent| {
+            let seed = Date::now() as u64;
+            if let Some(advice) = get_tichu_advice(&hand, &rules, &ADVICE_SEARCH_BUDGET, seed) {
+                advice_state.set(Some((hand.clone(), advice)));
+            }
+        })
+    };
+
+    match &*advice_state {
+        Some((advice_hand, advice)) if *advice_hand == hand => html! {
+            <div class="tichu-advisor">
+                <p>
+                    {format!(
+                        "Chance of going out first: {:.0}%",
+                        advice.probability_of_going_out_first * 100.0
+                    )}
+                </p>
+                <p>
+                    {format!(
+                        "Expected value of calling: {:+.0} points",
+                        advice.expected_value_of_calling
+                    )}
+                </p>
+                <p class="info">
+                    {format!("Based on {} simulated rounds", advice.number_of_simulations)}
+                </p>
+            </div>
+        },
+        _ => html! {
+            <div class="tichu-advisor">
+                <Button onclick={handle_get_advice}>
+                    {"Estimate Hand Strength"}
+                </Button>
+            </div>
+        },
+    }
+}
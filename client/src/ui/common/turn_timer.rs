@@ -0,0 +1,52 @@
+use crate::global::state::AppContext;
+use gloo::timers::callback::Interval;
+use js_sys::Date;
+use yew::prelude::*;
+
+/// How often the countdown is redrawn
+const COUNTDOWN_INTERVAL_MS: u32 = 250;
+
+/// Below this many seconds, the countdown is highlighted
+const URGENT_SECONDS_LEFT: f64 = 10.0;
+
+/// Counts down until the server acts for the users the game is waiting on.
+/// Only shown when the rules have a turn time limit.
+#[function_component(TurnTimer)]
+pub fn turn_timer() -> Html {
+    let app_context = use_context::<AppContext>().expect("AppContext not found");
+    let now_state = use_state(Date::now);
+
+    {
+        let now_state = now_state.clone();
+        use_effect_with_deps(
+            move |_| {
+                let interval =
+                    Interval::new(COUNTDOWN_INTERVAL_MS, move || now_state.set(Date::now()));
+                move || drop(interval)
+            },
+            (),
+        );
+    }
+
+    let turn_deadline = match app_context
+        .app_reducer_handle
+        .game_state
+        .as_ref()
+        .and_then(|game_state| game_state.turn_deadline)
+    {
+        Some(turn_deadline) => turn_deadline,
+        None => return html! {},
+    };
+    let seconds_left = ((turn_deadline as f64 - *now_state) / 1000.0)
+        .ceil()
+        .max(0.0);
+
+    let mut classes = vec!["turn-timer"];
+    if seconds_left <= URGENT_SECONDS_LEFT {
+        classes.push("urgent");
+    }
+
+    html! {
+        <p class={classes!(classes)}>{format!("{}s left", seconds_left)}</p>
+    }
+}
@@ -0,0 +1,39 @@
+use common::{AdminCommand, AdminStage};
+use yew::prelude::*;
+
+use crate::global::{state::AppContext, ws::CTSMsgInternal};
+
+/// Only shown to clients with an admin token, since the server rejects admin commands from anyone else
+#[function_component(AdminCommands)]
+pub fn admin_commands() -> Html {
+    let app_context = use_context::<AppContext>().expect("AppContext not found");
+    let app_state = &*app_context.app_reducer_handle;
+
+    if app_state.admin_token.is_empty() {
+        return html! {};
+    }
+
+    let send_admin_command = |admin_command: AdminCommand| {
+        let send_ws_message = app_context.send_ws_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            send_ws_message.emit(CTSMsgInternal::Admin(admin_command.clone()));
+        })
+    };
+
+    html! {
+      <>
+        <button onclick={send_admin_command(AdminCommand::JumpToStage(AdminStage::GrandTichu))}>
+          {"Skip to Grand Tichu Stage"}
+        </button>
+        <button onclick={send_admin_command(AdminCommand::JumpToStage(AdminStage::Trade))}>
+          {"Skip to Trade Stage"}
+        </button>
+        <button onclick={send_admin_command(AdminCommand::JumpToStage(AdminStage::Play))}>
+          {"Skip to Play Stage"}
+        </button>
+        <button onclick={send_admin_command(AdminCommand::EndRound)}>
+          {"End Round"}
+        </button>
+      </>
+    }
+}
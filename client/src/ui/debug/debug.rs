@@ -1,6 +1,6 @@
+use super::admin_commands::AdminCommands;
 use super::grand_tichus::AllGrandTichus;
 use super::owner::Owner;
-use super::skip_to_play_button::SkipToPlay;
 use super::small_tichus::AllSmallTichus;
 use super::teams::Teams;
 use super::user_id_to_give_dragon_to::UserIdToGiveDragonTo;
@@ -46,7 +46,7 @@ pub fn debug() -> Html {
                         ""
                 }}
                 </p>
-                <SkipToPlay />
+                <AdminCommands />
                 <ViewParticipants />
                 <Owner />
                 <Teams />
@@ -1,9 +1,9 @@
+pub mod admin_commands;
 pub mod debug;
 pub mod grand_tichus;
 pub mod immutable_team;
 pub mod mutable_team;
 pub mod owner;
-pub mod skip_to_play_button;
 pub mod small_tichus;
 pub mod teams;
 pub mod user_id_to_give_dragon_to;
@@ -3,6 +3,8 @@ use crate::ui::common::button::{Button, ButtonVariant};
 use crate::ui::common::call_small_tichu_container::CallSmallTichuContainer;
 use crate::ui::common::layout::Layout;
 use crate::ui::common::pre_play_hand::PrePlayHand;
+use crate::ui::common::tichu_advisor::TichuAdvisor;
+use crate::ui::common::turn_timer::TurnTimer;
 use crate::ui::icons::check::Check;
 use crate::ui::icons::x::X;
 use common::{CallGrandTichuRequest, TichuCallStatus};
@@ -21,6 +23,12 @@ pub fn grand_tichu() -> Html {
         })
     };
 
+    let rules = app_state
+        .game_state
+        .as_ref()
+        .map(|game_state| game_state.rules.clone())
+        .unwrap_or_default();
+
     let call_grand_tichu_prompt = match app_state.get_current_user_grand_tichu_call_status() {
         Some(call_status @ (TichuCallStatus::Called | TichuCallStatus::Declined)) => html! {
            <>
@@ -48,10 +56,10 @@ pub fn grand_tichu() -> Html {
             <>
                 <h1>{"Call Grand Tichu?"}</h1>
                 <p class="info">
-                    {"+200 extra points if you go out first."}
+                    {format!("+{} extra points if you go out first.", rules.grand_tichu_value)}
                 </p>
                 <p class="info">
-                    {"-200 points if you someone else goes out before you."}
+                    {format!("-{} points if someone else goes out before you.", rules.grand_tichu_value)}
                 </p>
                 <div class="button-container">
                     <Button
@@ -71,12 +79,14 @@ pub fn grand_tichu() -> Html {
                         <X classes={vec!["call-grand-tichu-icon".into(), "x".into()]} />
                     </Button>
                 </div>
+                <TichuAdvisor hand_size={rules.num_cards_before_grand_tichu} />
             </>
         },
     };
 
     html! {
         <Layout classes={vec!["grand-tichu-container".to_string()]}>
+            <TurnTimer />
             {call_grand_tichu_prompt}
 
             <CallSmallTichuContainer show_optional=true />
@@ -2,11 +2,13 @@ use crate::global::{
     state::{AppContext, AppReducerAction},
     ws::CTSMsgInternal,
 };
+use crate::ui::common::action_rejected_error::ActionRejectedError;
 use crate::ui::common::button::Button;
 use crate::ui::common::input::Input;
 use crate::ui::common::layout::Layout;
 use common::{
-    clean_up_display_name, validate_display_name, DISPLAY_NAME_MAX_LEN, GAME_CODE_MAX_LEN,
+    clean_up_display_name, validate_display_name, CTSMsgKind, DISPLAY_NAME_MAX_LEN,
+    GAME_CODE_MAX_LEN,
 };
 use wasm_bindgen::JsCast;
 use web_sys::{EventTarget, HtmlInputElement};
@@ -106,6 +108,7 @@ pub fn join() -> Html {
                     oninput={handle_join_room_room_code_input}
                     value={app_state.join_room_game_code_input.clone()}
                     maxlength={Some(GAME_CODE_MAX_LEN)}
+                    error={app_state.get_action_rejected_message(&[CTSMsgKind::JoinGameWithGameCode])}
                 />
                 <Button
                     button_type="submit"
@@ -132,6 +135,7 @@ pub fn join() -> Html {
                 >
                     {"Create game"}
                 </Button>
+                <ActionRejectedError requests={vec![CTSMsgKind::CreateGame]} />
             </form>
         </Layout>
     }
@@ -1,8 +1,11 @@
 use super::participants_list::ParticipantsList;
 use crate::global::{state::AppContext, ws::CTSMsgInternal};
+use crate::ui::common::action_rejected_error::ActionRejectedError;
 use crate::ui::common::button::{Button, ButtonVariant};
+use crate::ui::common::game_rules_form::GameRulesForm;
 use crate::ui::common::layout::Layout;
 use crate::ui::icons::check::Check;
+use common::{BotDifficulty, CTSMsgKind};
 use yew::prelude::*;
 
 #[function_component(Lobby)]
@@ -14,6 +17,24 @@ pub fn lobby() -> Html {
             send_ws_message.emit(CTSMsgInternal::LeaveGame);
         })
     };
+    let send_add_easy_bot_message = {
+        let send_ws_message = app_context.send_ws_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            send_ws_message.emit(CTSMsgInternal::AddBot(BotDifficulty::Easy));
+        })
+    };
+    let send_add_normal_bot_message = {
+        let send_ws_message = app_context.send_ws_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            send_ws_message.emit(CTSMsgInternal::AddBot(BotDifficulty::Normal));
+        })
+    };
+    let send_add_hard_bot_message = {
+        let send_ws_message = app_context.send_ws_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            send_ws_message.emit(CTSMsgInternal::AddBot(BotDifficulty::Hard));
+        })
+    };
     let app_state = &*app_context.app_reducer_handle;
 
     return if let Some(game_state) = &app_state.game_state {
@@ -36,6 +57,36 @@ pub fn lobby() -> Html {
                     <p class="joined">{"Joined:"}</p>
                     <ParticipantsList />
 
+                    <GameRulesForm />
+
+                    {if app_state.is_current_user_owner() {
+                        html! {
+                            <>
+                                <Button
+                                    onclick={send_add_easy_bot_message}
+                                    disabled={!app_state.can_add_bot()}
+                                >
+                                    {"Add Easy Bot"}
+                                </Button>
+                                <Button
+                                    onclick={send_add_normal_bot_message}
+                                    disabled={!app_state.can_add_bot()}
+                                >
+                                    {"Add Normal Bot"}
+                                </Button>
+                                <Button
+                                    onclick={send_add_hard_bot_message}
+                                    disabled={!app_state.can_add_bot()}
+                                >
+                                    {"Add Hard Bot"}
+                                </Button>
+                                <ActionRejectedError requests={vec![CTSMsgKind::AddBot]} />
+                            </>
+                        }
+                    } else {
+                        html! {}
+                    }}
+
                     <Button
                         onclick={send_leave_game_message}
                         disabled={!app_state.can_leave_game()}
@@ -12,7 +12,10 @@ pub fn view_participants() -> Html {
             <ul class="participants-list">
                 {for game_state.participants.iter().map(|user| {
                     html! {
-                        <li>{&user.display_name}</li>
+                        <li>
+                            {&user.display_name}
+                            {if user.bot_difficulty.is_some() { " (bot)" } else { "" }}
+                        </li>
                     }
                 })}
             </ul>
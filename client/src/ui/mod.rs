@@ -6,5 +6,6 @@ pub mod icons;
 pub mod join;
 pub mod lobby;
 pub mod play;
+pub mod score;
 pub mod teams;
 pub mod trade;
@@ -8,9 +8,12 @@ use super::turns::Turns;
 use super::wish_for_card_input::WishForCardInput;
 use super::wished_for_card::WishedForCard;
 use crate::global::{state::AppContext, ws::CTSMsgInternal};
+use crate::ui::common::action_rejected_error::ActionRejectedError;
 use crate::ui::common::call_small_tichu_button::CallSmallTichuButton;
 use crate::ui::common::layout::Layout;
-use common::{get_card_combination, DRAGON, MAH_JONG};
+use crate::ui::common::round_history::RoundHistory;
+use crate::ui::common::turn_timer::TurnTimer;
+use common::{get_card_combination, CTSMsgKind, PublicGameStage, PublicGameState, MAH_JONG};
 use yew::prelude::*;
 
 #[function_component(Play)]
@@ -32,10 +35,18 @@ pub fn play() -> Html {
         })
     };
 
+    let handle_give_dragon = {
+        let send_ws_message = app_context.send_ws_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            send_ws_message.emit(CTSMsgInternal::GiveDragon);
+        })
+    };
+
     html! {
           <Layout classes={vec!["play-container".to_string()]}>
             <Scores />
             <Turns />
+            <TurnTimer />
             <CardsOnTable />
             <WishedForCard />
             {if app_state.selected_play_cards.contains(&MAH_JONG) {
@@ -43,8 +54,19 @@ pub fn play() -> Html {
             } else {
                 html!{}
             }}
-            {if app_state.selected_play_cards.contains(&DRAGON) {
-               html!{ <ChooseOpponentInput /> }
+            {if app_state.get_user_must_select_user_id_to_give_dragon_to() {
+               html!{
+                <>
+                    <ChooseOpponentInput />
+                    <button
+                        onclick={handle_give_dragon}
+                        type="button"
+                        disabled={!app_state.get_user_has_selected_user_id_to_give_dragon_to()}
+                        >
+                        {"Give Dragon"}
+                    </button>
+                </>
+               }
             } else {
                 html!{}
             }}
@@ -69,6 +91,9 @@ pub fn play() -> Html {
                     </button>
                 }
             }}
+            <ActionRejectedError
+              requests={vec![CTSMsgKind::PlayCards, CTSMsgKind::Pass, CTSMsgKind::GiveDragon]}
+            />
             <CallSmallTichuButton />
             <p>
               {if get_card_combination(app_state
@@ -84,6 +109,16 @@ pub fn play() -> Html {
             <SelectedPlayCards />
             <PlayHand />
             <Tricks />
+            {if let Some(PublicGameState { stage: PublicGameStage::Play(play_state), round_summaries, .. }) = app_state.game_state.as_ref() {
+                html!{
+                    <RoundHistory
+                      round_summaries={round_summaries.clone()}
+                      teams={play_state.teams.clone()}
+                    />
+                }
+            } else {
+                html!{}
+            }}
           </Layout>
     }
 }
@@ -1,4 +1,4 @@
-use crate::global::state::{AppContext, AppReducerAction};
+use crate::global::state::{AppContext, AppReducerAction, HandSortOrder};
 use yew::prelude::*;
 
 #[function_component(PlayHand)]
@@ -12,8 +12,28 @@ pub fn play_hand() -> Html {
         })
     };
 
+    let handle_toggle_hand_sort_order = {
+        let reducer_handle = app_context.app_reducer_handle.clone();
+        let next_hand_sort_order = match app_state.hand_sort_order {
+            HandSortOrder::ByValue => HandSortOrder::ByCombos,
+            HandSortOrder::ByCombos => HandSortOrder::ByValue,
+        };
+        Callback::from(move |_: MouseEvent| {
+            reducer_handle.dispatch(AppReducerAction::SetHandSortOrder(
+                next_hand_sort_order.clone(),
+            ))
+        })
+    };
+
     if let Some(game_state) = &app_state.game_state {
         html! {
+            <>
+                <button onclick={handle_toggle_hand_sort_order}>
+                    {match app_state.hand_sort_order {
+                        HandSortOrder::ByValue => "Group by combos",
+                        HandSortOrder::ByCombos => "Sort by value",
+                    }}
+                </button>
                 <ul>
                     {for game_state.current_user.hand.iter().enumerate().map(|(i, card)| {
                     // do not render card if the stage is Trade and it is currently selected
@@ -35,6 +55,7 @@ pub fn play_hand() -> Html {
                     }
                 })}
                 </ul>
+            </>
         }
     } else {
         html! {}
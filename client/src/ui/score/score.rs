@@ -0,0 +1,39 @@
+use crate::global::state::AppContext;
+use crate::ui::common::layout::Layout;
+use crate::ui::common::round_history::RoundHistory;
+use common::PublicGameStage;
+use yew::prelude::*;
+
+#[function_component(Score)]
+pub fn score() -> Html {
+    let app_context = use_context::<AppContext>().expect("AppContext not found");
+    let app_state = &*app_context.app_reducer_handle;
+
+    let score_state = match &app_state.game_state {
+        Some(game_state) => match &game_state.stage {
+            PublicGameStage::Score(score_state) => score_state,
+            _ => return html! {},
+        },
+        None => return html! {},
+    };
+
+    html! {
+          <Layout classes={vec!["score-container".to_string()]}>
+            <h1>{"Game Over"}</h1>
+            <div class="scores">
+              {for score_state.teams.iter().map(|team| {
+                html!{
+                  <div class="score">
+                    <p class="team-name">{&team.team_name}{":"}</p>
+                    <p class="team-score">{&team.score}</p>
+                  </div>
+                }
+              })}
+            </div>
+            <RoundHistory
+              round_summaries={score_state.round_summaries.clone()}
+              teams={score_state.teams.clone()}
+            />
+          </Layout>
+    }
+}
@@ -3,7 +3,7 @@ use crate::global::{
     ws::CTSMsgInternal,
 };
 use crate::ui::common::input::Input;
-use common::TeamOption;
+use common::{CTSMsgKind, TeamOption};
 use wasm_bindgen::JsCast;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
@@ -58,6 +58,13 @@ pub fn team_input(props: &TeamNameInputProps) -> Html {
 
     let disabled = !app_state.is_team_stage() || is_on_opposite_team;
 
+    // only the user's own team name can be edited, so only show errors there
+    let error = if disabled {
+        None
+    } else {
+        app_state.get_action_rejected_message(&[CTSMsgKind::RenameTeam])
+    };
+
     html! {
         <form onsubmit={handle_team_submit} class="team-input">
             <Input
@@ -67,6 +74,7 @@ pub fn team_input(props: &TeamNameInputProps) -> Html {
                 input_type="text"
                 value={input_value}
                {disabled}
+               {error}
             />
         </form>
     }
@@ -3,6 +3,7 @@ use super::team_name_input::TeamNameInput;
 use super::view_team_as_cards::ViewTeamAsCards;
 use crate::global::{state::AppContext, ws::CTSMsgInternal};
 use crate::ui::common::button::Button;
+use crate::ui::common::game_rules_form::GameRulesForm;
 use crate::ui::common::layout::Layout;
 use common::{PublicGameStage, PublicGameState, TeamOption};
 use yew::prelude::*;
@@ -57,6 +58,8 @@ pub fn teams() -> Html {
                   />
                 </div>
 
+                <GameRulesForm />
+
                 // Start button
                 {if app_state.is_current_user_owner() {
                   html!{
@@ -25,6 +25,11 @@ pub fn view_user_as_card(props: &ViewUserAsCardProps) -> Html {
         html! {
           <div class={classes!(classes)}>
             <p>{&user.display_name}</p>
+            {if user.bot_difficulty.is_some() {
+                html! { <p class="bot-label">{"Bot"}</p> }
+            } else {
+                html! {}
+            }}
           </div>
         }
     } else {
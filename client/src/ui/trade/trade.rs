@@ -1,12 +1,14 @@
 use super::trade_to_person::TradeToPerson;
 use crate::global::{state::AppContext, ws::CTSMsgInternal};
+use crate::ui::common::action_rejected_error::ActionRejectedError;
 use crate::ui::common::button::{Button, ButtonVariant};
 use crate::ui::common::call_small_tichu_container::CallSmallTichuContainer;
 use crate::ui::common::layout::Layout;
 use crate::ui::common::pre_play_hand::PrePlayHand;
+use crate::ui::common::turn_timer::TurnTimer;
 use crate::ui::icons::check::Check;
 use crate::ui::icons::up_arrow::UpArrow;
-use common::OtherPlayerOption;
+use common::{CTSMsgKind, OtherPlayerOption};
 use yew::prelude::*;
 
 #[function_component(Trade)]
@@ -23,6 +25,7 @@ pub fn trade() -> Html {
 
     html! {
           <Layout classes={vec!["trade".to_string()]}>
+              <TurnTimer />
               {if !app_state.has_submitted_trade() {
                   html!{
                         <>
@@ -35,6 +38,7 @@ pub fn trade() -> Html {
                             >
                                 <UpArrow />
                              </Button>
+                            <ActionRejectedError requests={vec![CTSMsgKind::SubmitTrade]} />
 
                             <div class="trade-to-person-container">
                                 <TradeToPerson player={OtherPlayerOption::Opponent1} />
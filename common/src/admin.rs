@@ -0,0 +1,149 @@
+use crate::{
+    get_timeout_action, CTSMsg, CallGrandTichuRequest, Card, Deck, GameEvent, PrivateGameStage,
+    PrivateGameState, TichuError, MAX_CARDS_IN_HAND,
+};
+use serde::{Deserialize, Serialize};
+
+/// A stage that an admin can jump to from the Teams stage
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum AdminStage {
+    /// Start the game, just like the owner would
+    GrandTichu,
+
+    /// Start the game, and decline Grand Tichu for every user
+    Trade,
+
+    /// Skip straight to playing with full hands: nobody calls Grand Tichu and nobody trades
+    Play,
+}
+
+/// Commands for setting up a game by hand while testing.
+///
+/// The server only carries these out for admins.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum AdminCommand {
+    /// Move from the Teams stage to the chosen stage
+    JumpToStage(AdminStage),
+
+    /// Skip from the Teams stage straight to the Play stage, dealing each user the given cards,
+    /// in participant order. Hands with fewer than 14 cards are filled from the rest of the deck.
+    DealHands { hands: [Vec<Card>; 4] },
+
+    /// Overwrite both teams' scores, in team order
+    SetScores { scores: [i32; 2] },
+
+    /// Play out the rest of the round as if every user ran out of time on every turn
+    EndRound,
+}
+
+impl PrivateGameState {
+    /// Carries out an admin command. Checking that it came from an admin is up to the server.
+    pub fn run_admin_command(&self, admin_command: &AdminCommand) -> Result<Self, TichuError> {
+        match admin_command {
+            AdminCommand::JumpToStage(stage) => self.admin_jump_to_stage(stage),
+            AdminCommand::DealHands { hands } => self.admin_deal_hands(hands),
+            AdminCommand::SetScores { scores } => {
+                self.apply(&GameEvent::__AdminScoresSet { scores: *scores })
+            }
+            AdminCommand::EndRound => self.admin_end_round(),
+        }
+    }
+
+    fn admin_jump_to_stage(&self, stage: &AdminStage) -> Result<Self, TichuError> {
+        match stage {
+            AdminStage::GrandTichu => self.start_grand_tichu(&self.owner_id),
+            AdminStage::Trade => {
+                let mut new_game_state = self.start_grand_tichu(&self.owner_id)?;
+                for participant in self.participants.iter() {
+                    new_game_state = new_game_state
+                        .call_grand_tichu(&CallGrandTichuRequest::Decline, &participant.user_id)?;
+                }
+                Ok(new_game_state)
+            }
+            AdminStage::Play => self.__admin_skip_to_play(),
+        }
+    }
+
+    fn admin_deal_hands(&self, hands: &[Vec<Card>; 4]) -> Result<Self, TichuError> {
+        if hands.iter().any(|hand| hand.len() > MAX_CARDS_IN_HAND) {
+            return Err(TichuError::InvalidHand);
+        }
+
+        // take the chosen cards out of the round's usual deck, to fill up short hands with
+        let mut rest_of_deck = Deck::new().shuffle_with_seed(self.get_round_seed()).0;
+        let chosen_cards: Vec<&Card> = hands.iter().flatten().collect();
+        for card in chosen_cards.iter() {
+            match rest_of_deck.iter().position(|deck_card| deck_card == *card) {
+                Some(i) => {
+                    rest_of_deck.remove(i);
+                }
+                None if chosen_cards.iter().filter(|other| other == &card).count() > 1 => {
+                    return Err(TichuError::DuplicateCard((*card).clone()));
+                }
+                None => return Err(TichuError::InvalidHand),
+            }
+        }
+
+        let mut deck = Vec::new();
+        for hand in hands.iter() {
+            deck.extend(hand.iter().cloned());
+            deck.extend(rest_of_deck.drain(..(MAX_CARDS_IN_HAND - hand.len())));
+        }
+
+        self.apply(&GameEvent::__AdminSkippedToPlay { deck: Deck(deck) })
+    }
+
+    fn admin_end_round(&self) -> Result<Self, TichuError> {
+        if !matches!(
+            self.stage,
+            PrivateGameStage::GrandTichu(_)
+                | PrivateGameStage::Trade(_)
+                | PrivateGameStage::Play(_)
+        ) {
+            return Err(TichuError::WrongStage);
+        }
+
+        let round_count = self.round_summaries.len();
+        let seed = self.get_round_seed();
+        let mut new_game_state = self.clone();
+
+        // one action at a time, since every action can change what the others may do
+        while new_game_state.round_summaries.len() == round_count {
+            let (user_id, msg) = new_game_state
+                .participants
+                .iter()
+                .find_map(|participant| {
+                    let observation = new_game_state.get_observation(&participant.user_id).ok()?;
+                    get_timeout_action(&observation, seed)
+                        .map(|msg| (participant.user_id.clone(), msg))
+                })
+                .ok_or_else(|| {
+                    TichuError::Internal("No user can act, so the round can't be ended".into())
+                })?;
+            new_game_state = new_game_state.admin_act_for_user(&user_id, msg)?;
+        }
+
+        Ok(new_game_state)
+    }
+
+    fn admin_act_for_user(&self, user_id: &str, msg: CTSMsg) -> Result<Self, TichuError> {
+        match msg {
+            CTSMsg::CallGrandTichu(call_grand_tichu_request) => {
+                self.call_grand_tichu(&call_grand_tichu_request, user_id)
+            }
+            CTSMsg::CallSmallTichu => self.call_small_tichu(user_id),
+            CTSMsg::SubmitTrade(submit_trade) => self.submit_trade(user_id, &submit_trade),
+            CTSMsg::PlayCards {
+                cards,
+                wished_for_card_value,
+            } => self.play_cards(user_id, cards, wished_for_card_value),
+            CTSMsg::Pass => self.pass(user_id),
+            CTSMsg::GiveDragon {
+                user_id: recipient_user_id,
+            } => self.give_dragon(user_id, &recipient_user_id),
+            _ => Err(TichuError::Internal(format!(
+                "Timeout actions should only be game requests, not {msg:?}"
+            ))),
+        }
+    }
+}
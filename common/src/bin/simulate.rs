@@ -0,0 +1,383 @@
+//! Plays complete games between two bot strategies and reports how each of them did.
+//!
+//! Games only go through `PrivateGameState` transitions. Invariants are checked after every
+//! request and every finished game is replayed from its events, so any rejected request, panic,
+//! broken invariant, or inconsistent event log is reported along with the seed of the game it
+//! happened in. The same arguments always produce the same report.
+//!
+//! Usage: `cargo run --release --bin simulate -- [--games N] [--seed SEED] <strategy> <strategy>`
+//!
+//! Strategies: `easy`, `normal`, `hard`, or `ismcts:<iterations>`
+
+use common::{
+    get_bot_action, get_card_combination, get_ismcts_action, BotDifficulty, CTSMsg, GameEvent,
+    Observation, PrivateGameStage, PrivateGameState, SearchBudget, SmallTichuArray,
+    TichuCallStatus,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::{env, process};
+
+const DEFAULT_NUM_GAMES: u64 = 100;
+const DEFAULT_SEED: u64 = 0;
+
+/// A game that takes more actions than this is assumed to be stuck
+const MAX_ACTIONS_PER_GAME: usize = 100_000;
+
+const USAGE: &str =
+    "Usage: simulate [--games N] [--seed SEED] <strategy> <strategy>\n\nStrategies: easy, normal, hard, ismcts:<iterations>";
+
+/// Chooses the actions for both users on a team
+trait Strategy {
+    fn get_action(&self, observation: &Observation, seed: u64) -> Option<CTSMsg>;
+}
+
+impl Strategy for BotDifficulty {
+    fn get_action(&self, observation: &Observation, seed: u64) -> Option<CTSMsg> {
+        get_bot_action(observation, self, seed)
+    }
+}
+
+/// Searches for every play with a fixed number of iterations.
+/// Plays like a Normal bot before the Play stage.
+struct Ismcts {
+    iterations: u32,
+}
+
+impl Strategy for Ismcts {
+    fn get_action(&self, observation: &Observation, seed: u64) -> Option<CTSMsg> {
+        get_ismcts_action(
+            observation,
+            &SearchBudget::Iterations(self.iterations),
+            seed,
+        )
+        .or_else(|| get_bot_action(observation, &BotDifficulty::Normal, seed))
+    }
+}
+
+fn parse_strategy(name: &str) -> Result<Box<dyn Strategy>, String> {
+    match name {
+        "easy" => Ok(Box::new(BotDifficulty::Easy)),
+        "normal" => Ok(Box::new(BotDifficulty::Normal)),
+        "hard" => Ok(Box::new(BotDifficulty::Hard)),
+        _ => match name.strip_prefix("ismcts:").map(str::parse) {
+            Some(Ok(iterations)) if iterations > 0 => Ok(Box::new(Ismcts { iterations })),
+            _ => Err(format!("Unknown strategy \"{name}\"")),
+        },
+    }
+}
+
+/// Everything a strategy did across all of the games it played
+#[derive(Debug, Default)]
+struct StrategyStats {
+    games_won: u64,
+    rounds_played: u64,
+    round_points: i64,
+    small_tichus_called: u64,
+    small_tichus_achieved: u64,
+    grand_tichus_called: u64,
+    grand_tichus_achieved: u64,
+    double_victories: u64,
+    bombs: u64,
+}
+
+impl StrategyStats {
+    fn add_tichus(&mut self, tichus: &SmallTichuArray, team_user_ids: &[String; 2], grand: bool) {
+        for tichu in tichus.iter() {
+            if !team_user_ids.contains(&tichu.user_id) {
+                continue;
+            }
+            let (called, achieved) = if grand {
+                (
+                    &mut self.grand_tichus_called,
+                    &mut self.grand_tichus_achieved,
+                )
+            } else {
+                (
+                    &mut self.small_tichus_called,
+                    &mut self.small_tichus_achieved,
+                )
+            };
+            match tichu.tichu_call_status {
+                TichuCallStatus::Achieved => {
+                    *called += 1;
+                    *achieved += 1;
+                }
+                TichuCallStatus::Failed => *called += 1,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Plays a game from the lobby to the scoreboard.
+///
+/// `strategies[0]` plays for Team A and `strategies[1]` plays for Team B.
+fn play_game(strategies: [&dyn Strategy; 2], game_seed: u64) -> Result<PrivateGameState, String> {
+    let mut game_state =
+        PrivateGameState::new_with_seed("1".into(), "User 1".into(), &HashMap::new(), game_seed);
+    for user_id in ["2", "3", "4"] {
+        game_state = game_state
+            .add_user(user_id.into(), format!("User {user_id}"))
+            .map_err(|err| format!("User {user_id} couldn't join: {err:?}"))?;
+    }
+    game_state = game_state
+        .start_grand_tichu("1")
+        .map_err(|err| format!("Game couldn't start: {err:?}"))?;
+    let team_user_ids = get_team_user_ids(&game_state)?;
+
+    let mut rng = ChaCha8Rng::seed_from_u64(game_seed);
+    for _ in 0..MAX_ACTIONS_PER_GAME {
+        if matches!(game_state.stage, PrivateGameStage::Score(_)) {
+            return Ok(game_state);
+        }
+
+        // the first user with something to do acts, then everyone looks at the game again
+        let seed = rng.gen();
+        let action = game_state.participants.iter().find_map(|participant| {
+            let team_i = team_user_ids
+                .iter()
+                .position(|user_ids| user_ids.contains(&participant.user_id))?;
+            let observation = game_state.get_observation(&participant.user_id).ok()?;
+            strategies[team_i]
+                .get_action(&observation, seed)
+                .map(|msg| (participant.user_id.clone(), msg))
+        });
+        let (user_id, msg) = action.ok_or("No user had anything to do")?;
+
+        game_state = handle_request(&game_state, &user_id, msg.clone())
+            .map_err(|err| format!("{msg:?} from user {user_id} was rejected: {err}"))?;
+        game_state
+            .check_invariants()
+            .map_err(|violations| format!("{msg:?} from user {user_id} broke {violations:?}"))?;
+    }
+
+    Err(format!(
+        "Game didn't end after {MAX_ACTIONS_PER_GAME} actions"
+    ))
+}
+
+/// Carries out a request the same way the server would
+fn handle_request(
+    game_state: &PrivateGameState,
+    user_id: &str,
+    msg: CTSMsg,
+) -> Result<PrivateGameState, String> {
+    match msg {
+        CTSMsg::CallGrandTichu(call_grand_tichu_request) => {
+            game_state.call_grand_tichu(&call_grand_tichu_request, user_id)
+        }
+        CTSMsg::CallSmallTichu => game_state.call_small_tichu(user_id),
+        CTSMsg::SubmitTrade(submit_trade) => game_state.submit_trade(user_id, &submit_trade),
+        CTSMsg::PlayCards {
+            cards,
+            wished_for_card_value,
+        } => game_state.play_cards(user_id, cards, wished_for_card_value),
+        CTSMsg::Pass => game_state.pass(user_id),
+        CTSMsg::GiveDragon {
+            user_id: recipient_user_id,
+        } => game_state.give_dragon(user_id, &recipient_user_id),
+        _ => return Err("Strategies can only make game requests".into()),
+    }
+    .map_err(|err| format!("{err:?}"))
+}
+
+fn get_team_user_ids(game_state: &PrivateGameState) -> Result<[[String; 2]; 2], String> {
+    if let PrivateGameStage::GrandTichu(grand_tichu_state) = &game_state.stage {
+        Ok(grand_tichu_state.teams.clone().map(|team| team.user_ids))
+    } else {
+        Err("Game should be in the Grand Tichu stage once it starts".into())
+    }
+}
+
+/// Adds a finished game to the stats of the strategies that played it
+fn record_game(
+    stats: &mut [StrategyStats; 2],
+    strategy_is: [usize; 2],
+    game_state: &PrivateGameState,
+) -> Result<(), String> {
+    let teams = if let PrivateGameStage::Score(score) = &game_state.stage {
+        score.teams.clone()
+    } else {
+        return Err("Game should be in the Score stage once it ends".into());
+    };
+
+    let winning_team_i = if teams[0].score > teams[1].score {
+        0
+    } else {
+        1
+    };
+    stats[strategy_is[winning_team_i]].games_won += 1;
+
+    for round_summary in game_state.round_summaries.iter() {
+        for (team_i, team) in teams.iter().enumerate() {
+            let strategy_stats = &mut stats[strategy_is[team_i]];
+            strategy_stats.rounds_played += 1;
+            strategy_stats.round_points += round_summary.teams[team_i].round_total() as i64;
+            if round_summary.double_victory_team_id.as_ref() == Some(&team.id) {
+                strategy_stats.double_victories += 1;
+            }
+            strategy_stats.add_tichus(&round_summary.small_tichus, &team.user_ids, false);
+            strategy_stats.add_tichus(&round_summary.grand_tichus, &team.user_ids, true);
+        }
+    }
+
+    for event in game_state.events.iter() {
+        if let GameEvent::CardsPlayed { user_id, cards, .. } = event {
+            let is_bomb =
+                get_card_combination(None, cards, user_id).is_some_and(|combo| combo.is_bomb());
+            let team_i = teams
+                .iter()
+                .position(|team| team.user_ids.contains(user_id));
+            if let (true, Some(team_i)) = (is_bomb, team_i) {
+                stats[strategy_is[team_i]].bombs += 1;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_report(names: &[String; 2], stats: &[StrategyStats; 2], num_games: u64) {
+    let percent = |numerator: u64, denominator: u64| {
+        if denominator == 0 {
+            "-".to_string()
+        } else {
+            format!("{:.1}%", numerator as f64 * 100.0 / denominator as f64)
+        }
+    };
+    let per = |numerator: i64, denominator: u64| {
+        if denominator == 0 {
+            "-".to_string()
+        } else {
+            format!("{:.2}", numerator as f64 / denominator as f64)
+        }
+    };
+    let lines: [(&str, [String; 2]); 6] = [
+        (
+            "Win rate",
+            stats
+                .each_ref()
+                .map(|stats| percent(stats.games_won, num_games)),
+        ),
+        (
+            "Average round score",
+            stats
+                .each_ref()
+                .map(|stats| per(stats.round_points, stats.rounds_played)),
+        ),
+        (
+            "Small Tichu success",
+            stats.each_ref().map(|stats| {
+                format!(
+                    "{}/{} ({})",
+                    stats.small_tichus_achieved,
+                    stats.small_tichus_called,
+                    percent(stats.small_tichus_achieved, stats.small_tichus_called)
+                )
+            }),
+        ),
+        (
+            "Grand Tichu success",
+            stats.each_ref().map(|stats| {
+                format!(
+                    "{}/{} ({})",
+                    stats.grand_tichus_achieved,
+                    stats.grand_tichus_called,
+                    percent(stats.grand_tichus_achieved, stats.grand_tichus_called)
+                )
+            }),
+        ),
+        (
+            "Double victories per game",
+            stats
+                .each_ref()
+                .map(|stats| per(stats.double_victories as i64, num_games)),
+        ),
+        (
+            "Bombs per game",
+            stats
+                .each_ref()
+                .map(|stats| per(stats.bombs as i64, num_games)),
+        ),
+    ];
+
+    println!("{:<28}{:>20}{:>20}", "", names[0], names[1]);
+    for (label, values) in lines {
+        println!("{:<28}{:>20}{:>20}", label, values[0], values[1]);
+    }
+}
+
+fn main() {
+    let mut num_games = DEFAULT_NUM_GAMES;
+    let mut seed = DEFAULT_SEED;
+    let mut names = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let parsed = match arg.as_str() {
+            "--games" => args
+                .next()
+                .and_then(|n| n.parse().ok())
+                .map(|n| num_games = n),
+            "--seed" => args.next().and_then(|n| n.parse().ok()).map(|n| seed = n),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ => {
+                names.push(arg);
+                Some(())
+            }
+        };
+        if parsed.is_none() {
+            eprintln!("{USAGE}");
+            process::exit(2);
+        }
+    }
+
+    let names: [String; 2] = names.try_into().unwrap_or_else(|_| {
+        eprintln!("{USAGE}");
+        process::exit(2);
+    });
+    let strategies = names.each_ref().map(|name| {
+        parse_strategy(name).unwrap_or_else(|err| {
+            eprintln!("{err}\n\n{USAGE}");
+            process::exit(2);
+        })
+    });
+
+    let mut stats = [StrategyStats::default(), StrategyStats::default()];
+    let mut num_failed_games = 0;
+    for game_i in 0..num_games {
+        let game_seed = seed.wrapping_add(game_i);
+
+        // strategies switch teams every game
+        let strategy_is = if game_i % 2 == 0 { [0, 1] } else { [1, 0] };
+        let teams = strategy_is.map(|strategy_i| strategies[strategy_i].as_ref());
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let game_state = play_game(teams, game_seed)?;
+            if PrivateGameState::replay(&game_state.events).as_ref() != Ok(&game_state) {
+                return Err("Replaying the game's events produced a different game".to_string());
+            }
+            record_game(&mut stats, strategy_is, &game_state)
+        }))
+        .unwrap_or_else(|_| Err("Engine panicked".into()));
+
+        if let Err(err) = result {
+            eprintln!("Game {game_i} (seed {game_seed}) failed: {err}");
+            num_failed_games += 1;
+        }
+    }
+
+    println!("Played {num_games} games starting from seed {seed}\n");
+    print_report(&names, &stats, num_games);
+
+    if num_failed_games > 0 {
+        eprintln!("\n{num_failed_games} of {num_games} games failed");
+        process::exit(1);
+    }
+}
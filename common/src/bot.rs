@@ -0,0 +1,240 @@
+use crate::{
+    get_ismcts_action, get_user_can_pass, legal_plays, CTSMsg, CallGrandTichuRequest, Card,
+    CardSuit, CardTrade, Observation, PublicGameStage, SearchBudget, TichuCallStatus,
+    ValidCardCombo,
+};
+use rand::{prelude::SliceRandom, rngs::SmallRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// How well a bot plays.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum BotDifficulty {
+    /// Plays a random legal move
+    Easy,
+
+    /// Gets rid of its lowest cards first, saves its bombs, and never overtakes its teammate
+    Normal,
+
+    /// Trades like Normal, but searches for the best play by simulating the rest of the round
+    Hard,
+}
+
+/// How many rounds a Hard bot simulates before each play
+const HARD_BOT_SEARCH_ITERATIONS: u32 = 1_000;
+
+/// The request a bot sends in response to what it has seen of the game.
+///
+/// `observation` is the bot's own view of the game (i.e. the bot is the `current_user`), so bots
+/// never see other users' cards. Returns None when the bot has nothing to do.
+/// The same `seed` always produces the same request.
+pub fn get_bot_action(
+    observation: &Observation,
+    bot_difficulty: &BotDifficulty,
+    seed: u64,
+) -> Option<CTSMsg> {
+    let game_state = &observation.game_state;
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let user_id = &game_state.current_user.user_id;
+
+    match &game_state.stage {
+        PublicGameStage::GrandTichu(grand_tichu_state) => {
+            let grand_tichu = grand_tichu_state
+                .grand_tichus
+                .iter()
+                .find(|grand_tichu| grand_tichu.user_id == *user_id)?;
+            if grand_tichu.tichu_call_status == TichuCallStatus::Undecided {
+                Some(CTSMsg::CallGrandTichu(CallGrandTichuRequest::Decline))
+            } else {
+                None
+            }
+        }
+        PublicGameStage::Trade(trade_state) => {
+            if trade_state.submitted_trades.contains(user_id) {
+                return None;
+            }
+
+            let other_players = game_state.get_other_players()?;
+            let mut hand = game_state.current_user.hand.clone();
+            match bot_difficulty {
+                BotDifficulty::Easy => hand.shuffle(&mut rng),
+                // lowest cards to opponents, highest card to teammate
+                BotDifficulty::Normal | BotDifficulty::Hard => hand.sort_by_key(get_card_rank),
+            }
+            let teammate_card = hand.pop()?;
+            let opponent_1_card = hand.first()?.clone();
+            let opponent_2_card = hand.get(1)?.clone();
+
+            Some(CTSMsg::SubmitTrade([
+                CardTrade {
+                    from_user_id: user_id.clone(),
+                    card: opponent_1_card,
+                    to_user_id: other_players.opponent_1.user_id,
+                },
+                CardTrade {
+                    from_user_id: user_id.clone(),
+                    card: teammate_card,
+                    to_user_id: other_players.teammate.user_id,
+                },
+                CardTrade {
+                    from_user_id: user_id.clone(),
+                    card: opponent_2_card,
+                    to_user_id: other_players.opponent_2.user_id,
+                },
+            ]))
+        }
+        PublicGameStage::Play(_) if *bot_difficulty == BotDifficulty::Hard => get_ismcts_action(
+            observation,
+            &SearchBudget::Iterations(HARD_BOT_SEARCH_ITERATIONS),
+            seed,
+        ),
+        PublicGameStage::Play(play_state) => {
+            let team_categories = game_state.get_immutable_team_categories()?;
+
+            // give the Dragon trick to an opponent who is still in play, if there is one
+            if let Some(dragon_winner_user_id) = &play_state.dragon_winner_user_id {
+                if dragon_winner_user_id != user_id {
+                    return None;
+                }
+                let opponent_user_ids = &team_categories.opposing_team.user_ids;
+                let recipient_user_id = opponent_user_ids
+                    .iter()
+                    .find(|user_id| play_state.users_in_play.contains(user_id))
+                    .unwrap_or(&opponent_user_ids[0]);
+                return Some(CTSMsg::GiveDragon {
+                    user_id: recipient_user_id.clone(),
+                });
+            }
+
+            if play_state.turn_user_id != *user_id {
+                return None;
+            }
+
+            let hand = &game_state.current_user.hand;
+            let table_top = play_state.table.last();
+            let wished_for_card_value = play_state.wished_for_card_value.as_ref();
+            let plays = legal_plays(hand, table_top, wished_for_card_value);
+            let can_pass = get_user_can_pass(user_id, table_top, hand, wished_for_card_value);
+
+            let play = match bot_difficulty {
+                BotDifficulty::Easy => {
+                    // passing is just one more option
+                    let number_of_options = plays.len() + usize::from(can_pass);
+                    if number_of_options == 0 {
+                        None
+                    } else {
+                        plays.get(rng.gen_range(0..number_of_options))
+                    }
+                }
+                BotDifficulty::Normal | BotDifficulty::Hard => {
+                    let teammate_is_winning = table_top.is_some_and(|table_top| {
+                        team_categories
+                            .current_team
+                            .user_ids
+                            .contains(table_top.user_id())
+                    });
+                    if can_pass && teammate_is_winning {
+                        None
+                    } else if table_top.is_none() {
+                        // lead with the lowest cards, getting rid of as many as possible at once
+                        plays.iter().min_by_key(|combo| {
+                            (
+                                combo.is_bomb(),
+                                get_lowest_card_rank(combo),
+                                Reverse(combo.cards().len()),
+                            )
+                        })
+                    } else {
+                        // beat the table as cheaply as possible, and only bomb if forced to
+                        plays
+                            .iter()
+                            .filter(|combo| !can_pass || !combo.is_bomb())
+                            .min_by_key(|combo| (combo.is_bomb(), get_lowest_card_rank(combo)))
+                    }
+                }
+            };
+
+            match play {
+                Some(play) => Some(CTSMsg::PlayCards {
+                    cards: play.cards().clone(),
+                    wished_for_card_value: None,
+                }),
+                None if can_pass => Some(CTSMsg::Pass),
+                // a user whose turn it is can always play or pass, so this only happens if the game state is broken
+                None => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The request the server sends on behalf of a user who ran out of time to act.
+///
+/// Declines Grand Tichu, trades random cards, gives the Dragon trick to an opponent,
+/// and passes if possible. Otherwise (e.g. when leading, or when a wish must be fulfilled)
+/// makes the smallest legal play. Returns None when the game isn't waiting on the user.
+pub fn get_timeout_action(observation: &Observation, seed: u64) -> Option<CTSMsg> {
+    let game_state = &observation.game_state;
+    let play_state = match &game_state.stage {
+        PublicGameStage::Play(play_state) if play_state.dragon_winner_user_id.is_none() => {
+            play_state
+        }
+        // an Easy bot makes every one of these decisions the same way
+        _ => return get_bot_action(observation, &BotDifficulty::Easy, seed),
+    };
+
+    if play_state.turn_user_id != game_state.current_user.user_id {
+        return None;
+    }
+
+    let hand = &game_state.current_user.hand;
+    let table_top = play_state.table.last();
+    let wished_for_card_value = play_state.wished_for_card_value.as_ref();
+    if get_user_can_pass(
+        &game_state.current_user.user_id,
+        table_top,
+        hand,
+        wished_for_card_value,
+    ) {
+        return Some(CTSMsg::Pass);
+    }
+
+    let play = legal_plays(hand, table_top, wished_for_card_value)
+        .into_iter()
+        .min_by_key(|combo| {
+            (
+                combo.is_bomb(),
+                get_lowest_card_rank(&combo),
+                combo.cards().len(),
+            )
+        });
+    Some(match play {
+        Some(play) => CTSMsg::PlayCards {
+            cards: play.cards().clone(),
+            wished_for_card_value: None,
+        },
+        None => CTSMsg::Pass,
+    })
+}
+
+/// Ranks cards from least to most useful to hold on to
+pub(crate) fn get_card_rank(card: &Card) -> u8 {
+    match card.suit {
+        CardSuit::Dog => 0,
+        CardSuit::MahJong => 1,
+        CardSuit::Phoenix => 15,
+        CardSuit::Dragon => 16,
+        _ => card.value.0,
+    }
+}
+
+/// The lowest-ranked card in the combo, ignoring the Phoenix when it completes a larger combo
+fn get_lowest_card_rank(combo: &&ValidCardCombo) -> u8 {
+    let cards = combo.cards();
+    cards
+        .iter()
+        .filter(|card| cards.len() == 1 || card.suit != CardSuit::Phoenix)
+        .map(get_card_rank)
+        .min()
+        .unwrap_or_default()
+}
@@ -0,0 +1,285 @@
+use crate::{Card, CardSuit, CardValue, CARD_VALUE_MAX, CARD_VALUE_MIN, TOTAL_CARDS};
+use std::ops::{BitAnd, BitOr, Not, Sub};
+
+/// Standard suits in the order their cards are laid out in a CardSet
+const STANDARD_SUITS: [CardSuit; 4] = [
+    CardSuit::Sword,
+    CardSuit::Jade,
+    CardSuit::Pagoda,
+    CardSuit::Star,
+];
+
+/// Special cards come after the 52 standard cards
+const SPECIAL_SUITS: [CardSuit; 4] = [
+    CardSuit::MahJong,
+    CardSuit::Dog,
+    CardSuit::Phoenix,
+    CardSuit::Dragon,
+];
+
+const NUM_STANDARD_CARDS: u32 = 52;
+
+/// Every card of a single value: one bit per standard suit
+const VALUE_MASK: u64 = 0b1111;
+
+/// Every card of a single suit: one bit per value
+const SUIT_MASK: u64 = 0x0001_1111_1111_1111;
+
+/// A set of cards, stored as one bit per card of the deck.
+///
+/// Standard cards are grouped by value, from 2 to Ace, with one bit per suit,
+/// followed by the Mah Jong, Dog, Phoenix, and Dragon. This makes set operations
+/// and counting cards of the same value a single instruction each,
+/// which matters when bots evaluate huge numbers of positions.
+///
+/// Cards are still sent over the wire as `Card`s: a CardSet only exists in memory.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub struct CardSet(pub u64);
+
+impl CardSet {
+    pub const EMPTY: CardSet = CardSet(0);
+
+    /// Every card in the deck
+    pub fn full() -> Self {
+        CardSet((1 << TOTAL_CARDS) - 1)
+    }
+
+    /// Every standard card of the given value
+    pub fn of_value(value: &CardValue) -> Self {
+        if value.0 < CARD_VALUE_MIN || value.0 > CARD_VALUE_MAX {
+            return CardSet::EMPTY;
+        }
+        CardSet(VALUE_MASK << Self::get_value_shift(value.0))
+    }
+
+    /// Every card of the given suit: either all 13 standard cards, or the special card
+    pub fn of_suit(suit: &CardSuit) -> Self {
+        if let Some(i) = STANDARD_SUITS
+            .iter()
+            .position(|standard_suit| standard_suit == suit)
+        {
+            CardSet(SUIT_MASK << i)
+        } else {
+            let i = SPECIAL_SUITS
+                .iter()
+                .position(|special_suit| special_suit == suit)
+                .expect("Every suit should be either standard or special");
+            CardSet(1 << (NUM_STANDARD_CARDS as usize + i))
+        }
+    }
+
+    /// The Mah Jong, Dog, Phoenix, and Dragon
+    pub fn special_cards() -> Self {
+        CardSet::full() - CardSet((1 << NUM_STANDARD_CARDS) - 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Cards outside of the deck are never in a CardSet
+    pub fn contains(&self, card: &Card) -> bool {
+        Self::get_index(card).is_some_and(|i| self.0 & (1 << i) != 0)
+    }
+
+    /// Adds a card, returning whether it was newly added
+    ///
+    /// Panics if the card isn't one of the 56 cards of the deck:
+    /// validate untrusted cards before adding them.
+    pub fn insert(&mut self, card: &Card) -> bool {
+        let i = Self::get_index(card)
+            .unwrap_or_else(|| panic!("Only cards from the deck fit in a CardSet: {:?}", card));
+        let is_new = self.0 & (1 << i) == 0;
+        self.0 |= 1 << i;
+        is_new
+    }
+
+    /// Removes a card, returning whether it was in the set
+    pub fn remove(&mut self, card: &Card) -> bool {
+        let is_present = self.contains(card);
+        if let Some(i) = Self::get_index(card) {
+            self.0 &= !(1 << i);
+        }
+        is_present
+    }
+
+    pub fn is_subset(&self, other: &CardSet) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    /// How many standard cards of the given value are in the set
+    pub fn count_of_value(&self, value: &CardValue) -> usize {
+        (*self & CardSet::of_value(value)).len()
+    }
+
+    /// Whether every card is a standard card of the same suit
+    pub fn is_one_suit(&self) -> bool {
+        (0..STANDARD_SUITS.len()).any(|i| self.is_subset(&CardSet(SUIT_MASK << i)))
+            && !self.is_empty()
+    }
+
+    /// How many standard cards of each value are in the set, indexed by value
+    pub fn get_value_counts(&self) -> [usize; CARD_VALUE_MAX as usize + 1] {
+        let mut value_counts = [0; CARD_VALUE_MAX as usize + 1];
+        for value in CARD_VALUE_MIN..=CARD_VALUE_MAX {
+            value_counts[value as usize] =
+                ((self.0 >> Self::get_value_shift(value)) & VALUE_MASK).count_ones() as usize;
+        }
+        value_counts
+    }
+
+    /// Every subset with exactly `size` cards: only meant for small sets
+    pub fn combinations(&self, size: usize) -> Vec<CardSet> {
+        if size == 0 {
+            return vec![CardSet::EMPTY];
+        }
+        if self.len() < size {
+            return Vec::new();
+        }
+
+        // either the lowest card is part of the subset or it isn't
+        let lowest_card = CardSet(self.0 & self.0.wrapping_neg());
+        let rest = *self - lowest_card;
+        let mut combinations: Vec<CardSet> = rest
+            .combinations(size - 1)
+            .into_iter()
+            .map(|combination| combination | lowest_card)
+            .collect();
+        combinations.append(&mut rest.combinations(size));
+        combinations
+    }
+
+    /// Every card, from 2 to Ace (ordered by suit within a value), followed by special cards
+    pub fn iter(&self) -> impl Iterator<Item = Card> {
+        let bits = self.0;
+        (0..TOTAL_CARDS as u32)
+            .filter(move |i| bits & (1 << i) != 0)
+            .map(Self::get_card)
+    }
+
+    pub fn to_cards(&self) -> Vec<Card> {
+        self.iter().collect()
+    }
+
+    /// Where a card lives in the set, if it is a card from the deck
+    fn get_index(card: &Card) -> Option<u32> {
+        if let Some(i) = SPECIAL_SUITS
+            .iter()
+            .position(|special_suit| *special_suit == card.suit)
+        {
+            return card
+                .value
+                .is_noop()
+                .then_some(NUM_STANDARD_CARDS + i as u32);
+        }
+
+        let suit_i = STANDARD_SUITS
+            .iter()
+            .position(|standard_suit| *standard_suit == card.suit)? as u32;
+        if card.value.0 < CARD_VALUE_MIN || card.value.0 > CARD_VALUE_MAX {
+            return None;
+        }
+        Some(Self::get_value_shift(card.value.0) + suit_i)
+    }
+
+    fn get_card(i: u32) -> Card {
+        if i >= NUM_STANDARD_CARDS {
+            Card {
+                suit: SPECIAL_SUITS[(i - NUM_STANDARD_CARDS) as usize].clone(),
+                value: CardValue::noop(),
+            }
+        } else {
+            Card {
+                suit: STANDARD_SUITS[(i % 4) as usize].clone(),
+                value: CardValue((i / 4) as u8 + CARD_VALUE_MIN),
+            }
+        }
+    }
+
+    fn get_value_shift(value: u8) -> u32 {
+        (value - CARD_VALUE_MIN) as u32 * 4
+    }
+}
+
+impl From<&Card> for CardSet {
+    fn from(card: &Card) -> Self {
+        let mut card_set = CardSet::EMPTY;
+        card_set.insert(card);
+        card_set
+    }
+}
+
+impl From<&[Card]> for CardSet {
+    fn from(cards: &[Card]) -> Self {
+        cards.iter().collect()
+    }
+}
+
+impl From<&Vec<Card>> for CardSet {
+    fn from(cards: &Vec<Card>) -> Self {
+        cards.iter().collect()
+    }
+}
+
+impl From<CardSet> for Vec<Card> {
+    fn from(card_set: CardSet) -> Self {
+        card_set.to_cards()
+    }
+}
+
+impl<'a> FromIterator<&'a Card> for CardSet {
+    fn from_iter<T: IntoIterator<Item = &'a Card>>(cards: T) -> Self {
+        let mut card_set = CardSet::EMPTY;
+        for card in cards {
+            card_set.insert(card);
+        }
+        card_set
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<T: IntoIterator<Item = Card>>(cards: T) -> Self {
+        let mut card_set = CardSet::EMPTY;
+        for card in cards {
+            card_set.insert(&card);
+        }
+        card_set
+    }
+}
+
+impl BitOr for CardSet {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        CardSet(self.0 | other.0)
+    }
+}
+
+impl BitAnd for CardSet {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        CardSet(self.0 & other.0)
+    }
+}
+
+impl Sub for CardSet {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        CardSet(self.0 & !other.0)
+    }
+}
+
+impl Not for CardSet {
+    type Output = Self;
+
+    /// Every card of the deck that isn't in the set
+    fn not(self) -> Self {
+        CardSet::full() - self
+    }
+}
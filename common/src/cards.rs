@@ -1,6 +1,7 @@
 #[cfg(feature = "client")]
 use js_sys::Math::random;
-use rand::{prelude::SliceRandom, rngs::SmallRng, SeedableRng};
+use rand::{prelude::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, fmt::Display};
 
@@ -316,18 +317,33 @@ impl Default for Deck {
 
 // only runs on client: uses Math.random to seed random number
 #[cfg(feature = "client")]
-fn get_random_u64() -> u64 {
-    (random() * 1_000_000 as f64) as u64
+pub fn get_random_seed() -> u64 {
+    (random() * u64::MAX as f64) as u64
 }
 
-// only runs on server: uses SystemTime to seed random number
-#[cfg(not(feature = "client"))]
-fn get_random_u64() -> u64 {
+// only runs on server: uses the operating system's cryptographically secure random number generator
+#[cfg(feature = "server")]
+pub fn get_random_seed() -> u64 {
+    use rand::{rngs::OsRng, RngCore};
+    OsRng.next_u64()
+}
+
+/// Milliseconds since the Unix epoch
+// only runs on client: uses the browser's clock
+#[cfg(feature = "client")]
+pub fn get_current_time_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+/// Milliseconds since the Unix epoch
+// only runs on server: uses the system clock
+#[cfg(feature = "server")]
+pub fn get_current_time_ms() -> f64 {
     use std::time::{SystemTime, UNIX_EPOCH};
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
-        .as_millis() as u64
+        .map(|duration| duration.as_secs_f64() * 1000.0)
+        .unwrap_or_default()
 }
 
 impl Deck {
@@ -336,9 +352,15 @@ impl Deck {
         Deck::default()
     }
 
-    pub fn shuffle(mut self) -> Self {
-        let rand_num = get_random_u64();
-        self.0.shuffle(&mut SmallRng::seed_from_u64(rand_num));
+    /// Shuffles the deck with a fresh random seed
+    pub fn shuffle(self) -> Self {
+        self.shuffle_with_seed(get_random_seed())
+    }
+
+    /// Shuffles the deck so that the same seed always produces the same order,
+    /// no matter which platform it runs on.
+    pub fn shuffle_with_seed(mut self, seed: u64) -> Self {
+        self.0.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
         self
     }
 
@@ -0,0 +1,211 @@
+use crate::Card;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Why a request to change the game state was rejected.
+///
+/// Shared between the server and the client so that failures can be matched on
+/// rather than parsed out of log messages.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum TichuError {
+    /// The message received from the client could not be deserialized
+    InvalidMessage,
+
+    /// The user's websocket connection could not be found on the server
+    ConnectionNotFound,
+
+    /// The request is marked as coming from a different user than the one connected
+    UserIdMismatch,
+
+    /// The user is not associated with any game
+    NotInGame,
+
+    /// The user is already associated with a game
+    AlreadyInGame,
+
+    /// The game the user is associated with no longer exists on the server
+    GameNotFound,
+
+    /// No game exists with the given game code
+    GameCodeNotFound,
+
+    /// The display name is empty or too long
+    InvalidDisplayName,
+
+    /// The game code is empty or malformed
+    InvalidGameCode,
+
+    /// The game already has 4 participants or is no longer accepting new ones
+    GameFull,
+
+    /// The request can't be handled in the game's current stage
+    WrongStage,
+
+    /// Only the owner of the game can make this request
+    NotOwner,
+
+    /// The user could not be found in the game
+    UserNotFound,
+
+    /// There are fewer than 4 participants in the game
+    NotEnoughParticipants,
+
+    /// The user is already on the team they're trying to move to
+    AlreadyOnTeam,
+
+    /// The user is not on the team they're trying to change
+    NotOnTeam,
+
+    /// Both teams must have exactly 2 users before the game can start
+    TeamsNotReady,
+
+    /// The user has already called or declined this Tichu
+    TichuAlreadyDecided,
+
+    /// The user does not hold this card
+    CardNotInHand(Card),
+
+    /// The same card was referenced more than once in a single action
+    DuplicateCard(Card),
+
+    /// A submitted trade is marked as coming from a different user
+    TradeNotFromUser,
+
+    /// A user can't trade a card to themself
+    TradeToSelf,
+
+    /// The user being traded to is not on either team
+    TradeRecipientNotFound,
+
+    /// The user has already submitted a trade this round
+    TradeAlreadySubmitted,
+
+    /// A trade must send one card to each of the other three users
+    DuplicateTradeRecipient,
+
+    /// The cards played do not form a valid combination
+    InvalidCombo,
+
+    /// It is not the user's turn (and the play is not a bomb)
+    NotYourTurn,
+
+    /// The combination played does not beat the combination on the table
+    ComboDoesNotBeatTable,
+
+    /// The user leading a trick has to play something
+    CannotPassOnLead,
+
+    /// The user can play the wished-for card, so they must
+    MustFulfillWish,
+
+    /// Nobody can act until the winner of the Dragon trick gives it away
+    DragonNotGivenAway,
+
+    /// The user did not win a trick with the Dragon
+    NotDragonWinner,
+
+    /// The Dragon trick can only be given to an opponent
+    RecipientNotOpponent,
+
+    /// The house rules would make the game impossible to play or to win
+    InvalidRules,
+
+    /// Only an admin can make this request
+    NotAdmin,
+
+    /// The hands an admin asked for can't be dealt from a single deck
+    InvalidHand,
+
+    /// The server is shutting down, so it isn't starting any new games
+    ServerShuttingDown,
+
+    /// The game state is inconsistent with itself. This should never happen.
+    Internal(String),
+}
+
+impl fmt::Display for TichuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TichuError::InvalidMessage => write!(f, "the message could not be read"),
+            TichuError::ConnectionNotFound => {
+                write!(f, "the user's connection could not be found")
+            }
+            TichuError::NotInGame => write!(f, "the user is not in a game"),
+            TichuError::AlreadyInGame => write!(f, "the user is already in a game"),
+            TichuError::GameNotFound => write!(f, "the game could not be found"),
+            TichuError::GameCodeNotFound => write!(f, "no game exists with that game code"),
+            TichuError::InvalidDisplayName => write!(f, "the display name is not valid"),
+            TichuError::InvalidGameCode => write!(f, "the game code is not valid"),
+            TichuError::GameFull => write!(f, "the game is not accepting any more participants"),
+            TichuError::WrongStage => write!(f, "the game is not in the right stage"),
+            TichuError::NotOwner => write!(f, "the user is not the owner of the game"),
+            TichuError::UserNotFound => write!(f, "the user could not be found in the game"),
+            TichuError::NotEnoughParticipants => {
+                write!(f, "there are not enough participants in the game")
+            }
+            TichuError::AlreadyOnTeam => write!(f, "the user is already on that team"),
+            TichuError::NotOnTeam => write!(f, "the user is not on that team"),
+            TichuError::TeamsNotReady => write!(f, "the teams are not ready to start the game"),
+            TichuError::TichuAlreadyDecided => {
+                write!(f, "the user has already called or declined that Tichu")
+            }
+            TichuError::CardNotInHand(card) => {
+                write!(f, "card {:?} is not in the user's hand", card)
+            }
+            TichuError::DuplicateCard(card) => {
+                write!(f, "card {:?} was included more than once", card)
+            }
+            TichuError::UserIdMismatch => {
+                write!(f, "the request is marked as coming from a different user")
+            }
+            TichuError::TradeNotFromUser => {
+                write!(f, "a trade is marked as coming from a different user")
+            }
+            TichuError::TradeToSelf => write!(f, "the user is trying to trade to themself"),
+            TichuError::TradeRecipientNotFound => {
+                write!(f, "the user being traded to was not found in the teams")
+            }
+            TichuError::TradeAlreadySubmitted => {
+                write!(f, "the user has already submitted a trade")
+            }
+            TichuError::DuplicateTradeRecipient => {
+                write!(f, "a trade must send one card to each other user")
+            }
+            TichuError::InvalidCombo => write!(f, "the cards are not a valid combination"),
+            TichuError::NotYourTurn => write!(f, "it is not the user's turn"),
+            TichuError::ComboDoesNotBeatTable => {
+                write!(
+                    f,
+                    "the combination does not beat the combination on the table"
+                )
+            }
+            TichuError::CannotPassOnLead => write!(f, "the user can't pass on the lead"),
+            TichuError::MustFulfillWish => {
+                write!(f, "the user can play the wished-for card but didn't")
+            }
+            TichuError::DragonNotGivenAway => {
+                write!(f, "the Dragon has not been given away yet")
+            }
+            TichuError::NotDragonWinner => {
+                write!(f, "the user did not win a trick with the Dragon")
+            }
+            TichuError::RecipientNotOpponent => {
+                write!(f, "the Dragon can only be given to an opponent")
+            }
+            TichuError::InvalidRules => write!(f, "the house rules are not valid"),
+            TichuError::NotAdmin => write!(f, "the user is not an admin"),
+            TichuError::InvalidHand => {
+                write!(f, "the hands can't be dealt from a single deck")
+            }
+            TichuError::ServerShuttingDown => {
+                write!(
+                    f,
+                    "the server is restarting, so no new games can be created"
+                )
+            }
+            TichuError::Internal(message) => write!(f, "game state error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for TichuError {}
@@ -0,0 +1,111 @@
+use crate::{
+    BotDifficulty, CallGrandTichuRequest, Card, CardValue, Deck, GameRules, SubmitTrade, TeamOption,
+};
+use serde::{Deserialize, Serialize};
+
+/// Everything that has happened to a game, in the order it happened.
+///
+/// Every accepted user action is recorded, as well as every effect the server carries out
+/// on its own (dealing, collecting tricks, scoring rounds). Anything random, such as ids
+/// and shuffled decks, is stored on the event, so applying the same events to a new game
+/// always produces exactly the same game state.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum GameEvent {
+    /// Always the first event in a game's log. `seed` determines how every round is dealt.
+    GameCreated {
+        game_id: String,
+        game_code: String,
+        owner_id: String,
+        owner_display_name: String,
+        seed: u64,
+    },
+    UserJoined {
+        user_id: String,
+        display_name: String,
+    },
+
+    /// The owner filled an empty seat with a bot
+    BotJoined {
+        requesting_user_id: String,
+        user_id: String,
+        display_name: String,
+        bot_difficulty: BotDifficulty,
+    },
+
+    /// The 4th user joined, so the participants are split into two teams
+    TeamsFormed {
+        team_ids: [String; 2],
+    },
+    UserLeft {
+        user_id: String,
+    },
+    OwnerReassigned,
+    MovedToTeam {
+        user_id: String,
+        team_option: TeamOption,
+    },
+    TeamRenamed {
+        user_id: String,
+        team_option: TeamOption,
+        team_name: String,
+    },
+
+    /// The owner changed the house rules before the game started
+    RulesUpdated {
+        user_id: String,
+        rules: GameRules,
+    },
+
+    /// The owner started the game. Cards are dealt separately.
+    GrandTichuStarted {
+        user_id: String,
+    },
+
+    /// The first cards are dealt to every user from the top of `deck` (9, unless the rules say otherwise).
+    /// The rest of the deck is dealt once every Grand Tichu has been decided.
+    CardsDealt {
+        deck: Deck,
+    },
+    GrandTichuCalled {
+        user_id: String,
+        call_grand_tichu_request: CallGrandTichuRequest,
+    },
+    SmallTichuCalled {
+        user_id: String,
+    },
+    TradeSubmitted {
+        user_id: String,
+        submit_trade: SubmitTrade,
+    },
+    CardsPlayed {
+        user_id: String,
+        cards: Vec<Card>,
+        wished_for_card_value: Option<CardValue>,
+    },
+    Passed {
+        user_id: String,
+    },
+    DragonGiven {
+        user_id: String,
+        recipient_user_id: String,
+    },
+
+    /// Every combo on the table is moved into the receiving user's tricks.
+    /// This is the user who won the trick, unless they won it with the Dragon and gave it away.
+    TrickWon {
+        receiving_user_id: String,
+    },
+
+    /// The round is scored, and either a new round begins or the game ends
+    RoundOver,
+
+    /// Admin event: skip straight to the Play stage, dealing 14 cards to every user from `deck`
+    __AdminSkippedToPlay {
+        deck: Deck,
+    },
+
+    /// Admin event: both teams' scores are overwritten, in team order
+    __AdminScoresSet {
+        scores: [i32; 2],
+    },
+}
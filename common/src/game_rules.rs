@@ -0,0 +1,107 @@
+use crate::{
+    TichuError, GRAND_TICHU_VALUE, MAX_CARDS_IN_HAND, NUM_CARDS_BEFORE_GRAND_TICHU,
+    SMALL_TICHU_VALUE,
+};
+use serde::{Deserialize, Serialize};
+
+/// Points a team scores when both of its members go out before either opponent
+pub const DOUBLE_VICTORY_VALUE: i32 = 200;
+
+/// A team wins once its score is higher than this at the end of a round
+pub const DEFAULT_TARGET_SCORE: i32 = 1000;
+
+/// How long a game lasts.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum GameEnd {
+    /// Game ends once a team's score is higher than this at the end of a round
+    TargetScore(i32),
+
+    /// Game ends after this many rounds
+    Rounds(usize),
+}
+
+/// House rules a game is played with.
+///
+/// Chosen by the owner before the game starts, and fixed from then on.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct GameRules {
+    pub game_end: GameEnd,
+
+    /// If false, every card is dealt at once and nobody can call Grand Tichu
+    pub grand_tichu_enabled: bool,
+
+    /// What a Small Tichu call is worth in this game. Defaults to [`SMALL_TICHU_VALUE`].
+    pub small_tichu_value: i32,
+
+    /// What a Grand Tichu call is worth in this game. Defaults to [`GRAND_TICHU_VALUE`].
+    pub grand_tichu_value: i32,
+
+    /// What a double victory is worth in this game, replacing the round's card points.
+    /// Defaults to [`DOUBLE_VICTORY_VALUE`].
+    pub double_victory_value: i32,
+
+    /// How many cards each user sees before deciding on Grand Tichu.
+    /// Defaults to [`NUM_CARDS_BEFORE_GRAND_TICHU`].
+    pub num_cards_before_grand_tichu: usize,
+
+    /// How long users have to act before the server acts for them. None means no limit.
+    pub turn_time_limit_secs: Option<u64>,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        GameRules {
+            game_end: GameEnd::TargetScore(DEFAULT_TARGET_SCORE),
+            grand_tichu_enabled: true,
+            small_tichu_value: SMALL_TICHU_VALUE,
+            grand_tichu_value: GRAND_TICHU_VALUE,
+            double_victory_value: DOUBLE_VICTORY_VALUE,
+            num_cards_before_grand_tichu: NUM_CARDS_BEFORE_GRAND_TICHU,
+            turn_time_limit_secs: None,
+        }
+    }
+}
+
+impl GameRules {
+    /// Rules must leave every game winnable, deal at least 1 card at a time,
+    /// and leave users some time to act
+    pub fn validate(&self) -> Result<(), TichuError> {
+        let game_end_is_valid = match self.game_end {
+            GameEnd::TargetScore(target_score) => target_score > 0,
+            GameEnd::Rounds(rounds) => rounds > 0,
+        };
+        let values_are_valid = self.small_tichu_value >= 0
+            && self.grand_tichu_value >= 0
+            && self.double_victory_value >= 0;
+        let deal_is_valid = (1..MAX_CARDS_IN_HAND).contains(&self.num_cards_before_grand_tichu);
+        let turn_time_limit_is_valid = self.turn_time_limit_secs != Some(0);
+
+        if game_end_is_valid && values_are_valid && deal_is_valid && turn_time_limit_is_valid {
+            Ok(())
+        } else {
+            Err(TichuError::InvalidRules)
+        }
+    }
+
+    /// How many cards each user is dealt at the start of a round,
+    /// before anyone has decided on Grand Tichu
+    pub fn get_num_cards_first_dealt(&self) -> usize {
+        if self.grand_tichu_enabled {
+            self.num_cards_before_grand_tichu
+        } else {
+            MAX_CARDS_IN_HAND
+        }
+    }
+
+    /// Whether the game is over after `rounds_played` rounds with the given team scores.
+    ///
+    /// A game never ends in a tie: rounds are played until one team is ahead.
+    pub fn get_is_game_over(&self, rounds_played: usize, scores: [i32; 2]) -> bool {
+        let is_tied = scores[0] == scores[1];
+        let has_reached_end = match self.game_end {
+            GameEnd::TargetScore(target_score) => scores.iter().any(|score| *score > target_score),
+            GameEnd::Rounds(rounds) => rounds_played >= rounds,
+        };
+        has_reached_end && !is_tied
+    }
+}
@@ -14,7 +14,7 @@ pub enum PrivateGameStage {
     GrandTichu(Box<PrivateGrandTichu>),
     Trade(Box<PrivateTrade>),
     Play(Box<PrivatePlay>),
-    Score(Score),
+    Score(Box<Score>),
 }
 
 /// Client state that holds all stage-specific state and is PUBLIC.
@@ -27,7 +27,7 @@ pub enum PublicGameStage {
     GrandTichu(Box<PublicGrandTichu>),
     Trade(Box<PublicTrade>),
     Play(Box<PublicPlay>),
-    Score(Score),
+    Score(Box<Score>),
 }
 
 impl From<PrivateGameStage> for PublicGameStage {
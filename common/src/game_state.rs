@@ -1,12 +1,15 @@
 use crate::{
-    get_card_combination, get_new_game_code, get_user_can_play_wished_for_card,
-    next_combo_beats_prev, sort_cards_for_hand, user::UserRole, CallGrandTichuRequest, Card,
-    CardSuit, CardValue, Deck, GetSmallTichu, ImmutableTeam, MutableTeam, OtherPlayers,
-    PassWithUserId, PrivateGameStage, PrivateGrandTichu, PrivatePlay, PrivateUser, PublicGameStage,
-    PublicUser, SubmitTrade, TeamCategories, TeamOption, TichuCallStatus,
-    UserIdWithTichuCallStatus, ValidCardCombo, DOG, DRAGON, MAH_JONG, MAX_CARDS_IN_HAND,
-    NUM_CARDS_AFTER_GRAND_TICHU, NUM_CARDS_BEFORE_GRAND_TICHU,
+    get_card_combination, get_combo_fulfills_wish, get_new_game_code, get_play_respects_wish,
+    get_random_seed, get_user_can_play_wished_for_card, next_combo_beats_prev, sort_cards_for_hand,
+    user::UserRole, validate_cards_in_hand, BotDifficulty, CallGrandTichuRequest, Card, CardSet,
+    CardSuit, CardValue, Deck, GameEvent, GameRules, GetSmallTichu, ImmutableTeam, MutableTeam,
+    Observation, OtherPlayers, PassWithUserId, PrivateGameStage, PrivateGrandTichu, PrivatePlay,
+    PrivateUser, PublicGameStage, PublicUser, RoundSummary, Score, SubmitTrade, TeamCategories,
+    TeamOption, TeamRoundScore, TichuCallStatus, TichuError, UserIdWithTichuCallStatus,
+    ValidCardCombo, DOG, DRAGON, MAH_JONG, MAX_CARDS_IN_HAND,
 };
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -22,6 +25,25 @@ pub struct PrivateGameState {
     pub owner_id: String,
     pub stage: PrivateGameStage,
     pub participants: Vec<PrivateUser>,
+
+    /// Every round's deck is shuffled with a seed derived from this one
+    pub seed: u64,
+
+    /// House rules the game is played with: can only change before the game starts
+    pub rules: GameRules,
+
+    /// Breakdown of every finished round, in the order they were played
+    pub round_summaries: Vec<RoundSummary>,
+
+    /// When the users the game is waiting on run out of time to act (in ms since the Unix epoch).
+    ///
+    /// Only set by the server's turn timer, when the rules have a turn time limit.
+    /// Every event clears it, since the game is then waiting on a new decision.
+    pub turn_deadline: Option<u64>,
+
+    /// Every event applied to this game so far, starting with `GameCreated`.
+    /// Replaying these reproduces this game state exactly.
+    pub events: Vec<GameEvent>,
     // active_player: String,
     // card_wished_for: Card,
     // discard: Vec<Card>,
@@ -35,79 +57,309 @@ impl PrivateGameState {
         owner_display_name: String,
         existing_game_codes: &HashMap<String, String>,
     ) -> PrivateGameState {
-        let owner_user = PrivateUser {
-            display_name: owner_display_name,
-            user_id: owner_id.clone(),
-            role: UserRole::Owner,
-            tricks: vec![],
-            hand: vec![],
-            has_played_first_card: false,
-        };
-        PrivateGameState {
+        PrivateGameState::new_with_seed(
+            owner_id,
+            owner_display_name,
+            existing_game_codes,
+            get_random_seed(),
+        )
+    }
+
+    /// Initializes a new, default PrivateGameState whose cards are always dealt the same way
+    pub fn new_with_seed(
+        owner_id: String,
+        owner_display_name: String,
+        existing_game_codes: &HashMap<String, String>,
+        seed: u64,
+    ) -> PrivateGameState {
+        let game_created = GameEvent::GameCreated {
             game_id: Uuid::new_v4().to_string(),
             game_code: get_new_game_code(existing_game_codes),
-            stage: PrivateGameStage::Lobby,
-            participants: vec![owner_user],
             owner_id,
+            owner_display_name,
+            seed,
+        };
+        PrivateGameState::replay(&[game_created])
+            .expect("A GameCreated event should always create a new game")
+    }
+
+    /// Rebuilds a game from its event log.
+    ///
+    /// The first event must be `GameCreated`, and every following event is applied in order.
+    pub fn replay(events: &[GameEvent]) -> Result<PrivateGameState, TichuError> {
+        let (game_created, rest) = events
+            .split_first()
+            .ok_or_else(|| TichuError::Internal("Can't replay an empty event log".into()))?;
+
+        let mut game_state = if let GameEvent::GameCreated {
+            game_id,
+            game_code,
+            owner_id,
+            owner_display_name,
+            seed,
+        } = game_created
+        {
+            let owner_user = PrivateUser {
+                display_name: owner_display_name.clone(),
+                user_id: owner_id.clone(),
+                role: UserRole::Owner,
+                tricks: vec![],
+                hand: vec![],
+                has_played_first_card: false,
+                bot_difficulty: None,
+            };
+            PrivateGameState {
+                game_id: game_id.clone(),
+                game_code: game_code.clone(),
+                stage: PrivateGameStage::Lobby,
+                participants: vec![owner_user],
+                owner_id: owner_id.clone(),
+                seed: *seed,
+                rules: GameRules::default(),
+                round_summaries: Vec::new(),
+                turn_deadline: None,
+                events: vec![game_created.clone()],
+            }
+        } else {
+            return Err(TichuError::Internal(
+                "The first event in a game's log must be GameCreated".into(),
+            ));
+        };
+
+        for event in rest {
+            game_state = game_state.apply(event)?;
         }
+
+        Ok(game_state)
+    }
+
+    /// Applies a single event to the game and records it in the event log.
+    ///
+    /// Does not carry out any effects that follow from the event (e.g. collecting a trick
+    /// after the last pass): those are recorded as events of their own.
+    pub fn apply(&self, event: &GameEvent) -> Result<Self, TichuError> {
+        let mut new_game_state = match event {
+            GameEvent::GameCreated { .. } => {
+                return Err(TichuError::Internal(
+                    "GameCreated can only be the first event in a game's log".into(),
+                ));
+            }
+            GameEvent::UserJoined {
+                user_id,
+                display_name,
+            } => self.apply_user_joined(user_id, display_name, None),
+            GameEvent::BotJoined {
+                requesting_user_id,
+                user_id,
+                display_name,
+                bot_difficulty,
+            } => self.apply_bot_joined(requesting_user_id, user_id, display_name, bot_difficulty),
+            GameEvent::TeamsFormed { team_ids } => self.apply_teams_formed(team_ids),
+            GameEvent::UserLeft { user_id } => self.apply_user_left(user_id),
+            GameEvent::OwnerReassigned => self.apply_owner_reassigned(),
+            GameEvent::MovedToTeam {
+                user_id,
+                team_option,
+            } => self.apply_moved_to_team(team_option, user_id),
+            GameEvent::TeamRenamed {
+                user_id,
+                team_option,
+                team_name,
+            } => self.apply_team_renamed(team_option, user_id, team_name),
+            GameEvent::RulesUpdated { user_id, rules } => self.apply_rules_updated(user_id, rules),
+            GameEvent::GrandTichuStarted { user_id } => self.apply_grand_tichu_started(user_id),
+            GameEvent::CardsDealt { deck } => self.apply_cards_dealt(deck),
+            GameEvent::GrandTichuCalled {
+                user_id,
+                call_grand_tichu_request,
+            } => self.apply_grand_tichu_called(call_grand_tichu_request, user_id),
+            GameEvent::SmallTichuCalled { user_id } => self.apply_small_tichu_called(user_id),
+            GameEvent::TradeSubmitted {
+                user_id,
+                submit_trade,
+            } => self.apply_trade_submitted(user_id, submit_trade),
+            GameEvent::CardsPlayed {
+                user_id,
+                cards,
+                wished_for_card_value,
+            } => self.apply_cards_played(user_id, cards.clone(), wished_for_card_value.clone()),
+            GameEvent::Passed { user_id } => self.apply_passed(user_id),
+            GameEvent::DragonGiven {
+                user_id,
+                recipient_user_id,
+            } => self.apply_dragon_given(user_id, recipient_user_id),
+            GameEvent::TrickWon { receiving_user_id } => self.apply_trick_won(receiving_user_id),
+            GameEvent::RoundOver => self.apply_round_over(),
+            GameEvent::__AdminSkippedToPlay { deck } => self.apply_admin_skipped_to_play(deck),
+            GameEvent::__AdminScoresSet { scores } => self.apply_admin_scores_set(scores),
+        }?;
+
+        // only transitions are checked, since tests often start from partial game states.
+        // a violation rejects the event rather than panicking, so a live game keeps running
+        #[cfg(debug_assertions)]
+        if self.check_invariants().is_ok() {
+            let violation_message = if let Err(violations) = new_game_state.check_invariants() {
+                Some(format!(
+                    "{:?} broke the game's invariants: {:?}",
+                    event, violations
+                ))
+            } else if let Err(violations) = self.check_event_invariants(event) {
+                Some(format!(
+                    "{:?} shouldn't have been accepted: {:?}",
+                    event, violations
+                ))
+            } else {
+                None
+            };
+            if let Some(violation_message) = violation_message {
+                eprintln!("{}", violation_message);
+                return Err(TichuError::Internal(violation_message));
+            }
+        }
+
+        new_game_state.turn_deadline = None;
+        new_game_state.events.push(event.clone());
+
+        Ok(new_game_state)
     }
 
     /// Adds a user to the game.
-    pub fn add_user(&self, user_id: String, display_name: String) -> Result<Self, String> {
-        let current_participants = self.participants.len();
-        let game_has_max_participants = current_participants == 4;
+    ///
+    /// Once the 4th user joins, they are split into teams.
+    pub fn add_user(&self, user_id: String, display_name: String) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::UserJoined {
+            user_id,
+            display_name,
+        })?
+        .form_teams_if_full()
+    }
+
+    /// Fills an empty seat with a bot. Only the owner can add bots.
+    ///
+    /// Once the 4th user joins, they are split into teams.
+    pub fn add_bot(
+        &self,
+        requesting_user_id: &str,
+        bot_difficulty: BotDifficulty,
+    ) -> Result<Self, TichuError> {
+        let number_of_bots = self
+            .participants
+            .iter()
+            .filter(|participant| participant.is_bot())
+            .count();
+        self.apply(&GameEvent::BotJoined {
+            requesting_user_id: requesting_user_id.to_string(),
+            user_id: Uuid::new_v4().to_string(),
+            display_name: format!("Bot {}", number_of_bots + 1),
+            bot_difficulty,
+        })?
+        .form_teams_if_full()
+    }
+
+    fn form_teams_if_full(self) -> Result<Self, TichuError> {
+        if self.participants.len() == 4 {
+            return self.apply(&GameEvent::TeamsFormed {
+                team_ids: [Uuid::new_v4().to_string(), Uuid::new_v4().to_string()],
+            });
+        }
+
+        Ok(self)
+    }
+
+    fn apply_user_joined(
+        &self,
+        user_id: &str,
+        display_name: &str,
+        bot_difficulty: Option<BotDifficulty>,
+    ) -> Result<Self, TichuError> {
+        let game_has_max_participants = self.participants.len() == 4;
         let is_lobby = matches!(self.stage, PrivateGameStage::Lobby);
 
         // don't add any more than 4 users at a time
         if !is_lobby || game_has_max_participants {
-            return Err("Can't add more than 4 participants in a room at once".to_string());
+            return Err(TichuError::GameFull);
         }
 
         let participant = PrivateUser {
-            display_name,
-            user_id: user_id.clone(),
+            display_name: display_name.to_string(),
+            user_id: user_id.to_string(),
             role: UserRole::Participant,
             tricks: vec![],
             hand: vec![],
             has_played_first_card: false,
+            bot_difficulty,
         };
-        let mut new_participants = self.participants.clone();
-        new_participants.push(participant);
-
-        // if 4 have joined, the new game stage should become Teams
-        let new_stage = if current_participants == 3 {
-            let team_a = MutableTeam {
-                id: Uuid::new_v4().to_string(),
-                score: 0,
-                team_name: "Team A".into(),
-                user_ids: vec![user_id, self.participants.get(0).unwrap().user_id.clone()],
-            };
 
-            let team_b = MutableTeam {
-                id: Uuid::new_v4().to_string(),
-                score: 0,
-                team_name: "Team B".into(),
-                user_ids: vec![
-                    self.participants.get(1).unwrap().user_id.clone(),
-                    self.participants.get(2).unwrap().user_id.clone(),
-                ],
-            };
+        // clone old game state and update only what's necessary
+        let mut new_game_state = self.clone();
+        new_game_state.participants.push(participant);
 
-            PrivateGameStage::Teams([team_a, team_b])
-        } else {
-            PrivateGameStage::Lobby
+        Ok(new_game_state)
+    }
+
+    fn apply_bot_joined(
+        &self,
+        requesting_user_id: &str,
+        user_id: &str,
+        display_name: &str,
+        bot_difficulty: &BotDifficulty,
+    ) -> Result<Self, TichuError> {
+        if requesting_user_id != self.owner_id {
+            return Err(TichuError::NotOwner);
+        }
+
+        self.apply_user_joined(user_id, display_name, Some(bot_difficulty.clone()))
+    }
+
+    /// Once 4 users have joined, the game stage becomes Teams
+    fn apply_teams_formed(&self, team_ids: &[String; 2]) -> Result<Self, TichuError> {
+        if !matches!(self.stage, PrivateGameStage::Lobby) {
+            return Err(TichuError::WrongStage);
+        }
+        if self.participants.len() < 4 {
+            return Err(TichuError::NotEnoughParticipants);
+        }
+
+        // the last user to join is teamed up with the first
+        let team_a = MutableTeam {
+            id: team_ids[0].clone(),
+            score: 0,
+            team_name: "Team A".into(),
+            user_ids: vec![
+                self.participants[3].user_id.clone(),
+                self.participants[0].user_id.clone(),
+            ],
+        };
+
+        let team_b = MutableTeam {
+            id: team_ids[1].clone(),
+            score: 0,
+            team_name: "Team B".into(),
+            user_ids: vec![
+                self.participants[1].user_id.clone(),
+                self.participants[2].user_id.clone(),
+            ],
         };
 
-        // clone old game state and update only what's necessary
         let mut new_game_state = self.clone();
-        new_game_state.participants = new_participants;
-        new_game_state.stage = new_stage;
+        new_game_state.stage = PrivateGameStage::Teams([team_a, team_b]);
 
         Ok(new_game_state)
     }
 
     /// Removes a uer from the game.
-    pub fn remove_user(&self, user_id: &str) -> Result<Self, String> {
+    pub fn remove_user(&self, user_id: &str) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::UserLeft {
+            user_id: user_id.to_string(),
+        })
+    }
+
+    fn apply_user_left(&self, user_id: &str) -> Result<Self, TichuError> {
+        // users can only leave before teams are formed: afterward, the game is deleted instead
+        if !matches!(self.stage, PrivateGameStage::Lobby) {
+            return Err(TichuError::WrongStage);
+        }
+
         let mut new_participants = self.participants.clone();
 
         // filter out removed user
@@ -121,13 +373,18 @@ impl PrivateGameState {
     }
 
     /// Reassigns ownership of the game to a different user.
-    pub fn reassign_owner(&self) -> Result<Self, String> {
+    pub fn reassign_owner(&self) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::OwnerReassigned)
+    }
+
+    fn apply_owner_reassigned(&self) -> Result<Self, TichuError> {
         // clone old game state and update only what's necessary
         let mut new_game_state = self.clone();
         let mut new_owner = new_game_state
             .participants
             .iter_mut()
-            .find(|user| user.user_id != new_game_state.owner_id);
+            // bots can't own a game
+            .find(|user| user.user_id != new_game_state.owner_id && !user.is_bot());
         let new_owner = new_owner.as_deref_mut();
         match new_owner {
             Some(new_owner) => {
@@ -135,12 +392,46 @@ impl PrivateGameState {
                 new_game_state.owner_id = new_owner.user_id.clone();
                 Ok(new_game_state)
             }
-            None => Err("Couldn't find new owner in list of participants".to_string()),
+            None => Err(TichuError::UserNotFound),
         }
     }
 
+    /// Everything a specific user has seen of the game so far: their view of the game state,
+    /// plus the cards they traded away this round.
+    pub fn get_observation(&self, current_user_id: &str) -> Result<Observation, TichuError> {
+        let game_state = self.to_public_game_state(current_user_id)?;
+
+        // trades always come after the round's cards are dealt
+        let cards_traded_away = self
+            .events
+            .iter()
+            .rev()
+            .take_while(|event| {
+                !matches!(
+                    event,
+                    GameEvent::CardsDealt { .. } | GameEvent::__AdminSkippedToPlay { .. }
+                )
+            })
+            .find_map(|event| match event {
+                GameEvent::TradeSubmitted {
+                    user_id,
+                    submit_trade,
+                } if user_id == current_user_id => Some(submit_trade.to_vec()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        Ok(Observation {
+            game_state,
+            cards_traded_away,
+        })
+    }
+
     /// Converts game state that only the server can see into state relevant for a specific user.
-    pub fn to_public_game_state(&self, current_user_id: &str) -> Result<PublicGameState, String> {
+    pub fn to_public_game_state(
+        &self,
+        current_user_id: &str,
+    ) -> Result<PublicGameState, TichuError> {
         let mut public_participants: Vec<PublicUser> = Vec::with_capacity(4);
         let mut current_user = None;
         for private_participant in self.participants.iter() {
@@ -155,7 +446,7 @@ impl PrivateGameState {
         let mut current_user = if let Some(current_user) = current_user {
             current_user
         } else {
-            return Err("Can't convert PrivateGameState to PublicGameState, because current user does not exist in list of participants".to_string());
+            return Err(TichuError::UserNotFound);
         };
 
         // sort users hand just in case
@@ -168,6 +459,9 @@ impl PrivateGameState {
             stage: self.stage.clone().into(),
             participants: public_participants,
             current_user,
+            rules: self.rules.clone(),
+            turn_deadline: self.turn_deadline,
+            round_summaries: self.round_summaries.clone(),
         };
 
         Ok(public_game_state)
@@ -178,7 +472,18 @@ impl PrivateGameState {
         &self,
         team_to_move_to: &TeamOption,
         current_user_id: &str,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::MovedToTeam {
+            user_id: current_user_id.to_string(),
+            team_option: team_to_move_to.clone(),
+        })
+    }
+
+    fn apply_moved_to_team(
+        &self,
+        team_to_move_to: &TeamOption,
+        current_user_id: &str,
+    ) -> Result<Self, TichuError> {
         let mut new_state = self.clone();
         match &mut new_state.stage {
             PrivateGameStage::Teams(teams) => {
@@ -203,18 +508,12 @@ impl PrivateGameState {
                     };
                     new_team.user_ids.push(current_user_id.to_string());
                 } else {
-                    return Err(format!(
-                        "Can't move {} to new team because user is already on team",
-                        current_user_id
-                    ));
+                    return Err(TichuError::AlreadyOnTeam);
                 }
                 Ok(new_state)
             }
             // game stage is not teams, can't move teams
-            _ => Err(format!(
-                "Can't move {} to new team because game stage is not Teams",
-                current_user_id
-            )),
+            _ => Err(TichuError::WrongStage),
         }
     }
 
@@ -227,7 +526,20 @@ impl PrivateGameState {
         team_to_rename: &TeamOption,
         current_user_id: &str,
         new_team_a_name: &str,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::TeamRenamed {
+            user_id: current_user_id.to_string(),
+            team_option: team_to_rename.clone(),
+            team_name: new_team_a_name.to_string(),
+        })
+    }
+
+    fn apply_team_renamed(
+        &self,
+        team_to_rename: &TeamOption,
+        current_user_id: &str,
+        new_team_a_name: &str,
+    ) -> Result<Self, TichuError> {
         let mut new_state = self.clone();
         match &mut new_state.stage {
             PrivateGameStage::Teams(teams) => {
@@ -245,25 +557,53 @@ impl PrivateGameState {
                     team_to_rename.team_name = new_team_a_name.to_string();
                     Ok(new_state)
                 } else {
-                    Err(format!(
-                        "Can't rename team for {} because user is not on the team they want to rename",
-                        current_user_id
-                    ))
+                    Err(TichuError::NotOnTeam)
                 }
             }
             // game stage is not teams, can't rename any team
-            _ => Err(format!(
-                "Can't rename team for {} because game stage is not Teams",
-                current_user_id
-            )),
+            _ => Err(TichuError::WrongStage),
         }
     }
 
+    /// Changes the house rules. Only the owner can change them, and only before the game starts.
+    pub fn update_rules(
+        &self,
+        requesting_user_id: &str,
+        rules: &GameRules,
+    ) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::RulesUpdated {
+            user_id: requesting_user_id.to_string(),
+            rules: rules.clone(),
+        })
+    }
+
+    fn apply_rules_updated(
+        &self,
+        requesting_user_id: &str,
+        rules: &GameRules,
+    ) -> Result<Self, TichuError> {
+        if requesting_user_id != self.owner_id {
+            return Err(TichuError::NotOwner);
+        }
+        if !matches!(
+            self.stage,
+            PrivateGameStage::Lobby | PrivateGameStage::Teams(_)
+        ) {
+            return Err(TichuError::WrongStage);
+        }
+        rules.validate()?;
+
+        let mut new_game_state = self.clone();
+        new_game_state.rules = rules.clone();
+
+        Ok(new_game_state)
+    }
+
     pub fn create_undecided_tichu_statuses(
         participants: &Vec<PrivateUser>,
-    ) -> Result<[UserIdWithTichuCallStatus; 4], String> {
+    ) -> Result<[UserIdWithTichuCallStatus; 4], TichuError> {
         if participants.len() < 4 {
-            return Err("Not enough participants to make Grand Tichu call statuses".into());
+            return Err(TichuError::NotEnoughParticipants);
         }
         Ok([
             UserIdWithTichuCallStatus {
@@ -285,16 +625,20 @@ impl PrivateGameState {
         ])
     }
 
-    /// Move from Teams stage to Grand Tichu stage
-    pub fn start_grand_tichu(&self, requesting_user_id: &str) -> Result<Self, String> {
+    /// Move from Teams stage to Grand Tichu stage, and deal the first cards
+    pub fn start_grand_tichu(&self, requesting_user_id: &str) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::GrandTichuStarted {
+            user_id: requesting_user_id.to_string(),
+        })?
+        .deal()
+    }
+
+    fn apply_grand_tichu_started(&self, requesting_user_id: &str) -> Result<Self, TichuError> {
         let mut new_game_state = self.clone();
 
         // requesting user must be the owner
         if new_game_state.owner_id != requesting_user_id {
-            return Err(format!(
-                "User {} cannot start Grand Tichu stage because the user is not the owner. Ignoring request.",
-                requesting_user_id,
-            ));
+            return Err(TichuError::NotOwner);
         }
 
         match &new_game_state.stage {
@@ -308,20 +652,6 @@ impl PrivateGameState {
 
                     match (team_0, team_1) {
                         (Some(team_0), Some(team_1)) => {
-                            let mut deck = Deck::new().shuffle().to_owned();
-
-                            // deal 9 cards to each player
-                            new_game_state
-                                .participants
-                                .iter_mut()
-                                .for_each(|participant| {
-                                    let mut cards = deck.draw(NUM_CARDS_BEFORE_GRAND_TICHU);
-                                    sort_cards_for_hand(&mut cards);
-                                    for card in cards.into_iter() {
-                                        participant.hand.push(card);
-                                    }
-                                });
-
                             // create undecided Grand Tichu statuses
                             let grand_tichus = PrivateGameState::create_undecided_tichu_statuses(
                                 &new_game_state.participants,
@@ -335,7 +665,8 @@ impl PrivateGameState {
                                 grand_tichus,
                                 small_tichus,
                                 teams: [team_0, team_1],
-                                deck,
+                                // cards are dealt by a separate CardsDealt event
+                                deck: Deck(vec![]),
                             };
 
                             // move into Grand Tichu stage
@@ -344,29 +675,75 @@ impl PrivateGameState {
 
                             Ok(new_game_state)
                         }
-                        _ => {
-                            return Err(format!(
-                                "Could not convert MutableTeams to ImmutableTeams. Ignoring request to start Grand Tichu stage by user {}",
-                                requesting_user_id,
-                            ));
-                        }
+                        _ => Err(TichuError::TeamsNotReady),
                     }
                 } else {
-                    return Err(format!(
-                        "Teams are not ready to start game. Ignoring request to start Grand Tichu stage by user {}",
-                        requesting_user_id,
-                    ));
+                    Err(TichuError::TeamsNotReady)
                 }
             }
-            _ => {
-                return Err(format!(
-                    "Game stage is not currently teams. Ignoring request to start by user {}",
-                    requesting_user_id,
-                ));
-            }
+            _ => Err(TichuError::WrongStage),
         }
     }
 
+    /// Seed for shuffling the current round's deck, derived from the game's seed and the round number
+    pub fn get_round_seed(&self) -> u64 {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        rng.set_stream(self.round_summaries.len() as u64);
+        rng.next_u64()
+    }
+
+    /// Shuffles the current round's deck and deals it
+    fn deal(&self) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::CardsDealt {
+            deck: Deck::new().shuffle_with_seed(self.get_round_seed()),
+        })
+    }
+
+    /// Deals the first cards of the round to each player (9, unless the rules say otherwise).
+    ///
+    /// The rest of the deck is saved to be dealt after Grand Tichus have been called.
+    /// If the rules don't allow Grand Tichu, every card is dealt and trading starts right away.
+    fn apply_cards_dealt(&self, deck: &Deck) -> Result<Self, TichuError> {
+        let mut new_game_state = self.clone();
+        let grand_tichu_state =
+            if let PrivateGameStage::GrandTichu(grand_tichu_state) = &mut new_game_state.stage {
+                grand_tichu_state
+            } else {
+                return Err(TichuError::WrongStage);
+            };
+
+        if new_game_state
+            .participants
+            .iter()
+            .any(|participant| !participant.hand.is_empty())
+        {
+            return Err(TichuError::Internal(
+                "Cards have already been dealt this round".into(),
+            ));
+        }
+
+        // deal the first cards to each player
+        let num_cards_first_dealt = self.rules.get_num_cards_first_dealt();
+        let mut deck = deck.clone();
+        new_game_state
+            .participants
+            .iter_mut()
+            .for_each(|participant| {
+                let mut cards = deck.draw(num_cards_first_dealt);
+                sort_cards_for_hand(&mut cards);
+                for card in cards.into_iter() {
+                    participant.hand.push(card);
+                }
+            });
+        grand_tichu_state.deck = deck;
+
+        if !self.rules.grand_tichu_enabled {
+            return new_game_state.start_trade();
+        }
+
+        Ok(new_game_state)
+    }
+
     /// Saves a users Grand Tichu choice
     ///
     /// User can either Call or Decline
@@ -374,7 +751,18 @@ impl PrivateGameState {
         &self,
         call_grand_tichu_request: &CallGrandTichuRequest,
         user_id: &str,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::GrandTichuCalled {
+            user_id: user_id.to_string(),
+            call_grand_tichu_request: call_grand_tichu_request.clone(),
+        })
+    }
+
+    fn apply_grand_tichu_called(
+        &self,
+        call_grand_tichu_request: &CallGrandTichuRequest,
+        user_id: &str,
+    ) -> Result<Self, TichuError> {
         let mut new_game_state = self.clone();
 
         // game stage must be GrandTichu
@@ -386,13 +774,13 @@ impl PrivateGameState {
                     .position(|user_call_status| *user_call_status.user_id == *user_id);
                 match i {
                     None => {
-                        return Err(format!("Couldn't find user's call status in GrandTichu call stage. Ignoring request to call Grand Tichu from user {}", user_id));
+                        return Err(TichuError::UserNotFound);
                     }
                     Some(i) => {
                         let grand_tichus = &mut grand_tichu_state.grand_tichus;
                         let user_call_status = &grand_tichus[i];
                         if user_call_status.tichu_call_status != TichuCallStatus::Undecided {
-                            return Err(format!("User has already declared or declined Grand Tichu. Ignoring request to call Grand Tichu from user {}", user_id));
+                            return Err(TichuError::TichuAlreadyDecided);
                         }
                         grand_tichus[i] = UserIdWithTichuCallStatus {
                             user_id: user_id.to_string(),
@@ -421,7 +809,7 @@ impl PrivateGameState {
                 }
             }
             _ => {
-                return Err(format!("Can't call Grand Tichu when game stage is not GrandTichu. Ignoring request from user {}", user_id));
+                return Err(TichuError::WrongStage);
             }
         }
 
@@ -431,16 +819,19 @@ impl PrivateGameState {
     /// Saves user's Small Tichu choice
     ///
     /// User can only CALL small tichu. Cannot decline.
-    pub fn call_small_tichu(&self, user_id: &str) -> Result<Self, String> {
+    pub fn call_small_tichu(&self, user_id: &str) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::SmallTichuCalled {
+            user_id: user_id.to_string(),
+        })
+    }
+
+    fn apply_small_tichu_called(&self, user_id: &str) -> Result<Self, TichuError> {
         let mut new_game_state = self.clone();
 
         // game stage cannot be lobby, teams, or scoreboard
         let small_tichus = match &mut new_game_state.stage {
             PrivateGameStage::Lobby | PrivateGameStage::Teams(_) | PrivateGameStage::Score(_) => {
-                return Err(format!(
-                    "Can't call Small Tichu when game is not active. Ignoring request from user {}",
-                    user_id
-                ));
+                return Err(TichuError::WrongStage);
             }
             PrivateGameStage::GrandTichu(grand_tichu_state) => {
                 grand_tichu_state.get_small_tichu_mut()
@@ -454,12 +845,12 @@ impl PrivateGameState {
             .position(|user_call_status| *user_call_status.user_id == *user_id);
         match i {
             None => {
-                return Err(format!("Couldn't find user's call status in call stage state. Ignoring request to call Small Tichu from user {}", user_id));
+                return Err(TichuError::UserNotFound);
             }
             Some(i) => {
                 let user_call_status = &small_tichus[i];
                 if user_call_status.tichu_call_status != TichuCallStatus::Undecided {
-                    return Err(format!("User is not in Undecided state about Small Tichu. Ignoring request to call Small Tichu from user {}", user_id));
+                    return Err(TichuError::TichuAlreadyDecided);
                 }
                 small_tichus[i] = UserIdWithTichuCallStatus {
                     user_id: user_id.to_string(),
@@ -473,12 +864,14 @@ impl PrivateGameState {
 
     /// Start trade occurs automatically after last Grand Tichu is either Called or Denied
     /// Mutates self rather than cloning game state, since it only occurs in conjunction with CallGrandTichu
-    fn start_trade(mut self) -> Result<Self, String> {
+    fn start_trade(mut self) -> Result<Self, TichuError> {
         // must currently be in Grand Tichu stage
         if let PrivateGameStage::GrandTichu(mut grand_tichu) = self.stage {
             // deal the rest of the cards to each player
             for participant in self.participants.iter_mut() {
-                let mut drawn_cards = grand_tichu.deck.draw(NUM_CARDS_AFTER_GRAND_TICHU);
+                let mut drawn_cards = grand_tichu
+                    .deck
+                    .draw(MAX_CARDS_IN_HAND - participant.hand.len());
                 for _ in 0..drawn_cards.len() {
                     let drawn_card = drawn_cards.pop().unwrap();
                     participant.hand.push(drawn_card)
@@ -488,7 +881,7 @@ impl PrivateGameState {
             // move game stage to Trade game stage
             self.stage = PrivateGameStage::Trade(Box::new((*grand_tichu).into()));
         } else {
-            return Err("Can't start trade when not in Grand Tichu stage".to_string());
+            return Err(TichuError::WrongStage);
         }
         Ok(self)
     }
@@ -496,7 +889,22 @@ impl PrivateGameState {
     /// Saves a user's trade choice.
     ///
     /// These trades are actually committed/enacted once all users have submitted their trades.
-    pub fn submit_trade(&self, user_id: &str, submit_trade: &SubmitTrade) -> Result<Self, String> {
+    pub fn submit_trade(
+        &self,
+        user_id: &str,
+        submit_trade: &SubmitTrade,
+    ) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::TradeSubmitted {
+            user_id: user_id.to_string(),
+            submit_trade: submit_trade.clone(),
+        })
+    }
+
+    fn apply_trade_submitted(
+        &self,
+        user_id: &str,
+        submit_trade: &SubmitTrade,
+    ) -> Result<Self, TichuError> {
         let mut new_game_state = self.clone();
 
         // Must be Trade stage
@@ -509,18 +917,35 @@ impl PrivateGameState {
             let user = if let Some(i) = i {
                 &mut new_game_state.participants[i]
             } else {
-                return Err(format!("Couldn't accept traded submitted by user {user_id} because user could not be found in participants"));
+                return Err(TichuError::UserNotFound);
             };
 
+            // User can only trade once per round
+            let already_submitted = trade_stage
+                .trades
+                .iter()
+                .flatten()
+                .any(|trade| trade[0].from_user_id == user_id);
+            if already_submitted {
+                return Err(TichuError::TradeAlreadySubmitted);
+            }
+
+            // User must actually have those cards in their hand (each one traded only once)
+            let traded_cards: Vec<Card> = submit_trade
+                .iter()
+                .map(|trade| trade.card.clone())
+                .collect();
+            validate_cards_in_hand(&user.hand, &traded_cards)?;
+
             for trade in submit_trade {
-                // User must actually have those cards in their hand
-                if !user.hand.contains(&trade.card) {
-                    return Err(format!("Couldn't accept traded submitted by user {} because user does {:?}, which they are trying to trade", user_id, trade.card));
+                // Trade must come from the user submitting it
+                if trade.from_user_id != user_id {
+                    return Err(TichuError::TradeNotFromUser);
                 }
 
                 // Trade must not be to self
                 if trade.to_user_id == user_id {
-                    return Err( format!("Couldn't accept traded submitted by user {} because user is trying to trade to self", user_id));
+                    return Err(TichuError::TradeToSelf);
                 }
 
                 // Trade must be to a valid participant who is on a team
@@ -533,10 +958,18 @@ impl PrivateGameState {
                     }
                 }
                 if !recipient_found_in_teams {
-                    return Err(format!("Couldn't accept traded submitted by user {} because the person the user is trying to trade to was not found in the teams", user_id));
+                    return Err(TichuError::TradeRecipientNotFound);
                 }
             }
 
+            // Every other user must receive exactly one card
+            if submit_trade[0].to_user_id == submit_trade[1].to_user_id
+                || submit_trade[0].to_user_id == submit_trade[2].to_user_id
+                || submit_trade[1].to_user_id == submit_trade[2].to_user_id
+            {
+                return Err(TichuError::DuplicateTradeRecipient);
+            }
+
             // Save the user's trade for later
             let free_index = trade_stage
                 .trades
@@ -545,7 +978,9 @@ impl PrivateGameState {
             if let Some(free_index) = free_index {
                 trade_stage.trades[free_index] = Some(submit_trade.clone());
             } else {
-                return Err(format!("Couldn't accept traded submitted by user {} because no free index was found to save to in the Trade state `trades` array", user_id));
+                return Err(TichuError::Internal(
+                    "No free index was found to save to in the Trade state `trades` array".into(),
+                ));
             }
 
             // Remove traded cards from user's hand
@@ -576,10 +1011,9 @@ impl PrivateGameState {
                                 if let Some(i) = i {
                                     new_game_state.participants[i].hand.push(card.card.clone());
                                 } else {
-                                    return Err(
-                                        "Game state error: Couldn't find user to trade card to"
-                                            .to_string(),
-                                    );
+                                    return Err(TichuError::Internal(
+                                        "Couldn't find user to trade card to".into(),
+                                    ));
                                 }
                             }
                         }
@@ -598,7 +1032,9 @@ impl PrivateGameState {
                     let first_turn_user_id = if let Some(first_turn_user_id) = first_turn_user_id {
                         first_turn_user_id
                     } else {
-                        return Err("Could not find MahJong among the participants' hands".into());
+                        return Err(TichuError::Internal(
+                            "Could not find MahJong among the participants' hands".into(),
+                        ));
                     };
                     let mut play_state: PrivatePlay = (**private_trade).clone().into();
                     play_state.turn_user_id = first_turn_user_id;
@@ -608,21 +1044,16 @@ impl PrivateGameState {
 
             Ok(new_game_state)
         } else {
-            return Err(format!(
-                "Couldn't accept traded submitted by user {} because Game Stage is not Trade",
-                user_id
-            ));
+            Err(TichuError::WrongStage)
         }
     }
 
-    pub fn get_number_of_users_who_have_passed(&self) -> Result<usize, String> {
+    pub fn get_number_of_users_who_have_passed(&self) -> Result<usize, TichuError> {
         if let PrivateGameStage::Play(play_state) = &self.stage {
             return Ok(play_state.passes.iter().filter(|pass| pass.passed).count());
         }
 
-        Err(String::from(
-            "Can't get number of passes when game stage is not PlayStage",
-        ))
+        Err(TichuError::WrongStage)
     }
 
     pub fn get_only_turn_users_teammates_are_in_play(&self) -> bool {
@@ -645,7 +1076,21 @@ impl PrivateGameState {
         }
     }
 
-    pub fn pass(&self, user_id: &str) -> Result<Self, String> {
+    /// Saves a user's pass.
+    ///
+    /// Once every user in play has passed on a combo, its trick is won by whoever played it.
+    pub fn pass(&self, user_id: &str) -> Result<Self, TichuError> {
+        let new_game_state = self.apply(&GameEvent::Passed {
+            user_id: user_id.to_string(),
+        })?;
+
+        match new_game_state.get_trick_winner_user_id() {
+            Some(trick_winner_user_id) => new_game_state.win_trick(&trick_winner_user_id),
+            None => Ok(new_game_state),
+        }
+    }
+
+    fn apply_passed(&self, user_id: &str) -> Result<Self, TichuError> {
         let mut new_game_state = self.clone();
 
         let number_of_users_who_have_passed =
@@ -655,94 +1100,219 @@ impl PrivateGameState {
             if let PrivateGameStage::Play(new_play_state) = &mut new_game_state.stage {
                 new_play_state
             } else {
-                return Err(format!(
-                    "Couldn't accept pass from user {} because Game Stage is not Play",
-                    user_id
-                ));
+                return Err(TichuError::WrongStage);
             };
 
-        let is_penultimate_pass =
-            number_of_users_who_have_passed == new_play_state.users_in_play.len() - 1;
-
-        // if this is the penultimate pass, next user wins the trick,so move them into the user's tricks
-        if is_penultimate_pass {
-            let last_trick = new_play_state.table.last();
-
-            let last_trick = if let Some(last_trick) = last_trick {
-                last_trick
-            } else {
-                // if no trick was played, but this is the penultimate pass, then that means
-                // every user passed without playing a card, so lead returns to first player
-                new_play_state.turn_user_id = new_play_state
-                    .get_next_turn_user_id()
-                    .ok_or_else(|| format!("Couldn't find next user id: {:#?}", new_play_state))?
-                    .clone();
-
-                // reset passes
-                new_play_state.passes.iter_mut().for_each(|pass| {
-                    pass.passed = false;
-                });
+        // nobody can pass until the Dragon has been given away
+        if new_play_state.dragon_winner_user_id.is_some() {
+            return Err(TichuError::DragonNotGivenAway);
+        }
 
-                return Ok(new_game_state);
-            };
-            let last_trick_contains_dragon = last_trick.cards().contains(&DRAGON);
+        if new_play_state.turn_user_id != user_id {
+            return Err(TichuError::NotYourTurn);
+        }
 
-            let user_who_played_last_trick_id = last_trick.user_id().clone();
-            let receiving_user_id = if last_trick_contains_dragon {
-                // if it contains a dragon, give trick to the user who the winner chose
-                new_play_state
-                .user_id_to_give_dragon_to
-                .as_ref()
-                .expect("If someone won with the dragon, then the user has to have picked a user to give it to")
-                .clone()
-            } else {
-                // if it does not contains a dragon, give it to the user who played the last trick
-                user_who_played_last_trick_id.clone()
-            };
+        // the user leading a trick has to play something
+        if new_play_state.table.is_empty() {
+            return Err(TichuError::CannotPassOnLead);
+        }
 
-            // remove trick from table and give to receiving user
-            let receiving_user = new_game_state
-                .participants
-                .iter_mut()
-                .find(|user| (*user.user_id == *receiving_user_id))
-                .expect("Receiving user should be in the game");
-            let mut current_table_cards: Vec<ValidCardCombo> =
-                new_play_state.table.drain(..).collect();
-            receiving_user.tricks.append(&mut current_table_cards);
-
-            if self.get_round_is_over() {
-                return new_game_state.round_over();
+        // users can't pass if they are able to fulfill the wish,
+        // unless their own combo is still on top and they are passing to take the trick
+        let is_own_combo_on_top = new_play_state
+            .table
+            .last()
+            .is_some_and(|combo| combo.user_id() == user_id);
+        if let (false, Some(wished_for_card)) =
+            (is_own_combo_on_top, &new_play_state.wished_for_card_value)
+        {
+            let user = self
+                .get_user_by_user_id(user_id)
+                .ok_or(TichuError::UserNotFound)?;
+            if get_user_can_play_wished_for_card(
+                new_play_state.table.last(),
+                &user.hand,
+                wished_for_card,
+            ) {
+                return Err(TichuError::MustFulfillWish);
             }
+        }
 
-            // user keeps the lead if they won the trick
-            new_play_state.turn_user_id = user_who_played_last_trick_id;
+        let is_penultimate_pass =
+            number_of_users_who_have_passed == new_play_state.users_in_play.len() - 1;
 
-            // reset passes
-            new_play_state.passes.iter_mut().for_each(|pass| {
-                pass.passed = false;
-            });
+        // if this is the penultimate pass, next user wins the trick
+        if is_penultimate_pass {
+            // nobody passes on the lead, so something is on the table
+            let last_trick = new_play_state
+                .table
+                .last()
+                .ok_or_else(|| TichuError::Internal("Nothing on the table to win".into()))?;
+            let user_who_played_last_trick_id = last_trick.user_id().clone();
 
-            return Ok(new_game_state);
+            // if it contains a dragon, wait for the winner to choose who receives the trick
+            if last_trick.cards().contains(&DRAGON) {
+                new_play_state.dragon_winner_user_id = Some(user_who_played_last_trick_id);
+                return Ok(new_game_state);
+            }
         }
 
-        // else if not the penultimate pass, merely save the pass and move the turn
+        // save the pass
         let user_pass_index = new_play_state
             .passes
             .iter()
             .position(|pass| pass.user_id == user_id)
             .expect("User should be in the passes state");
         new_play_state.passes[user_pass_index].passed = true;
+
+        // on the penultimate pass, the trick is won with a separate TrickWon event,
+        // which also decides who leads next
+        if is_penultimate_pass {
+            return Ok(new_game_state);
+        }
+
+        // else move the turn
         new_play_state.turn_user_id = new_play_state
             .get_next_turn_user_id()
-            .ok_or_else(|| format!("Couldn't find next user id: {:#?}", new_play_state))?
+            .ok_or_else(|| {
+                TichuError::Internal(format!("Couldn't find next user id: {:#?}", new_play_state))
+            })?
             .clone();
 
         Ok(new_game_state)
     }
 
-    fn round_over(&self) -> Result<Self, String> {
+    /// The user who won the trick on the table, once every user in play has passed on it.
+    ///
+    /// None while the trick is still being played, or while the Dragon is waiting to be given away.
+    pub fn get_trick_winner_user_id(&self) -> Option<String> {
+        if let PrivateGameStage::Play(play_state) = &self.stage {
+            if play_state.dragon_winner_user_id.is_some() {
+                return None;
+            }
+            let last_combo = play_state.table.last()?;
+            let number_of_users_who_have_passed =
+                play_state.passes.iter().filter(|pass| pass.passed).count();
+            if number_of_users_who_have_passed >= play_state.users_in_play.len() {
+                return Some(last_combo.user_id().clone());
+            }
+        }
+        None
+    }
+
+    /// The user who won a trick with the Dragon gives it to an opponent of their choosing
+    pub fn give_dragon(&self, user_id: &str, recipient_user_id: &str) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::DragonGiven {
+            user_id: user_id.to_string(),
+            recipient_user_id: recipient_user_id.to_string(),
+        })?
+        .win_trick(recipient_user_id)
+    }
+
+    fn apply_dragon_given(
+        &self,
+        user_id: &str,
+        recipient_user_id: &str,
+    ) -> Result<Self, TichuError> {
+        let play_state = if let PrivateGameStage::Play(play_state) = &self.stage {
+            play_state
+        } else {
+            return Err(TichuError::WrongStage);
+        };
+
+        if play_state.dragon_winner_user_id.as_deref() != Some(user_id) {
+            return Err(TichuError::NotDragonWinner);
+        }
+
+        let recipient_is_opponent = play_state.teams.iter().any(|team| {
+            team.user_ids.iter().any(|id| id == recipient_user_id)
+                && !team.user_ids.iter().any(|id| id == user_id)
+        });
+        if !recipient_is_opponent {
+            return Err(TichuError::RecipientNotOpponent);
+        }
+
+        let mut new_game_state = self.clone();
+        if let PrivateGameStage::Play(new_play_state) = &mut new_game_state.stage {
+            new_play_state.dragon_winner_user_id = None;
+        }
+
+        Ok(new_game_state)
+    }
+
+    /// Gives the trick to the receiving user, then scores the round if it's over
+    /// and deals the next one if the game isn't.
+    fn win_trick(&self, receiving_user_id: &str) -> Result<Self, TichuError> {
+        let mut new_game_state = self.apply(&GameEvent::TrickWon {
+            receiving_user_id: receiving_user_id.to_string(),
+        })?;
+
+        if new_game_state.get_round_is_over() {
+            new_game_state = new_game_state.apply(&GameEvent::RoundOver)?;
+
+            if let PrivateGameStage::GrandTichu(_) = new_game_state.stage {
+                new_game_state = new_game_state.deal()?;
+            }
+        }
+
+        Ok(new_game_state)
+    }
+
+    /// Moves every combo on the table into the tricks of the receiving user.
+    ///
+    /// If the round isn't over, the user who played the last combo leads next.
+    fn apply_trick_won(&self, receiving_user_id: &str) -> Result<Self, TichuError> {
+        let mut new_game_state = self.clone();
+        let new_play_state =
+            if let PrivateGameStage::Play(new_play_state) = &mut new_game_state.stage {
+                new_play_state
+            } else {
+                return Err(TichuError::WrongStage);
+            };
+
+        let user_who_played_last_trick_id = new_play_state
+            .table
+            .last()
+            .ok_or_else(|| TichuError::Internal("Can't collect trick from an empty table".into()))?
+            .user_id()
+            .clone();
+
+        // remove trick from table and give to receiving user
+        let receiving_user = new_game_state
+            .participants
+            .iter_mut()
+            .find(|user| *user.user_id == *receiving_user_id)
+            .ok_or(TichuError::UserNotFound)?;
+        let mut current_table_cards: Vec<ValidCardCombo> = new_play_state.table.drain(..).collect();
+        receiving_user.tricks.append(&mut current_table_cards);
+
+        // the round is scored with a separate RoundOver event
+        if self.get_round_is_over() {
+            return Ok(new_game_state);
+        }
+
+        // user keeps the lead if they won the trick,
+        // unless they're out of cards, in which case the next user in play leads
+        new_play_state.turn_user_id = new_play_state
+            .get_next_turn_user_id_starting_with_user_id(&user_who_played_last_trick_id)
+            .ok_or_else(|| {
+                TichuError::Internal(format!("Couldn't find next user id: {:#?}", new_play_state))
+            })?
+            .clone();
+
+        // reset passes
+        new_play_state.passes.iter_mut().for_each(|pass| {
+            pass.passed = false;
+        });
+
+        Ok(new_game_state)
+    }
+
+    fn apply_round_over(&self) -> Result<Self, TichuError> {
         if !self.get_round_is_over() {
-            return Err("Can't calculate round over state when the round is not over".to_string());
+            return Err(TichuError::Internal(
+                "Can't calculate round over state when the round is not over".into(),
+            ));
         }
 
         let mut new_game_state = self.clone();
@@ -750,6 +1320,12 @@ impl PrivateGameState {
             let is_double_victory =
                 new_play_state.users_in_play.len() == 2 && self.get_is_only_one_team_in_play();
 
+            let scores_before_round = new_play_state.teams.clone().map(|team| team.score);
+            let mut card_points = [0; 2];
+            let mut double_victory_points = [0; 2];
+            let mut double_victory_team_id = None;
+            let mut last_user_out = None;
+
             if is_double_victory {
                 // get users who went out
                 let users_who_went_out: Vec<PrivateUser> = self
@@ -768,35 +1344,45 @@ impl PrivateGameState {
                         .find(|team| team.user_ids.contains(&user_who_went_out.user_id))
                         .expect("Should be able to find the double victory team")
                 } else {
-                    return Err(
+                    return Err(TichuError::Internal(
                         "Couldn't find the team of the users who went out first in double victory"
-                            .to_string(),
-                    );
+                            .into(),
+                    ));
                 };
 
                 // if a double victory occurs, cards are not moved/counted for points
-                double_victory_team.score += 200;
+                double_victory_team.score += self.rules.double_victory_value;
+                double_victory_team_id = Some(double_victory_team.id.clone());
+                for (i, team) in new_play_state.teams.iter().enumerate() {
+                    if Some(&team.id) == double_victory_team_id.as_ref() {
+                        double_victory_points[i] = self.rules.double_victory_value;
+                    }
+                }
             } else {
                 // plain round over (not double victory)
                 let last_player_id = new_play_state
                     .users_in_play
                     .last()
                     .expect("There should be a user left in play");
+                last_user_out = Some(last_player_id.clone());
 
+                let first_user_out_id = new_play_state
+                    .first_user_out
+                    .clone()
+                    .unwrap_or_else(|| String::from(""));
+
+                // the opponent receiving the last player's hand must be a different participant
+                // than the user who went out first, since both are borrowed mutably below
                 let last_player_opponent_id = new_play_state
                     .teams
                     .iter()
                     .find(|team| !team.user_ids.contains(last_player_id))
                     .expect("Should be able to find opposing team to the last player in the game")
                     .user_ids
-                    .first()
+                    .iter()
+                    .find(|user_id| **user_id != first_user_out_id)
                     .expect("Opposing team should have a user in it");
 
-                let first_user_out_id = new_play_state
-                    .first_user_out
-                    .clone()
-                    .unwrap_or_else(|| String::from(""));
-
                 let mut first_player_out = None;
                 let mut last_player_out = None;
                 let mut last_player_out_opponent = None;
@@ -828,7 +1414,7 @@ impl PrivateGameState {
                 first_player_out.tricks.append(&mut last_player_tricks);
 
                 // increment team points based on everyone's newly moved cards
-                for team in new_play_state.teams.iter_mut() {
+                for (i, team) in new_play_state.teams.iter_mut().enumerate() {
                     for user_id in team.user_ids.iter() {
                         let participant = PrivateGameState::get_user_by_user_id_from_participants(
                             &new_game_state.participants,
@@ -842,35 +1428,65 @@ impl PrivateGameState {
                                         Card {
                                             value: CardValue(10) | CardValue(13),
                                             ..
-                                        } => team.score += 10,
+                                        } => card_points[i] += 10,
                                         // + 5 for each five
                                         Card {
                                             value: CardValue(5),
                                             ..
-                                        } => team.score += 5,
+                                        } => card_points[i] += 5,
                                         // + 25 for the Dragon, and
                                         Card {
                                             suit: CardSuit::Dragon,
                                             ..
-                                        } => team.score += 25,
+                                        } => card_points[i] += 25,
                                         // - 25 for the Phoenix
                                         Card {
                                             suit: CardSuit::Phoenix,
                                             ..
-                                        } => team.score -= 25,
+                                        } => card_points[i] -= 25,
                                         _ => {}
                                     }
                                 }
                             }
                         }
                     }
+                    team.score += card_points[i];
                 }
             };
 
-            let a_team_has_enough_points_to_win =
-                new_play_state.teams.iter().any(|team| team.score > 1000);
-            let teams_are_tied = new_play_state.teams[0].score == new_play_state.teams[1].score;
-            if a_team_has_enough_points_to_win && !teams_are_tied {
+            // every Small Tichu and Grand Tichu call is won or lost based on who went out first
+            new_play_state.settle_tichu_calls(&self.rules);
+
+            // record how every point was earned this round
+            let team_round_scores = [0, 1].map(|i| {
+                let team = &new_play_state.teams[i];
+                let running_total = team.score;
+                TeamRoundScore {
+                    team_id: team.id.clone(),
+                    card_points: card_points[i],
+                    double_victory_points: double_victory_points[i],
+                    tichu_points: running_total
+                        - scores_before_round[i]
+                        - card_points[i]
+                        - double_victory_points[i],
+                    running_total,
+                }
+            });
+            new_game_state.round_summaries.push(RoundSummary {
+                round_number: self.round_summaries.len() + 1,
+                teams: team_round_scores,
+                double_victory_team_id,
+                small_tichus: new_play_state.small_tichus.clone(),
+                grand_tichus: new_play_state.grand_tichus.clone(),
+                first_user_out: new_play_state.first_user_out.clone(),
+                last_user_out,
+            });
+
+            let is_game_over = self.rules.get_is_game_over(
+                new_game_state.round_summaries.len(),
+                new_play_state.teams.clone().map(|team| team.score),
+            );
+            if is_game_over {
                 new_game_state.game_over()?;
             } else {
                 // else start next round
@@ -879,32 +1495,26 @@ impl PrivateGameState {
 
             Ok(new_game_state)
         } else {
-            Err("Can't count round over, because it is not the Play stage".to_string())
+            Err(TichuError::WrongStage)
         };
     }
 
-    /// A round has finished, but nobody's points are high enough to actually win the game yet,
-    /// so clear tricks and deal fresh cards
+    /// A round has finished, but the game isn't over yet according to the rules,
+    /// so clear tricks and hands. Fresh cards are dealt with a separate CardsDealt event,
+    /// shuffled with the new round's seed.
+    ///
+    /// Every Tichu call starts out undecided again, so the settled calls must already be
+    /// recorded in the finished round's `RoundSummary`.
     ///
     /// Mutates game state in place
-    pub fn start_new_round(&mut self) -> Result<(), String> {
+    pub fn start_new_round(&mut self) -> Result<(), TichuError> {
         return if let PrivateGameStage::Play(play_state) = &self.stage {
-            let mut deck = Deck::new().shuffle().to_owned();
-
-            // deal 9 cards to each player
             self.participants.iter_mut().for_each(|participant| {
                 participant.tricks.clear();
 
                 participant.hand.clear();
 
                 participant.has_played_first_card = false;
-
-                // deal 9 cards
-                let mut cards = deck.draw(NUM_CARDS_BEFORE_GRAND_TICHU);
-                sort_cards_for_hand(&mut cards);
-                for card in cards.into_iter() {
-                    participant.hand.push(card);
-                }
             });
 
             // create undecided Grand Tichu statuses
@@ -918,7 +1528,7 @@ impl PrivateGameState {
                 grand_tichus,
                 small_tichus,
                 teams: play_state.teams.clone(),
-                deck,
+                deck: Deck(vec![]),
             };
 
             // move into Grand Tichu stage
@@ -926,19 +1536,21 @@ impl PrivateGameState {
 
             Ok(())
         } else {
-            Err("Can't start new round if not already in play stage".to_string())
+            Err(TichuError::WrongStage)
         };
     }
 
     /// Team has high enough points at the end of around to have won the game.
     ///
-    /// If one team is over 1000 and there is no tie, then highest scoring team wins, so move to scoreboard stage.
+    /// If the rules' end of the game has been reached (by default, one team is over 1000)
+    /// and there is no tie, then highest scoring team wins, so move to scoreboard stage.
     /// Mutates state in place
-    pub fn game_over(&mut self) -> Result<(), String> {
+    pub fn game_over(&mut self) -> Result<(), TichuError> {
         return if let PrivateGameStage::Play(play_state) = &self.stage {
-            return if play_state.teams.iter().any(|team| team.score > 1000)
-                && play_state.teams[0].score != play_state.teams[1].score
-            {
+            return if self.rules.get_is_game_over(
+                self.round_summaries.len(),
+                play_state.teams.clone().map(|team| team.score),
+            ) {
                 // clear users' state (hands, tricks, etc.)
                 self.participants.iter_mut().for_each(|participant| {
                     participant.tricks.clear();
@@ -946,13 +1558,17 @@ impl PrivateGameState {
                     participant.has_played_first_card = false;
                 });
 
-                self.stage = PrivateGameStage::Score((**play_state).to_owned().into());
+                let mut score: Score = (**play_state).to_owned().into();
+                score.round_summaries = self.round_summaries.clone();
+                self.stage = PrivateGameStage::Score(Box::new(score));
                 Ok(())
             } else {
-                Err("No team's points exceeded the score threshold, or there was a tie".to_string())
+                Err(TichuError::Internal(
+                    "The end of the game hasn't been reached, or there was a tie".into(),
+                ))
             };
         } else {
-            Err("Can't end game when PrivateGameStage is not Play".to_string())
+            Err(TichuError::WrongStage)
         };
     }
 
@@ -976,11 +1592,15 @@ impl PrivateGameState {
         user.hand.is_empty()
     }
 
-    pub fn __admin_skip_to_play(&self) -> Result<Self, String> {
-        eprintln!("{:#?}", self);
+    pub fn __admin_skip_to_play(&self) -> Result<Self, TichuError> {
+        self.apply(&GameEvent::__AdminSkippedToPlay {
+            deck: Deck::new().shuffle_with_seed(self.get_round_seed()),
+        })
+    }
+
+    fn apply_admin_skipped_to_play(&self, deck: &Deck) -> Result<Self, TichuError> {
         if let PrivateGameStage::Teams(team_state) = &self.stage {
             let mut updated_participants = self.participants.clone();
-            let deck = Deck::new().shuffle();
             updated_participants[0].hand = deck.0[0..MAX_CARDS_IN_HAND].to_vec();
             updated_participants[1].hand =
                 deck.0[(MAX_CARDS_IN_HAND * 1)..(MAX_CARDS_IN_HAND * 2)].to_vec();
@@ -1044,7 +1664,7 @@ impl PrivateGameState {
                 ],
                 table: Vec::new(),
                 turn_user_id: user_id_who_has_mah_jong,
-                user_id_to_give_dragon_to: None,
+                dragon_winner_user_id: None,
                 wished_for_card_value: None,
                 passes: [
                     PassWithUserId {
@@ -1077,14 +1697,43 @@ impl PrivateGameState {
                 game_code: self.game_code.clone(),
                 game_id: self.game_id.clone(),
                 owner_id: self.owner_id.clone(),
+                seed: self.seed,
+                rules: self.rules.clone(),
                 participants: updated_participants,
                 stage: new_game_stage,
+                round_summaries: self.round_summaries.clone(),
+                turn_deadline: self.turn_deadline,
+                events: self.events.clone(),
             };
 
             Ok(new_state)
         } else {
-            Err(String::from("Not in Teams stage"))
+            Err(TichuError::WrongStage)
+        }
+    }
+
+    fn apply_admin_scores_set(&self, scores: &[i32; 2]) -> Result<Self, TichuError> {
+        let mut new_game_state = self.clone();
+        match &mut new_game_state.stage {
+            PrivateGameStage::Teams(teams_state) => {
+                teams_state[0].score = scores[0];
+                teams_state[1].score = scores[1];
+            }
+            PrivateGameStage::GrandTichu(grand_tichu_state) => {
+                grand_tichu_state.teams[0].score = scores[0];
+                grand_tichu_state.teams[1].score = scores[1];
+            }
+            PrivateGameStage::Trade(trade_state) => {
+                trade_state.teams[0].score = scores[0];
+                trade_state.teams[1].score = scores[1];
+            }
+            PrivateGameStage::Play(play_state) => {
+                play_state.teams[0].score = scores[0];
+                play_state.teams[1].score = scores[1];
+            }
+            _ => return Err(TichuError::WrongStage),
         }
+        Ok(new_game_state)
     }
 
     pub fn get_is_only_one_team_in_play(&self) -> bool {
@@ -1128,16 +1777,51 @@ impl PrivateGameState {
         user_id: &str,
         next_cards: Vec<Card>,
         wished_for_card_value: Option<CardValue>,
-        user_id_to_give_dragon_to: Option<String>,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, TichuError> {
+        let new_game_state = self.apply(&GameEvent::CardsPlayed {
+            user_id: user_id.to_string(),
+            cards: next_cards,
+            wished_for_card_value,
+        })?;
+
+        // the user who ends the round with their last cards wins the trick on the table
+        let dragon_must_be_given_away = matches!(
+            &new_game_state.stage,
+            PrivateGameStage::Play(play_state) if play_state.dragon_winner_user_id.is_some()
+        );
+        if new_game_state.get_round_is_over() && !dragon_must_be_given_away {
+            return new_game_state.win_trick(user_id);
+        }
+
+        Ok(new_game_state)
+    }
+
+    fn apply_cards_played(
+        &self,
+        user_id: &str,
+        next_cards: Vec<Card>,
+        wished_for_card_value: Option<CardValue>,
+    ) -> Result<Self, TichuError> {
         let mut new_game_state = self.clone();
 
         // must be play stage
         if let PrivateGameStage::Play(new_play_stage) = &mut new_game_state.stage {
+            // nobody can play until the Dragon has been given away
+            if new_play_stage.dragon_winner_user_id.is_some() {
+                return Err(TichuError::DragonNotGivenAway);
+            }
+
+            // user must actually have every played card in their hand
+            let user = self
+                .get_user_by_user_id(user_id)
+                .ok_or(TichuError::UserNotFound)?;
+            validate_cards_in_hand(&user.hand, &next_cards)?;
+
             let next_combo =
                 get_card_combination(new_play_stage.table.last(), &next_cards, user_id);
             if let Some(next_combo) = next_combo {
                 let is_bomb = next_combo.is_bomb();
+                let is_users_turn = new_play_stage.turn_user_id == user_id;
 
                 // if is a bomb, then it must become that users' turn (and the others must pass as usual)
                 if is_bomb {
@@ -1150,39 +1834,20 @@ impl PrivateGameState {
                     // must be a valid play based on the previous card (or no card)
                     if next_combo_beats_prev(&prev_combo, &next_combo) {
                         // if there is a wish and the user can play it, does this combo contain it?
+                        if !get_play_respects_wish(
+                            prev_combo,
+                            &user.hand,
+                            &next_combo,
+                            new_play_stage.wished_for_card_value.as_ref(),
+                            is_users_turn,
+                        ) {
+                            return Err(TichuError::MustFulfillWish);
+                        }
+
+                        // any play containing the wished-for card fulfills the wish, so erase it
                         if let Some(wished_for_card) = &new_play_stage.wished_for_card_value {
-                            let user = new_game_state
-                                .participants
-                                .iter()
-                                .find(|user| user.user_id == user_id);
-                            if user.is_some() {
-                                let user_can_play_wish = get_user_can_play_wished_for_card(
-                                    prev_combo,
-                                    &next_cards,
-                                    wished_for_card,
-                                );
-
-                                if user_can_play_wish {
-                                    let combo_contains_wish = next_combo
-                                        .cards()
-                                        .iter()
-                                        .any(|card| card.value == *wished_for_card);
-
-                                    if combo_contains_wish {
-                                        // player is playing wish, so erase wished-for card
-                                        new_play_stage.wished_for_card_value = None;
-                                    } else {
-                                        return Err(format!(
-                                            "Couldn't accept card play submitted by user {} because user can play wished-for card but didn't",
-                                            user_id
-                                        ));
-                                    }
-                                }
-                            } else {
-                                return Err(format!(
-                                    "Couldn't accept card play submitted by user {} because user couldn't be found in participants",
-                                    user_id
-                                ));
+                            if get_combo_fulfills_wish(&next_combo, wished_for_card) {
+                                new_play_stage.wished_for_card_value = None;
                             }
                         }
 
@@ -1206,9 +1871,10 @@ impl PrivateGameState {
                         new_current_user.has_played_first_card = true;
 
                         // clear played cards from user's hand
+                        let played_cards = CardSet::from(&next_cards);
                         new_current_user
                             .hand
-                            .retain(|card| !next_cards.contains(card));
+                            .retain(|card| !played_cards.contains(card));
 
                         // if user is out of cards, remove user from users_in_play
                         if new_current_user.hand.is_empty() {
@@ -1222,6 +1888,24 @@ impl PrivateGameState {
                                 .retain(|user_id_in_play| *user_id_in_play != user_id)
                         }
 
+                        // if the round is over, the trick is won with a separate TrickWon event,
+                        // unless it was won with the Dragon, which must first be given away
+                        let only_one_team_is_in_play = new_play_stage.teams.iter().any(|team| {
+                            new_play_stage
+                                .users_in_play
+                                .iter()
+                                .all(|user_id_in_play| team.user_ids.contains(user_id_in_play))
+                        });
+                        let round_is_over = new_play_stage.users_in_play.len() <= 1
+                            || (new_play_stage.users_in_play.len() == 2
+                                && only_one_team_is_in_play);
+                        if round_is_over {
+                            if next_cards.contains(&DRAGON) {
+                                new_play_stage.dragon_winner_user_id = Some(user_id.to_owned());
+                            }
+                            return Ok(new_game_state);
+                        }
+
                         // if user played Dog, then turn moves to teammate (or next player after that)
                         if next_cards.len() == 1 && next_cards.contains(&DOG) {
                             // find teammate user_id
@@ -1231,67 +1915,42 @@ impl PrivateGameState {
                             let next_user_id = new_play_stage
                                 .get_next_turn_user_id_starting_with_user_id(teammate_user_id)
                                 .ok_or_else(|| {
-                                    format!("Couldn't find next user id: {:#?}", new_play_stage)
+                                    TichuError::Internal(format!(
+                                        "Couldn't find next user id: {:#?}",
+                                        new_play_stage
+                                    ))
                                 })?;
                             new_play_stage.turn_user_id = next_user_id.clone();
                             return Ok(new_game_state);
                         }
 
-                        // if user played a dragon, save who they want to give it to if they win
-                        if next_cards.contains(&DRAGON) {
-                            if let Some(user_id_to_give_dragon_to) = user_id_to_give_dragon_to {
-                                new_play_stage
-                                    .user_id_to_give_dragon_to
-                                    .replace(user_id_to_give_dragon_to);
-                            } else {
-                                return Err(format!(
-                                    "Couldn't accept card play submitted by user {} because user didn't choose a user to receive the dragon",
-                                    user_id
-                                ));
-                            }
-                        }
-
                         // if user played mahjong and has wished for a card, save it
                         let user_played_mah_jong = next_cards.contains(&MAH_JONG);
                         if user_played_mah_jong {
                             new_play_stage.wished_for_card_value = wished_for_card_value;
                         }
 
-                        // check if round is over
-                        if self.get_round_is_over() {
-                            return new_game_state.round_over();
-                        }
-
                         // if we've gotten this far
                         // there should always be users left in play, so move to the next user
                         let next_user_id =
                             new_play_stage.get_next_turn_user_id().ok_or_else(|| {
-                                format!("Couldn't find next user id: {:#?}", new_play_stage)
+                                TichuError::Internal(format!(
+                                    "Couldn't find next user id: {:#?}",
+                                    new_play_stage
+                                ))
                             })?;
                         new_play_stage.turn_user_id = next_user_id.clone();
                     } else {
-                        return Err(format!(
-                            "Couldn't accept card play submitted by user {} because combo does not beat combo on the table",
-                            user_id
-                        ));
+                        return Err(TichuError::ComboDoesNotBeatTable);
                     }
                 } else {
-                    return Err(format!(
-                        "Couldn't accept card play submitted by user {} because it is not the user's turn",
-                        user_id
-                    ));
+                    return Err(TichuError::NotYourTurn);
                 }
             } else {
-                return Err(format!(
-                    "Couldn't accept card play submitted by user {} because cards are not a valid combination",
-                    user_id
-                ));
+                return Err(TichuError::InvalidCombo);
             }
         } else {
-            return Err(format!(
-                "Couldn't accept card play submitted by user {} because Game Stage is not Play",
-                user_id
-            ));
+            return Err(TichuError::WrongStage);
         }
 
         Ok(new_game_state)
@@ -1306,6 +1965,15 @@ pub struct PublicGameState {
     pub stage: PublicGameStage,
     pub participants: Vec<PublicUser>,
 
+    /// House rules the game is played with
+    pub rules: GameRules,
+
+    /// When the users the game is waiting on run out of time to act (in ms since the Unix epoch)
+    pub turn_deadline: Option<u64>,
+
+    /// Breakdown of every round finished so far
+    pub round_summaries: Vec<RoundSummary>,
+
     // unique to PublicGameState:
     pub current_user: PrivateUser,
 }
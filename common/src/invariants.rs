@@ -0,0 +1,255 @@
+use crate::{
+    Card, GameEvent, PrivateGameStage, PrivateGameState, PrivatePlay, DRAGON, TOTAL_CARDS,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Something about a game state that should never be true, no matter what was requested.
+///
+/// Any of these means there is a bug in the engine, not in the request.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum InvariantViolation {
+    /// The same card is in more than one place at once
+    DuplicateCard(Card),
+
+    /// Cards were lost or created. During a round, all 56 cards are split between the deck,
+    /// the cards being traded, hands, the table and tricks (or none have been dealt yet).
+    /// Outside of a round, nobody holds any cards.
+    WrongNumberOfCards { expected: usize, found: usize },
+
+    /// The users on the teams aren't exactly the participants of the game
+    TeamsDontMatchParticipants,
+
+    /// `passes` doesn't have exactly one entry for every participant
+    PassesDontMatchParticipants,
+
+    /// `users_in_play` isn't exactly the users who still have cards
+    UsersInPlayDontMatchHands,
+
+    /// It's the turn of a user who is out of cards, even though the round isn't over
+    TurnUserNotInPlay(String),
+
+    /// A user is choosing who to give a trick to, but the trick wasn't won with the Dragon
+    DragonWinnerWithoutDragon(String),
+
+    /// A user has more than one trade submitted in the same round
+    DuplicateTradeSender(String),
+
+    /// A pass was accepted from a user whose turn it wasn't
+    PassOutOfTurn(String),
+}
+
+impl PrivateGameState {
+    /// Checks everything that must hold true for any game state the engine produces.
+    ///
+    /// Runs after every event in debug builds.
+    pub fn check_invariants(&self) -> Result<(), Vec<InvariantViolation>> {
+        let mut violations = self.get_card_violations();
+
+        let team_user_ids = match &self.stage {
+            PrivateGameStage::Lobby => None,
+            PrivateGameStage::Teams(teams) => Some(
+                teams
+                    .iter()
+                    .flat_map(|team| team.user_ids.iter())
+                    .collect::<Vec<&String>>(),
+            ),
+            PrivateGameStage::GrandTichu(grand_tichu_state) => Some(
+                grand_tichu_state
+                    .teams
+                    .iter()
+                    .flat_map(|team| team.user_ids.iter())
+                    .collect(),
+            ),
+            PrivateGameStage::Trade(trade_state) => Some(
+                trade_state
+                    .teams
+                    .iter()
+                    .flat_map(|team| team.user_ids.iter())
+                    .collect(),
+            ),
+            PrivateGameStage::Play(play_state) => Some(
+                play_state
+                    .teams
+                    .iter()
+                    .flat_map(|team| team.user_ids.iter())
+                    .collect(),
+            ),
+            PrivateGameStage::Score(score) => Some(
+                score
+                    .teams
+                    .iter()
+                    .flat_map(|team| team.user_ids.iter())
+                    .collect(),
+            ),
+        };
+        if let Some(team_user_ids) = team_user_ids {
+            if !self.is_every_participant_once(team_user_ids) {
+                violations.push(InvariantViolation::TeamsDontMatchParticipants);
+            }
+        }
+
+        if let PrivateGameStage::Trade(trade_state) = &self.stage {
+            let mut senders: Vec<&String> = Vec::new();
+            for trade in trade_state.trades.iter().flatten() {
+                let sender = &trade[0].from_user_id;
+                if senders.contains(&sender) {
+                    violations.push(InvariantViolation::DuplicateTradeSender(sender.clone()));
+                }
+                senders.push(sender);
+            }
+        }
+
+        if let PrivateGameStage::Play(play_state) = &self.stage {
+            violations.append(&mut self.get_play_violations(play_state));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Checks that an event the engine accepted was allowed in this game state.
+    ///
+    /// Runs along with `check_invariants` for every event in debug builds.
+    pub fn check_event_invariants(&self, event: &GameEvent) -> Result<(), Vec<InvariantViolation>> {
+        let mut violations = Vec::new();
+
+        if let (GameEvent::Passed { user_id }, PrivateGameStage::Play(play_state)) =
+            (event, &self.stage)
+        {
+            if play_state.turn_user_id != *user_id {
+                violations.push(InvariantViolation::PassOutOfTurn(user_id.clone()));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    fn get_card_violations(&self) -> Vec<InvariantViolation> {
+        let mut cards: Vec<&Card> = Vec::new();
+        for participant in self.participants.iter() {
+            cards.extend(participant.hand.iter());
+            for combo in participant.tricks.iter() {
+                cards.extend(combo.cards().iter());
+            }
+        }
+
+        let is_in_round = match &self.stage {
+            PrivateGameStage::Lobby | PrivateGameStage::Teams(_) | PrivateGameStage::Score(_) => {
+                false
+            }
+            PrivateGameStage::GrandTichu(grand_tichu_state) => {
+                cards.extend(grand_tichu_state.deck.0.iter());
+                true
+            }
+            PrivateGameStage::Trade(trade_state) => {
+                cards.extend(trade_state.deck.0.iter());
+                for trade in trade_state.trades.iter().flatten() {
+                    cards.extend(trade.iter().map(|card_trade| &card_trade.card));
+                }
+                true
+            }
+            PrivateGameStage::Play(play_state) => {
+                for combo in play_state.table.iter() {
+                    cards.extend(combo.cards().iter());
+                }
+                true
+            }
+        };
+
+        let mut violations = Vec::new();
+
+        // the next round's cards are dealt with their own event
+        let expected = if is_in_round && !cards.is_empty() {
+            TOTAL_CARDS
+        } else {
+            0
+        };
+        if cards.len() != expected {
+            violations.push(InvariantViolation::WrongNumberOfCards {
+                expected,
+                found: cards.len(),
+            });
+        }
+
+        let mut seen_cards = BTreeSet::new();
+        for card in cards {
+            if !seen_cards.insert(card) {
+                violations.push(InvariantViolation::DuplicateCard(card.clone()));
+            }
+        }
+
+        violations
+    }
+
+    fn get_play_violations(&self, play_state: &PrivatePlay) -> Vec<InvariantViolation> {
+        let mut violations = Vec::new();
+
+        if !self.is_every_participant_once(play_state.passes.iter().map(|pass| &pass.user_id)) {
+            violations.push(InvariantViolation::PassesDontMatchParticipants);
+        }
+
+        let users_with_cards: BTreeSet<&String> = self
+            .participants
+            .iter()
+            .filter(|participant| !participant.hand.is_empty())
+            .map(|participant| &participant.user_id)
+            .collect();
+        let users_in_play: BTreeSet<&String> = play_state.users_in_play.iter().collect();
+        if users_in_play.len() != play_state.users_in_play.len()
+            || users_in_play != users_with_cards
+        {
+            violations.push(InvariantViolation::UsersInPlayDontMatchHands);
+        }
+
+        // when the last card of the round is played, the turn doesn't move on
+        let round_is_over = users_in_play.len() <= 1
+            || play_state.teams.iter().any(|team| {
+                users_in_play
+                    .iter()
+                    .all(|user_id| team.user_ids.contains(user_id))
+            });
+        if !round_is_over && !users_in_play.contains(&play_state.turn_user_id) {
+            violations.push(InvariantViolation::TurnUserNotInPlay(
+                play_state.turn_user_id.clone(),
+            ));
+        }
+
+        if let Some(dragon_winner_user_id) = &play_state.dragon_winner_user_id {
+            let trick_was_won_with_dragon = play_state
+                .table
+                .last()
+                .is_some_and(|combo| combo.cards().contains(&DRAGON));
+            if !trick_was_won_with_dragon {
+                violations.push(InvariantViolation::DragonWinnerWithoutDragon(
+                    dragon_winner_user_id.clone(),
+                ));
+            }
+        }
+
+        violations
+    }
+
+    fn is_every_participant_once<'a>(
+        &self,
+        user_ids: impl IntoIterator<Item = &'a String>,
+    ) -> bool {
+        let mut user_ids: Vec<&String> = user_ids.into_iter().collect();
+        let mut participant_user_ids: Vec<&String> = self
+            .participants
+            .iter()
+            .map(|participant| &participant.user_id)
+            .collect();
+        user_ids.sort();
+        participant_user_ids.sort();
+
+        user_ids == participant_user_ids
+    }
+}
@@ -1,9 +1,16 @@
 #[cfg(feature = "client")]
 extern crate js_sys;
 extern crate rand;
+mod admin;
+mod bot;
+mod card_set;
 mod cards;
+mod errors;
+mod game_event;
+mod game_rules;
 mod game_stage;
 mod game_state;
+mod invariants;
 mod messages;
 mod play;
 mod score;
@@ -13,9 +20,16 @@ mod trade;
 mod user;
 mod utils;
 
+pub use admin::*;
+pub use bot::*;
+pub use card_set::*;
 pub use cards::*;
+pub use errors::*;
+pub use game_event::*;
+pub use game_rules::*;
 pub use game_stage::*;
 pub use game_state::*;
+pub use invariants::*;
 pub use messages::*;
 pub use play::*;
 pub use score::*;
@@ -27,3 +41,6 @@ pub use utils::*;
 
 #[cfg(all(feature = "client", feature = "server"))]
 compile_error!("feature \"client\" and feature \"server\" cannot be enabled at the same time");
+
+#[cfg(not(any(feature = "client", feature = "server")))]
+compile_error!("either feature \"client\" or feature \"server\" must be enabled");
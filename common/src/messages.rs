@@ -1,13 +1,18 @@
 use crate::{
-    CallGrandTichuRequest, Card, CardValue, PublicGameStage, PublicGameState, SubmitTrade,
-    TeamOption,
+    AdminCommand, BotDifficulty, CallGrandTichuRequest, Card, CardValue, GameRules,
+    PublicGameStage, PublicGameState, SubmitTrade, TeamOption, TichuError,
 };
 use serde::{Deserialize, Serialize};
 
 /// All possible Server-to-Client Websocket Messages
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum STCMsg {
-    UserIdAssigned(String),
+    /// A new user_id, along with the secret session token needed to reconnect as that user.
+    /// The token must never be shown to other users.
+    UserIdAssigned {
+        user_id: String,
+        session_token: String,
+    },
     GameCreated {
         game_id: String,
         game_code: String,
@@ -27,6 +32,9 @@ pub enum STCMsg {
     UserMovedToTeamA(String),
     UserMovedToTeamB(String),
 
+    /// The owner changed the house rules
+    RulesUpdated(GameRules),
+
     /// completely left game--not coming back.
     /// For now, this can only occur in the lobby.
     UserLeft(String),
@@ -44,7 +52,13 @@ pub enum STCMsg {
     /// after all submitted
     CardsPlayed,
     UserPassed(String),
-    PlayerReceivedDragon,
+
+    /// User won a trick with the Dragon and must now choose an opponent to give it to
+    DragonTrickWon(String),
+    PlayerReceivedDragon {
+        from_user_id: String,
+        to_user_id: String,
+    },
 
     /// Temporary end game: show scores, etc.
     /// Users can restart if they want to play another game.
@@ -65,6 +79,19 @@ pub enum STCMsg {
 
     /// User previously disconnected, but now reconnected.
     UserReconnected(String),
+
+    /// A request from this user was not carried out.
+    /// `request` is None if the message couldn't be deserialized.
+    ActionRejected {
+        request: Option<CTSMsgKind>,
+        reason: TichuError,
+    },
+
+    /// The server is about to stop, e.g. to restart for a deploy.
+    /// Clients should wait at least `reconnect_after_ms` before reconnecting.
+    ServerShuttingDown {
+        reconnect_after_ms: u64,
+    },
 }
 
 /// All possible Client-to-Server Websocket Messages
@@ -85,6 +112,11 @@ pub enum CTSMsg {
 
     /// User can only explicitly leave game when in the lobby
     LeaveGame,
+
+    /// Owner fills an empty seat in the lobby with a bot
+    AddBot {
+        difficulty: BotDifficulty,
+    },
     MoveToTeam(TeamOption),
 
     RenameTeam {
@@ -92,6 +124,9 @@ pub enum CTSMsg {
         team_name: String,
     },
 
+    /// Owner changes the house rules in the Lobby or Teams stage
+    UpdateRules(GameRules),
+
     /// Move from Teams stage to Grand Tichu stage
     StartGrandTichu,
 
@@ -105,11 +140,15 @@ pub enum CTSMsg {
     PlayCards {
         cards: Vec<Card>,
         wished_for_card_value: Option<CardValue>,
-        user_id_to_give_dragon_to: Option<String>,
     },
 
-    /// Admin message: skip to play stage
-    __AdminSkipToPlay,
+    /// After winning a trick with the Dragon, give it to an opponent
+    GiveDragon {
+        user_id: String,
+    },
+
+    /// Set up the game by hand while testing. Rejected unless the user connected as an admin.
+    Admin(AdminCommand),
 
     Pass,
 
@@ -117,3 +156,51 @@ pub enum CTSMsg {
     Pong,
     Test(String),
 }
+
+/// The kind of a Client-to-Server Websocket Message, without its data
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum CTSMsgKind {
+    JoinGameWithGameCode,
+    CreateGame,
+    LeaveGame,
+    AddBot,
+    MoveToTeam,
+    RenameTeam,
+    UpdateRules,
+    StartGrandTichu,
+    CallGrandTichu,
+    CallSmallTichu,
+    SubmitTrade,
+    PlayCards,
+    GiveDragon,
+    Admin,
+    Pass,
+    Ping,
+    Pong,
+    Test,
+}
+
+impl From<&CTSMsg> for CTSMsgKind {
+    fn from(msg: &CTSMsg) -> Self {
+        match msg {
+            CTSMsg::JoinGameWithGameCode { .. } => CTSMsgKind::JoinGameWithGameCode,
+            CTSMsg::CreateGame { .. } => CTSMsgKind::CreateGame,
+            CTSMsg::LeaveGame => CTSMsgKind::LeaveGame,
+            CTSMsg::AddBot { .. } => CTSMsgKind::AddBot,
+            CTSMsg::MoveToTeam(_) => CTSMsgKind::MoveToTeam,
+            CTSMsg::RenameTeam { .. } => CTSMsgKind::RenameTeam,
+            CTSMsg::UpdateRules(_) => CTSMsgKind::UpdateRules,
+            CTSMsg::StartGrandTichu => CTSMsgKind::StartGrandTichu,
+            CTSMsg::CallGrandTichu(_) => CTSMsgKind::CallGrandTichu,
+            CTSMsg::CallSmallTichu => CTSMsgKind::CallSmallTichu,
+            CTSMsg::SubmitTrade(_) => CTSMsgKind::SubmitTrade,
+            CTSMsg::PlayCards { .. } => CTSMsgKind::PlayCards,
+            CTSMsg::GiveDragon { .. } => CTSMsgKind::GiveDragon,
+            CTSMsg::Admin(_) => CTSMsgKind::Admin,
+            CTSMsg::Pass => CTSMsgKind::Pass,
+            CTSMsg::Ping => CTSMsgKind::Ping,
+            CTSMsg::Pong => CTSMsgKind::Pong,
+            CTSMsg::Test(_) => CTSMsgKind::Test,
+        }
+    }
+}
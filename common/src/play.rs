@@ -1,6 +1,6 @@
 use crate::{
-    CardValue, GetSmallTichu, ImmutableTeam, ImmutableTeams, PrivateTrade, SmallTichuArray,
-    TeamCategories, ValidCardCombo,
+    CardValue, GameRules, GetSmallTichu, ImmutableTeam, ImmutableTeams, PrivateTrade,
+    SmallTichuArray, TeamCategories, TichuCallStatus, ValidCardCombo,
 };
 use serde::{Deserialize, Serialize};
 
@@ -19,7 +19,9 @@ pub struct PrivatePlay {
     pub table: Vec<ValidCardCombo>,
     pub turn_user_id: String,
 
-    pub user_id_to_give_dragon_to: Option<String>,
+    /// Set when a user wins a trick with the Dragon: play is paused until
+    /// this user chooses an opponent to give the trick to
+    pub dragon_winner_user_id: Option<String>,
     pub wished_for_card_value: Option<CardValue>,
     pub passes: [PassWithUserId; 4],
 
@@ -59,7 +61,7 @@ impl From<PrivateTrade> for PrivatePlay {
             // this value is set in game state on transition
             turn_user_id: String::from(""),
             wished_for_card_value: None,
-            user_id_to_give_dragon_to: None,
+            dragon_winner_user_id: None,
             passes,
             users_in_play,
             first_user_out: None,
@@ -160,6 +162,26 @@ impl PrivatePlay {
         self.get_next_turn_user_id_after_user_id(&self.turn_user_id)
     }
 
+    /// Settles all Small Tichu and Grand Tichu calls at the end of the round.
+    ///
+    /// Small Tichus are worth ±100 points and Grand Tichus are worth ±200 points (unless the rules
+    /// say otherwise) to the caller's team, depending on whether the caller went out first.
+    pub fn settle_tichu_calls(&mut self, rules: &GameRules) {
+        let first_user_out = self.first_user_out.as_ref();
+        settle_tichu_call_statuses(
+            &mut self.small_tichus,
+            &mut self.teams,
+            first_user_out,
+            rules.small_tichu_value,
+        );
+        settle_tichu_call_statuses(
+            &mut self.grand_tichus,
+            &mut self.teams,
+            first_user_out,
+            rules.grand_tichu_value,
+        );
+    }
+
     pub fn get_teammate_of_user_id(&self, user_id: &str) -> Option<&String> {
         self.teams
             .iter()
@@ -176,6 +198,36 @@ impl PrivatePlay {
     }
 }
 
+/// Moves every `Called` status to `Achieved` or `Failed` and adjusts the caller's team score.
+///
+/// A call is only achieved if the user who made it was the first user to go out.
+fn settle_tichu_call_statuses(
+    tichus: &mut SmallTichuArray,
+    teams: &mut ImmutableTeams,
+    first_user_out: Option<&String>,
+    value: i32,
+) {
+    for user_call_status in tichus.iter_mut() {
+        if user_call_status.tichu_call_status != TichuCallStatus::Called {
+            continue;
+        }
+
+        let achieved = first_user_out == Some(&user_call_status.user_id);
+        let team = teams
+            .iter_mut()
+            .find(|team| team.user_ids.contains(&user_call_status.user_id))
+            .expect("User who called Tichu should be on a team");
+
+        if achieved {
+            user_call_status.tichu_call_status = TichuCallStatus::Achieved;
+            team.score += value;
+        } else {
+            user_call_status.tichu_call_status = TichuCallStatus::Failed;
+            team.score -= value;
+        }
+    }
+}
+
 impl GetSmallTichu for PrivatePlay {
     fn get_small_tichu(&self) -> &SmallTichuArray {
         &self.small_tichus
@@ -198,6 +250,10 @@ pub struct PublicPlay {
     /// Users who have not run out of cards: in turn order
     pub users_in_play: Vec<String>,
     pub wished_for_card_value: Option<CardValue>,
+    /// Set while waiting for the winner of a Dragon trick to give it to an opponent
+    pub dragon_winner_user_id: Option<String>,
+    /// The first user to run out of cards this round
+    pub first_user_out: Option<String>,
 }
 
 impl GetSmallTichu for PublicPlay {
@@ -221,6 +277,8 @@ impl From<PrivatePlay> for PublicPlay {
             passes: private_play.passes,
             users_in_play: private_play.users_in_play,
             wished_for_card_value: private_play.wished_for_card_value,
+            dragon_winner_user_id: private_play.dragon_winner_user_id,
+            first_user_out: private_play.first_user_out,
         }
     }
 }
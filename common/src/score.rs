@@ -1,16 +1,77 @@
-use crate::{ImmutableTeams, PrivatePlay};
+use crate::{ImmutableTeams, PrivatePlay, SmallTichuArray};
 use serde::{Deserialize, Serialize};
 
 /// Only public score is needed, since there is no sensitive information included (such as hand, etc.)
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct Score {
     pub teams: ImmutableTeams,
+
+    /// Settled Small Tichu calls from the final round (Achieved / Failed)
+    pub small_tichus: SmallTichuArray,
+
+    /// Settled Grand Tichu calls from the final round (Achieved / Failed)
+    pub grand_tichus: SmallTichuArray,
+
+    /// Breakdown of every round played, in the order they were played
+    pub round_summaries: Vec<RoundSummary>,
 }
 
 impl From<PrivatePlay> for Score {
     fn from(private_play: PrivatePlay) -> Self {
         Score {
             teams: private_play.teams,
+            small_tichus: private_play.small_tichus,
+            grand_tichus: private_play.grand_tichus,
+            round_summaries: Vec::new(),
         }
     }
 }
+
+/// Points a single team earned in one round
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct TeamRoundScore {
+    pub team_id: String,
+
+    /// Points from cards won in tricks (always 0 in a double victory)
+    pub card_points: i32,
+
+    /// Points awarded for going out first and second
+    pub double_victory_points: i32,
+
+    /// Points won or lost from Small Tichu and Grand Tichu calls
+    pub tichu_points: i32,
+
+    /// The team's total score after this round
+    pub running_total: i32,
+}
+
+impl TeamRoundScore {
+    /// Every point the team gained or lost this round
+    pub fn round_total(&self) -> i32 {
+        self.card_points + self.double_victory_points + self.tichu_points
+    }
+}
+
+/// Record of how a finished round was scored
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct RoundSummary {
+    /// Starts at 1 for the first round of the game
+    pub round_number: usize,
+
+    /// In the same order as the game's teams
+    pub teams: [TeamRoundScore; 2],
+
+    /// Team that went out first and second, if any
+    pub double_victory_team_id: Option<String>,
+
+    /// Settled Small Tichu calls (Achieved / Failed)
+    pub small_tichus: SmallTichuArray,
+
+    /// Settled Grand Tichu calls (Achieved / Failed)
+    pub grand_tichus: SmallTichuArray,
+
+    pub first_user_out: Option<String>,
+
+    /// User left holding cards at the end of the round (None in a double victory)
+    pub last_user_out: Option<String>,
+}
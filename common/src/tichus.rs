@@ -1,6 +1,12 @@
 use crate::{Deck, ImmutableTeams};
 use serde::{Deserialize, Serialize};
 
+/// Points won (or lost) by a team when one of its members calls Small Tichu
+pub const SMALL_TICHU_VALUE: i32 = 100;
+
+/// Points won (or lost) by a team when one of its members calls Grand Tichu
+pub const GRAND_TICHU_VALUE: i32 = 200;
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum TichuCallStatus {
     /// User has not called one way or the other yet
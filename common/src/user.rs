@@ -1,4 +1,4 @@
-use crate::{Card, ValidCardCombo};
+use crate::{BotDifficulty, Card, ValidCardCombo};
 use serde::{Deserialize, Serialize};
 
 /// Placeholder for when a user has not been assigned an id yet
@@ -21,6 +21,9 @@ pub struct PublicUser {
     pub display_name: String,
     pub tricks: Vec<ValidCardCombo>,
     pub has_played_first_card: bool,
+
+    /// Set if this seat is filled by a bot rather than a person
+    pub bot_difficulty: Option<BotDifficulty>,
 }
 
 impl From<PrivateUser> for PublicUser {
@@ -31,6 +34,7 @@ impl From<PrivateUser> for PublicUser {
             role: private_user.role.clone(),
             tricks: private_user.tricks.clone(),
             user_id: private_user.user_id,
+            bot_difficulty: private_user.bot_difficulty,
         }
     }
 }
@@ -43,6 +47,7 @@ impl From<&PrivateUser> for PublicUser {
             role: private_user.role.clone(),
             tricks: private_user.tricks.clone(),
             user_id: private_user.user_id.clone(),
+            bot_difficulty: private_user.bot_difficulty.clone(),
         }
     }
 }
@@ -57,6 +62,15 @@ pub struct PrivateUser {
     pub tricks: Vec<ValidCardCombo>,
     pub has_played_first_card: bool,
     pub hand: Vec<Card>,
+
+    /// Set if this seat is filled by a bot rather than a person
+    pub bot_difficulty: Option<BotDifficulty>,
+}
+
+impl PrivateUser {
+    pub fn is_bot(&self) -> bool {
+        self.bot_difficulty.is_some()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
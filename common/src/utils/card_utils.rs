@@ -1,89 +1,71 @@
-use crate::{BombOf4, Card, CardSuit, CardValue, FullHouse, MAX_CARDS_IN_HAND, Pair, Sequence, SequenceBomb, SequenceOfPairs, Single, Trio, ValidCardCombo};
-use itertools::Itertools;
-
+use crate::{BombOf4, Card, CardSet, CardSuit, CardValue, CARD_VALUE_MAX, CARD_VALUE_MIN, FullHouse, MAX_CARDS_IN_HAND, Pair, PHOENIX, Sequence, SequenceBomb, SequenceOfPairs, Single, TichuError, Trio, ValidCardCombo};
+
+
+/// What a set of cards can be played as, before the cards are attributed to a user
+enum ComboShape {
+    Single(CardValue),
+    Pair(CardValue),
+    Trio(CardValue),
+    BombOf4(CardValue),
+    FullHouse(CardValue),
+    Sequence { starting_value: CardValue, number_of_cards: u8 },
+    SequenceBomb { starting_value: CardValue, number_of_cards: u8, suit: CardSuit },
+    SequenceOfPairs { starting_value: CardValue, number_of_pairs: u8 },
+}
 
 pub fn get_card_combination(prev_combo: Option<&ValidCardCombo>, cards: &Vec<Card>, user_id_who_played_cards: &str) -> Option<ValidCardCombo> {
-    let original_cards = cards;
-    let mut cards = cards.clone();
-    let mut includes_phoenix = false;
-    let mut includes_non_phoenix_special_card = false;
-    let mut value_out_of_range = false;
-    let mut identical_cards_found = false;
-    let user_id =  user_id_who_played_cards.to_owned();
-
-    sort_cards_for_hand(&mut cards);
-
-    // rule out obvious invalid combinations and cards
-    for (i, card) in cards.iter().enumerate() {
-        // identical cards
-        if i > 0 {
-            let prev_card = &cards[i - 1];
-            if prev_card.suit == card.suit && prev_card.value == card.value {
-                identical_cards_found = true;
-                break;
-            }
-        }
-
-        // invalid combination of suit and value
-        if card.value > CardValue::max() 
+    // invalid combination of suit and value
+    let value_out_of_range = cards.iter().any(|card| {
+        card.value > CardValue::max()
         || (card.suit.is_special() && card.value != CardValue::noop())
-        || (!card.suit.is_special() && card.value < CardValue::min()) {
-            value_out_of_range = true;
-            break;
-        }
+        || (!card.suit.is_special() && card.value < CardValue::min())
+    });
 
-        // find phoenix
-        if card.suit == CardSuit::Phoenix {
-            includes_phoenix = true;
-        }
+    // cannot play more than is possible to hold in your hand
+    if cards.len() > MAX_CARDS_IN_HAND || value_out_of_range {
+        return None;
+    }
 
-        // find non-phoenix special cards
-        if card.suit == CardSuit::MahJong
-            || card.suit == CardSuit::Dog
-            || card.suit == CardSuit::Dragon
-        {
-            includes_non_phoenix_special_card = true;
-        }
+    let card_set: CardSet = cards.iter().collect();
+
+    // cannot have 2 of the EXACT same card
+    if card_set.len() != cards.len() {
+        return None;
     }
 
+    let combo_shape = get_combo_shape(prev_combo, card_set)?;
+    Some(to_valid_card_combo(combo_shape, cards.clone(), user_id_who_played_cards.to_owned()))
+}
+
+/// Like `get_card_combination`, but for a CardSet: the combo's cards are listed in CardSet order
+pub fn get_card_set_combination(prev_combo: Option<&ValidCardCombo>, card_set: CardSet, user_id_who_played_cards: &str) -> Option<ValidCardCombo> {
+    let combo_shape = get_combo_shape(prev_combo, card_set)?;
+    Some(to_valid_card_combo(combo_shape, card_set.to_cards(), user_id_who_played_cards.to_owned()))
+}
+
+fn get_combo_shape(prev_combo: Option<&ValidCardCombo>, card_set: CardSet) -> Option<ComboShape> {
+    let number_of_cards = card_set.len();
+
     // cannot play an empty combination
-    if cards.is_empty() 
-    // cannot play more than is possible to hold in your hand
-    || cards.len() > MAX_CARDS_IN_HAND 
-    // Non-Phoenix special cards cannot be played in combination
-    || includes_non_phoenix_special_card && cards.len() > 1  
-    // invalid combination of suit and value
-    || value_out_of_range
-    // cannot have 2 of the EXACT same card
-    || identical_cards_found
-    {
+    if number_of_cards == 0 || number_of_cards > MAX_CARDS_IN_HAND {
         return None;
     }
 
     // length 1: a single card
-    if cards.len() == 1 {
-        let card = cards.get(0).unwrap();
+    if number_of_cards == 1 {
+        let card = card_set.iter().next().unwrap();
         if card.suit == CardSuit::Phoenix {
             // since 1/2 values aren't possible with the Phoenix, new value should be equal
             // (which functions the same in the end)
             return if let Some(prev_combo) = prev_combo {
                 if let ValidCardCombo::Single(prev_single) = prev_combo {
                     match prev_single.cards.first().expect("Every single card should have a Vec of cards").suit {
-                        // lowest possible card
-                        CardSuit::Dog | CardSuit::MahJong => Some(ValidCardCombo::Single(Single{
-                            cards: vec![card.clone()],
-                            // any card can beat the Phoenix when it's led
-                            value: CardValue::min().minus(1),
-                            user_id,
-                        })),
+                        // lowest possible card: any card can beat the Phoenix when it's led
+                        CardSuit::Dog | CardSuit::MahJong => Some(ComboShape::Single(CardValue::min().minus(1))),
                         // invalid play
                         CardSuit::Dragon | CardSuit::Phoenix=> None,
                         // copy the value of the previous Single
-                        _ =>  Some(ValidCardCombo::Single(Single{
-                            cards: vec![card.clone()],
-                            value: prev_single.value.clone(),
-                            user_id,
-                        })),
+                        _ => Some(ComboShape::Single(prev_single.value.clone())),
                     }
                 } else {
                     // invalid prev_combo
@@ -91,311 +73,126 @@ pub fn get_card_combination(prev_combo: Option<&ValidCardCombo>, cards: &Vec<Car
                 }
             } else {
                 // no previous value was played: play the least possible value card
-                Some(ValidCardCombo::Single(Single{
-                    cards: vec![card.clone()],
-                    // any card can beat the Phoenix when it's led
-                    value: CardValue::min().minus(1),
-                    user_id,
-                }))
-            }
-        }
-
-        return Some(ValidCardCombo::Single(Single{
-            cards: vec![card.clone()],
-            value: card.value.clone(),
-            user_id,
-        }));
-    }
-
-    if cards.len() == 2 {
-        // standard pair
-        if let [card_0, card_1] = &cards[..cards.len()] {
-            return if card_0.value == card_1.value {
-                Some(ValidCardCombo::Pair(Pair {
-                    cards: original_cards.clone(),
-                    value: card_0.value.clone(),
-                    user_id,
-                }))
+                // (any card can beat the Phoenix when it's led)
+                Some(ComboShape::Single(CardValue::min().minus(1)))
             }
-            // pair with 1 standard card and 1 Phoenix
-            else if includes_phoenix {
-                let std_card = cards.iter().find(|card| card.suit != CardSuit::Phoenix);
-                Some(ValidCardCombo::Pair(Pair {
-                    cards: original_cards.clone(),
-                    value: std_card.unwrap().value.clone(),
-                    user_id,
-                }))
-            } else {
-                None
-            };
         }
 
-        return None;
+        return Some(ComboShape::Single(card.value));
     }
 
-    if cards.len() == 3 {
-        // trio of equal rank
-        if let [card_0, card_1, card_2] = &cards[..cards.len()] {
-            return if card_0.value == card_1.value && card_1.value == card_2.value {
-                Some(ValidCardCombo::Trio(Trio {
-                    cards: original_cards.clone(),
-                    value: card_0.value.clone(),
-                    user_id,
-                }))
-            } else if includes_phoenix {
-                let std_cards: Vec<&Card> = cards
-                    .iter()
-                    .filter(|card| card.suit != CardSuit::Phoenix)
-                    .collect();
-                if let [std_card_0, std_card_1] = &std_cards[0..std_cards.len()] {
-                    if std_card_0.value == std_card_1.value {
-                        Some(ValidCardCombo::Trio(Trio {
-                            cards: original_cards.clone(),
-                            value: std_card_0.value.clone(),
-                            user_id,
-                        }))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-        }
-
+    // Non-Phoenix special cards cannot be played in combination
+    let includes_phoenix = card_set.contains(&PHOENIX);
+    let standard_cards = card_set - CardSet::special_cards();
+    if standard_cards.len() + usize::from(includes_phoenix) != number_of_cards {
         return None;
     }
 
-    if cards.len() == 4 {
-        // a bomb (4 of the same)
-        if let [card_0, card_1, card_2, card_3] = &cards[..cards.len()] {
-            if card_0.value == card_1.value
-                && card_1.value == card_2.value
-                && card_2.value == card_3.value
-            {
-                return Some(ValidCardCombo::BombOf4(BombOf4 {
-                    cards: original_cards.clone(),
-                    value: card_0.value.clone(),
-                    user_id,
-                }));
-            }
-
-            // sequence of pairs is analyzed later
-        }
-    }
-
-    if cards.len() == 5 {
-        // manipulate locally
-        let mut adjusted_cards = cards.clone();
-
-        // replace Phoenix with a standard card
-       if includes_phoenix {
-            let mut cards_without_phoenix: Vec<Card> = cards
-                .iter()
-                .filter(|card| card.suit != CardSuit::Phoenix)
-                .map(|card| (*card).clone())
-                .collect();
-            let highest_value_card = cards_without_phoenix.last().expect("Should have non-Phoenix cards").clone();
-            cards_without_phoenix.push(highest_value_card);
-            adjusted_cards = cards_without_phoenix;
-        }
-
-        // full house (first 3 are equal)
-        if let [card_0, card_1, card_2, card_3, card_4] = &adjusted_cards[..adjusted_cards.len()] {
-            if (card_0.value == card_1.value && card_0.value == card_2.value)
-                && (card_3.value == card_4.value)
-                && (card_0.value != card_3.value)
-            {
-                return Some(ValidCardCombo::FullHouse(FullHouse {
-                    cards: original_cards.clone(),
-                    trio_value: card_0.value.clone(),
-                    user_id
-                }));
-            }
-            // full house (last 3 are equal)
-            else if (card_2.value == card_3.value && card_2.value == card_4.value)
-                && (card_0.value == card_1.value)
-                && (card_0.value != card_2.value)
-            {
-                return Some(ValidCardCombo::FullHouse(FullHouse {
-                    cards: original_cards.clone(),
-                    trio_value: card_2.value.clone(),
-                    user_id
-                }));
-            }
-
-            // plain sequences and sequence bombs are analyzed later
-        }
+    // the Phoenix stands in for whichever card is missing, so only standard cards are counted
+    let value_counts = standard_cards.get_value_counts();
+    let values: Vec<u8> = (CARD_VALUE_MIN..=CARD_VALUE_MAX)
+        .filter(|value| value_counts[*value as usize] > 0)
+        .collect();
+    let lowest_value = CardValue(values[0]);
+    let highest_value = CardValue(values[values.len() - 1]);
+    let number_of_values_spanned = (highest_value.0 - lowest_value.0) as usize + 1;
+
+    // pair, trio, or a bomb (4 of the same)
+    if values.len() == 1 {
+        return match number_of_cards {
+            2 => Some(ComboShape::Pair(lowest_value)),
+            3 => Some(ComboShape::Trio(lowest_value)),
+            4 if !includes_phoenix => Some(ComboShape::BombOf4(lowest_value)),
+            _ => None,
+        };
     }
 
-    // sequence 
-    if cards.len() >= 5 {
-        let mut is_sequence = true;
-        let mut all_same_suit = true;
-        // manipulate cards locally to this function (hacky for now)
-        let mut cards = cards.clone();
-
-        // if a phoenix is present, optimistically swap out the phoenix for a normal card
-        if includes_phoenix {
-            let cards_without_phoenix: Vec<&Card> = cards
-                .iter()
-                .filter(|card| card.suit != CardSuit::Phoenix)
-                .collect();
-
-            let mut i_of_last_continuos_sequence_card = None;
-            for i in 1..cards_without_phoenix.len() {
-                let prev_card = &cards_without_phoenix[i - 1];
-                let card = &cards_without_phoenix[i];
-
-                if prev_card.value.add(1) != card.value {
-                    i_of_last_continuos_sequence_card = Some(i - 1);
-                    break;
-                }
-            }
-
-            // create vector of cloned, owned cards
-            let mut cards_without_phoenix: Vec<Card> = cards_without_phoenix
-                .iter()
-                .map(|card| (*card).clone())
-                .collect();
-
-            // gap in cards found--insert Phoenix in gap
-            if let Some(i_of_last_continuos_sequence_card) = i_of_last_continuos_sequence_card {
-                let mut new_card = cards_without_phoenix[i_of_last_continuos_sequence_card].clone();
-                new_card.value = new_card.value.add(1);
-                cards_without_phoenix.insert(i_of_last_continuos_sequence_card + 1, new_card);
-            } else {
-                // NO gap in cards found--put Phoenix at the end of the Sequence
-                let mut new_card = cards_without_phoenix.last().expect("There should be non-Phoenix cards").clone();
-                new_card.value = new_card.value.add(1);
-                cards_without_phoenix.push(new_card);
-            }
-            cards = cards_without_phoenix;
-        }
-
-        // cards should no longer be mutable
-        let cards = cards;
-
-        // test if cards are sequence
-        for i in 1..cards.len() {
-            let card = &cards[i];
-            let prev_card = &cards[i - 1];
-            if prev_card.suit != card.suit {
-                all_same_suit = false;
-            }
-            if prev_card.value.add(1) != card.value {
-                is_sequence = false;
-                break;
-            }
-        }
-        if is_sequence {
-            return if !includes_phoenix && all_same_suit {
-                // bomb sequence of length at least 5
-                Some(ValidCardCombo::SequenceBomb(SequenceBomb {
-                    cards: original_cards.clone(),
-                    number_of_cards: original_cards.len() as u8,
-                    starting_value: cards[0].value.clone(),
-                    suit: cards[0].suit.clone(),
-                    user_id,
-                }))
-            } else {
-                // non-bomb sequence
-                Some(ValidCardCombo::Sequence(Sequence {
-                    cards: original_cards.clone(),
-                    number_of_cards: original_cards.len() as u8,
-                    starting_value: cards[0].value.clone(),
-                    user_id,
-                }))
-            };
-        }
+    // full house: with a Phoenix, it completes the lone card's pair, or the higher of 2 pairs' trio
+    if number_of_cards == 5 && values.len() == 2 {
+        let trio_value = values.iter().rev().find(|value| value_counts[**value as usize] == 3);
+        return match (trio_value, includes_phoenix) {
+            (Some(trio_value), _) => Some(ComboShape::FullHouse(CardValue(*trio_value))),
+            (None, true) => Some(ComboShape::FullHouse(highest_value)),
+            (None, false) => None,
+        };
     }
 
-    // any sequence of pairs of adjacent value
-    let even_number_of_cards = cards.len() % 2 == 0;
-    if even_number_of_cards && cards.len() > 3 {
-        let mut is_sequence_of_pairs = true;
-
-        // if a phoenix is present, optimistically swap out the phoenix for a normal card
-        let cards = if includes_phoenix {
-            let cards_without_phoenix: Vec<&Card> = cards
-                .iter()
-                .filter(|card| card.suit != CardSuit::Phoenix)
-                .collect();
-
-            let mut i_of_lone_card = None;
-            for i in 1..cards_without_phoenix.len() {
-                let prev_card = &cards_without_phoenix[i - 1];
-                let card = &cards_without_phoenix[i];
-                let next_card = cards_without_phoenix.get(i + 1);
-                let is_second_card = i == 1;
-                let is_last_card = i == cards_without_phoenix.len() - 1;
-
-                if is_second_card {
-                    if prev_card.value != card.value {
-                        i_of_lone_card = Some(i - 1);
-                        break;
-                    }
-                } else if is_last_card {
-                    if prev_card.value != card.value {
-                        i_of_lone_card = Some(i);
-                        break;
-                    }
-                } else if prev_card.value != card.value
-                    && card.value != next_card.expect("Card should be Some").value
-                {
-                    i_of_lone_card = Some(i);
-                    break;
-                }
+    // sequence of at least 5 cards
+    if number_of_cards >= 5 && values.len() == standard_cards.len() {
+        let starting_value = if !includes_phoenix && number_of_values_spanned == number_of_cards {
+            if standard_cards.is_one_suit() {
+                return Some(ComboShape::SequenceBomb {
+                    starting_value: lowest_value,
+                    number_of_cards: number_of_cards as u8,
+                    suit: card_set.iter().next().unwrap().suit,
+                });
             }
-
-            if let Some(i_of_lone_card) = i_of_lone_card {
-                let cloned_card = (*cards_without_phoenix.get(i_of_lone_card).unwrap()).clone();
-                let mut cards_without_phoenix: Vec<Card> = cards_without_phoenix
-                    .iter()
-                    .map(|card| (*card).clone())
-                    .collect();
-                cards_without_phoenix.insert(i_of_lone_card, cloned_card);
-                cards_without_phoenix
+            lowest_value
+        } else if includes_phoenix && number_of_values_spanned == number_of_cards {
+            // gap in cards found--Phoenix fills the gap
+            lowest_value
+        } else if includes_phoenix && number_of_values_spanned == number_of_cards - 1 {
+            // NO gap in cards found--put Phoenix at the end of the Sequence,
+            // unless nothing is higher than the last card, then put it at the start
+            if highest_value == CardValue::max() {
+                lowest_value.minus(1)
             } else {
-                // no lone card found (probably not a valid combo)
-                cards
+                lowest_value
             }
         } else {
-            // no phoenix present, cards stay as they are
-            cards
+            return None;
         };
 
-        // test if is sequence of pairs
-        for i in 1..cards.len() {
-            let card = &cards[i];
-            let prev_card = &cards[i - 1];
-            let is_even = i % 2 == 0;
-            // pairs have same value
-            if !is_even && card.value != prev_card.value {
-                is_sequence_of_pairs = false;
-                break;
-            }
-            // adjacent pairs are only 1 value away
-            if is_even && prev_card.value.add(1) != card.value {
-                is_sequence_of_pairs = false;
-                break;
-            }
-        }
-        if is_sequence_of_pairs {
-            return Some(ValidCardCombo::SequenceOfPairs(SequenceOfPairs {
-                cards: original_cards.clone(),
-                starting_value: cards[0].value.clone(),
-                number_of_pairs: cards.len() as u8 / 2,
-                user_id,
-            }));
-        }
+        return Some(ComboShape::Sequence {
+            starting_value,
+            number_of_cards: number_of_cards as u8,
+        });
+    }
+
+    // any sequence of pairs of adjacent value: with a Phoenix, it completes exactly 1 of the pairs
+    if number_of_cards.is_multiple_of(2)
+        && number_of_values_spanned == number_of_cards / 2
+        && values.len() == number_of_values_spanned
+        && values.iter().all(|value| value_counts[*value as usize] <= 2)
+    {
+        return Some(ComboShape::SequenceOfPairs {
+            starting_value: lowest_value,
+            number_of_pairs: (number_of_cards / 2) as u8,
+        });
     }
 
     None
 }
 
+fn to_valid_card_combo(combo_shape: ComboShape, cards: Vec<Card>, user_id: String) -> ValidCardCombo {
+    match combo_shape {
+        ComboShape::Single(value) => ValidCardCombo::Single(Single { cards, value, user_id }),
+        ComboShape::Pair(value) => ValidCardCombo::Pair(Pair { cards, value, user_id }),
+        ComboShape::Trio(value) => ValidCardCombo::Trio(Trio { cards, value, user_id }),
+        ComboShape::BombOf4(value) => ValidCardCombo::BombOf4(BombOf4 { cards, value, user_id }),
+        ComboShape::FullHouse(trio_value) => ValidCardCombo::FullHouse(FullHouse { cards, trio_value, user_id }),
+        ComboShape::Sequence { starting_value, number_of_cards } => ValidCardCombo::Sequence(Sequence {
+            cards,
+            starting_value,
+            number_of_cards,
+            user_id,
+        }),
+        ComboShape::SequenceBomb { starting_value, number_of_cards, suit } => ValidCardCombo::SequenceBomb(SequenceBomb {
+            cards,
+            starting_value,
+            number_of_cards,
+            suit,
+            user_id,
+        }),
+        ComboShape::SequenceOfPairs { starting_value, number_of_pairs } => ValidCardCombo::SequenceOfPairs(SequenceOfPairs {
+            cards,
+            starting_value,
+            number_of_pairs,
+            user_id,
+        }),
+    }
+}
+
 pub fn next_combo_beats_prev(prev: &Option<&ValidCardCombo>, next: &ValidCardCombo) -> bool {
     if let Some(prev) = prev {
         // sequence bomb
@@ -435,6 +232,18 @@ pub fn next_combo_beats_prev(prev: &Option<&ValidCardCombo>, next: &ValidCardCom
               }
           }
 
+          // sequences can only be beaten by sequences of the same length
+          if let (ValidCardCombo::Sequence(prev_sequence), ValidCardCombo::Sequence(next_sequence)) = (prev, next) {
+              if prev_sequence.number_of_cards != next_sequence.number_of_cards {
+                  return false;
+              }
+          }
+          if let (ValidCardCombo::SequenceOfPairs(prev_sequence_of_pairs), ValidCardCombo::SequenceOfPairs(next_sequence_of_pairs)) = (prev, next) {
+              if prev_sequence_of_pairs.number_of_pairs != next_sequence_of_pairs.number_of_pairs {
+                  return false;
+              }
+          }
+
           // any standard card must be the same type and greater
           (std::mem::discriminant(*prev) == std::mem::discriminant(next)) && (next > prev)
     } else {
@@ -443,6 +252,23 @@ pub fn next_combo_beats_prev(prev: &Option<&ValidCardCombo>, next: &ValidCardCom
     }
 }
 
+/// Checks that every card is held in the user's hand, and that no card is used more than once
+pub fn validate_cards_in_hand(hand: &[Card], cards: &[Card]) -> Result<(), TichuError> {
+    let hand: CardSet = hand.into();
+    let mut validated_cards = CardSet::EMPTY;
+    for card in cards {
+        if validated_cards.contains(card) {
+            return Err(TichuError::DuplicateCard(card.clone()));
+        }
+        // cards that aren't in the deck are never in a hand either
+        if !hand.contains(card) {
+            return Err(TichuError::CardNotInHand(card.clone()));
+        }
+        validated_cards.insert(card);
+    }
+    Ok(())
+}
+
 pub fn sort_cards_for_hand(cards: &mut Vec<Card>) {
     cards.sort_by(|a, b| {
         if a.value == b.value {
@@ -453,6 +279,11 @@ pub fn sort_cards_for_hand(cards: &mut Vec<Card>) {
     });
 }
 
+/// Whether the user holds a wished-for card and could legally play it on top of `prev_combo`
+///
+/// When leading, holding the wished-for card is enough. When following, the card must fit into
+/// a combo (of the same length as `prev_combo`, or a bomb) that beats the previous combo.
+/// The Phoenix may help complete such a combo, but it never counts as the wished-for card itself.
 pub fn get_user_can_play_wished_for_card(prev_combo: Option<&ValidCardCombo>, users_hand: &Vec<Card>, wished_for_card_value: &CardValue) -> bool {
     // CardValue::noop() is equivalent to None
     if *wished_for_card_value == CardValue::noop() {
@@ -460,49 +291,217 @@ pub fn get_user_can_play_wished_for_card(prev_combo: Option<&ValidCardCombo>, us
     }
 
     // if user does not have the wished for card, return false
-    if !users_hand.iter().any(|card| card.value == *wished_for_card_value) {
+    let hand: CardSet = users_hand.iter().collect();
+    let wished_for_cards = CardSet::of_value(wished_for_card_value);
+    if (hand & wished_for_cards).is_empty() {
         return false
     }
 
     // if prev combo is none and the user has the wished for card, then they can play it,
     // and we already know that the user has the wished-for card value
-    let prev_combo = if let Some(prev_combo) = prev_combo {
-        prev_combo
+    if prev_combo.is_none() {
+        return true;
+    }
+
+    // it's only necessary to find one combination that works to prove
+    // that the user CAN play the wished for card
+    get_candidate_card_sets(hand, prev_combo)
+        .into_iter()
+        // must have wished for card
+        .filter(|card_set| !(*card_set & wished_for_cards).is_empty())
+        // must be valid combo, and must beat previous combo
+        .any(|card_set| get_playable_combo(prev_combo, card_set).is_some())
+}
+
+/// Only a standard card of the wished-for value fulfills a wish (never the Phoenix)
+fn card_fulfills_wish(card: &Card, wished_for_card_value: &CardValue) -> bool {
+    !card.suit.is_special() && card.value == *wished_for_card_value
+}
+
+/// Whether the combo contains a card that fulfills the wish
+pub fn get_combo_fulfills_wish(combo: &ValidCardCombo, wished_for_card_value: &CardValue) -> bool {
+    combo.cards().iter().any(|card| card_fulfills_wish(card, wished_for_card_value))
+}
+
+/// Whether playing `next_combo` honors the current wish (if any)
+///
+/// The user whose turn it is must fulfill the wish whenever they are able to, even if that means
+/// playing a bomb. Bombs played out of turn never have to contain the wished-for card.
+pub fn get_play_respects_wish(prev_combo: Option<&ValidCardCombo>, users_hand: &Vec<Card>, next_combo: &ValidCardCombo, wished_for_card_value: Option<&CardValue>, is_users_turn: bool) -> bool {
+    let wished_for_card_value = if let Some(wished_for_card_value) = wished_for_card_value {
+        wished_for_card_value
     } else {
         return true;
     };
 
-    // user doesn't have enough cards to match the combo
-    if users_hand.len() < prev_combo.cards().len() {
-        return false;
+    !is_users_turn
+        || get_combo_fulfills_wish(next_combo, wished_for_card_value)
+        || !get_user_can_play_wished_for_card(prev_combo, users_hand, wished_for_card_value)
+}
+
+/// Whether the user may pass instead of playing
+///
+/// Users can pass whenever something is on the table, unless a card has been wished for and they are able to play it.
+/// The user leading a trick has to play something, but users whose combo is still on top can always pass to take the trick.
+pub fn get_user_can_pass(user_id: &str, table_top: Option<&ValidCardCombo>, users_hand: &Vec<Card>, wished_for_card_value: Option<&CardValue>) -> bool {
+    match (table_top, wished_for_card_value) {
+        (None, _) => false,
+        (Some(table_top), _) if table_top.user_id() == user_id => true,
+        (Some(_), Some(wished_for_card_value)) => {
+            !get_user_can_play_wished_for_card(table_top, users_hand, wished_for_card_value)
+        }
+        (Some(_), None) => true,
     }
+}
 
-    // create combinations of given length
-    let mut all_combos_of_same_length: Vec<Vec<Card>> = users_hand.iter()
-        .combinations(prev_combo.cards().len())
-        .map(|cards| {
-            cards.into_iter()
-            .map(|card| card.to_owned())
-            .collect()
-        })
-        .collect();
-    
-    // must have wished for card
-    all_combos_of_same_length.retain(|cards| {
-        cards.iter().any(|card| card.value == *wished_for_card_value)
+/// Every combo the user could legally play from their hand on their turn
+///
+/// Each distinct set of cards is listed once, including bombs and combos completed with the Phoenix.
+/// If the user is able to fulfill the wish, only combos that fulfill it are listed.
+/// Combos are not attributed to any user.
+pub fn legal_plays(hand: &[Card], table_top: Option<&ValidCardCombo>, wished_for_card_value: Option<&CardValue>) -> Vec<ValidCardCombo> {
+    legal_card_set_plays(hand.into(), table_top, wished_for_card_value)
+}
+
+/// Like `legal_plays`, but for a CardSet: cards in each combo are listed in CardSet order
+///
+/// Combos are listed from fewest to most cards.
+pub fn legal_card_set_plays(hand: CardSet, table_top: Option<&ValidCardCombo>, wished_for_card_value: Option<&CardValue>) -> Vec<ValidCardCombo> {
+    let card_sets = get_candidate_card_sets(hand, table_top);
+
+    // checked once up front, since it's expensive to work out
+    let must_fulfill_wish = wished_for_card_value.is_some_and(|wished_for_card_value| {
+        get_user_can_play_wished_for_card(table_top, &hand.to_cards(), wished_for_card_value)
     });
+    let wished_for_cards = wished_for_card_value.map(CardSet::of_value).unwrap_or_default();
+
+    card_sets
+        .into_iter()
+        .filter(|card_set| !must_fulfill_wish || !(*card_set & wished_for_cards).is_empty())
+        .filter_map(|card_set| get_playable_combo(table_top, card_set))
+        .collect()
+}
+
+/// The combo the cards form, if it's a valid combo that can be played on top of `table_top`
+fn get_playable_combo(table_top: Option<&ValidCardCombo>, card_set: CardSet) -> Option<ValidCardCombo> {
+    let combo = get_card_set_combination(table_top, card_set, "")?;
+
+    // only bombs can differ in length from the previous combo
+    let must_be_bomb = table_top.is_some_and(|table_top| table_top.cards().len() != card_set.len());
+    if must_be_bomb && !combo.is_bomb() {
+        return None;
+    }
+
+    next_combo_beats_prev(&table_top, &combo).then_some(combo)
+}
 
-    // must be valid combo, and must beat previous combo
-    return all_combos_of_same_length.iter().any(|cards| {
-        let card_combo = get_card_combination(Some(prev_combo), cards, &String::from(""));
-        if let Some(card_combo) = card_combo {
-            let combo_beats_prev_combo =   next_combo_beats_prev(&Some(prev_combo), &card_combo);
-            if combo_beats_prev_combo {
-                // it's only necessary to find one combination that works to prove
-                // that the user CAN play the wished for card
-                return true;
+/// Every distinct set of cards from the hand that could form a combo to play on top of `table_top`
+///
+/// Sets are only built in the shapes of valid combos (rather than trying every subset of the hand),
+/// but they still have to be checked with `get_playable_combo`. Sorted from fewest to most cards.
+fn get_candidate_card_sets(hand: CardSet, table_top: Option<&ValidCardCombo>) -> Vec<CardSet> {
+    let is_leading = table_top.is_none();
+    let is_table_top = |is_kind: fn(&ValidCardCombo) -> bool| table_top.is_some_and(is_kind);
+    let includes_phoenix = hand.contains(&PHOENIX);
+    let phoenix = if includes_phoenix { CardSet::from(&PHOENIX) } else { CardSet::EMPTY };
+    let mut card_sets = Vec::new();
+
+    if is_leading || is_table_top(|combo| matches!(combo, ValidCardCombo::Single(_))) {
+        card_sets.append(&mut hand.combinations(1));
+    }
+
+    // pairs, trios and bombs of 4: the Phoenix may replace any card but in a bomb
+    let get_groups_of_value = |cards_of_value: CardSet, number_of_cards: usize| {
+        let mut groups = cards_of_value.combinations(number_of_cards);
+        if includes_phoenix {
+            groups.extend(
+                cards_of_value
+                    .combinations(number_of_cards - 1)
+                    .into_iter()
+                    .map(|card_set| card_set | phoenix),
+            );
+        }
+        groups
+    };
+    let mut pairs = Vec::new();
+    let mut trios = Vec::new();
+    for value in CardValue::full_valid_range() {
+        let cards_of_value = hand & CardSet::of_value(&value);
+        if cards_of_value.len() == 4 {
+            card_sets.push(cards_of_value);
+        }
+        pairs.append(&mut get_groups_of_value(cards_of_value, 2));
+        trios.append(&mut get_groups_of_value(cards_of_value, 3));
+    }
+
+    if is_leading || is_table_top(|combo| matches!(combo, ValidCardCombo::Pair(_))) {
+        card_sets.extend(pairs.iter());
+    }
+    if is_leading || is_table_top(|combo| matches!(combo, ValidCardCombo::Trio(_))) {
+        card_sets.extend(trios.iter());
+    }
+    if is_leading || is_table_top(|combo| matches!(combo, ValidCardCombo::FullHouse(_))) {
+        for trio in trios.iter() {
+            // values can't overlap, and the Phoenix can't be used twice
+            card_sets.extend(
+                pairs
+                    .iter()
+                    .map(|pair| *trio | *pair)
+                    .filter(|full_house| full_house.len() == 5),
+            );
+        }
+    }
+
+    // sequences, including sequence bombs (which never include the Phoenix)
+    let can_play_sequence = is_leading || is_table_top(|combo| matches!(combo, ValidCardCombo::Sequence(_)));
+    for number_of_cards in 5..=hand.len().min(MAX_CARDS_IN_HAND) {
+        for starting_value in CardValue::start_iter().0..=(CardValue::max().0 + 1 - number_of_cards as u8) {
+            let sequence_phoenix = if can_play_sequence { phoenix } else { CardSet::EMPTY };
+            card_sets.append(&mut get_runs(hand, sequence_phoenix, starting_value, number_of_cards, 1));
+        }
+    }
+
+    if is_leading || is_table_top(|combo| matches!(combo, ValidCardCombo::SequenceOfPairs(_))) {
+        for number_of_pairs in 2..=hand.len().min(MAX_CARDS_IN_HAND) / 2 {
+            for starting_value in CardValue::min().0..=(CardValue::max().0 + 1 - number_of_pairs as u8) {
+                card_sets.append(&mut get_runs(hand, phoenix, starting_value, number_of_pairs, 2));
             }
         }
-        false
-    });
-}
\ No newline at end of file
+    }
+
+    card_sets.sort_by_key(|card_set| (card_set.len(), *card_set));
+    card_sets.dedup();
+    card_sets
+}
+
+/// Every way to take `cards_per_value` cards of each of `number_of_values` adjacent values,
+/// with the Phoenix (if given) standing in for at most 1 card
+fn get_runs(hand: CardSet, phoenix: CardSet, starting_value: u8, number_of_values: usize, cards_per_value: usize) -> Vec<CardSet> {
+    let mut runs = vec![CardSet::EMPTY];
+    for value in (starting_value..).take(number_of_values) {
+        let cards_of_value = hand & CardSet::of_value(&CardValue(value));
+        let mut options = cards_of_value.combinations(cards_per_value);
+        if !phoenix.is_empty() {
+            options.extend(
+                cards_of_value
+                    .combinations(cards_per_value - 1)
+                    .into_iter()
+                    .map(|card_set| card_set | phoenix),
+            );
+        }
+
+        runs = runs
+            .iter()
+            .flat_map(|run| {
+                options
+                    .iter()
+                    .filter(|option| (*run & **option).is_empty())
+                    .map(|option| *run | *option)
+            })
+            .collect();
+        if runs.is_empty() {
+            break;
+        }
+    }
+    runs
+}
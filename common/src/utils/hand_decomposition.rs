@@ -0,0 +1,206 @@
+use crate::{legal_card_set_plays, Card, CardSet, CardSuit, ValidCardCombo, TOTAL_CARDS};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Every combo takes a turn to play, so going out in fewer combos outweighs everything else
+const COMBO_PENALTY: i32 = 100;
+
+/// Combos ranked above this tend to win tricks, while lower ones have to be led to get rid of them
+const AVERAGE_COMBO_RANK: i32 = 8;
+
+/// A bomb wins a trick whenever it's needed
+const BOMB_BONUS: i32 = 20;
+
+/// Hands with many interchangeable cards (e.g. a long sequence with a choice of suits)
+/// can be split up in thousands of equally short ways: only this many are listed
+pub const MAX_HAND_PARTITIONS: usize = 20;
+
+/// One way to split a whole hand into combos to play out one by one.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+pub struct HandPartition {
+    /// Ordered by each combo's lowest card, in CardSet order (i.e. special cards last)
+    pub combos: Vec<ValidCardCombo>,
+
+    /// Standard cards that don't fit into any multi-card combo of this partition
+    pub isolated_cards: Vec<Card>,
+
+    pub has_bomb: bool,
+
+    /// Higher is better. Fewer combos always score higher, then higher-ranked combos and bombs.
+    pub score: i32,
+}
+
+/// Lists the ways to split the hand into the fewest possible combos, best score first.
+///
+/// The Phoenix may complete any combo it can be part of (or be played as a single).
+/// At most `MAX_HAND_PARTITIONS` are listed, but the best-scoring partition is always first.
+/// Combos are not attributed to any user.
+pub fn decompose_hand(hand: &[Card]) -> Vec<HandPartition> {
+    let hand = CardSet::from(hand);
+    if hand.is_empty() {
+        return Vec::new();
+    }
+
+    let combos: Vec<(CardSet, i32, ValidCardCombo)> = legal_card_set_plays(hand, None, None)
+        .into_iter()
+        .map(|combo| (CardSet::from(combo.cards()), get_combo_score(&combo), combo))
+        .collect();
+    let mut combos_by_lowest_card = vec![Vec::new(); TOTAL_CARDS];
+    for (i, (combo_cards, _, _)) in combos.iter().enumerate() {
+        combos_by_lowest_card[combo_cards.0.trailing_zeros() as usize].push(i);
+    }
+    let mut decomposer = HandDecomposer {
+        combos,
+        combos_by_lowest_card,
+        best_partitions: HashMap::new(),
+    };
+
+    let mut partitions = Vec::new();
+    decomposer.add_best_partitions(hand, &mut Vec::new(), &mut partitions);
+    partitions.sort_by_key(|partition| -partition.score);
+    partitions
+}
+
+/// Orders cards so that each combo of the hand's best partition is grouped together,
+/// from the combo with the lowest card to the one with the highest
+pub fn sort_cards_by_combos(cards: &mut Vec<Card>) {
+    if let Some(best_partition) = decompose_hand(cards).into_iter().next() {
+        *cards = best_partition
+            .combos
+            .into_iter()
+            .flat_map(|combo| combo.cards().clone())
+            .collect();
+    }
+}
+
+/// How strong a combo is compared to others of its kind: roughly, the value of its highest card
+fn get_combo_rank(combo: &ValidCardCombo) -> i32 {
+    let rank = match combo {
+        ValidCardCombo::Single(single) => match single.cards[0].suit {
+            CardSuit::Dragon => 16,
+            CardSuit::Phoenix => 15,
+            CardSuit::MahJong => 1,
+            CardSuit::Dog => 0,
+            _ => single.value.0,
+        },
+        ValidCardCombo::Pair(pair) => pair.value.0,
+        ValidCardCombo::Trio(trio) => trio.value.0,
+        ValidCardCombo::BombOf4(bomb_of_4) => bomb_of_4.value.0,
+        ValidCardCombo::FullHouse(full_house) => full_house.trio_value.0,
+        ValidCardCombo::Sequence(sequence) => {
+            sequence.starting_value.0 + sequence.number_of_cards - 1
+        }
+        ValidCardCombo::SequenceBomb(sequence_bomb) => {
+            sequence_bomb.starting_value.0 + sequence_bomb.number_of_cards - 1
+        }
+        ValidCardCombo::SequenceOfPairs(sequence_of_pairs) => {
+            sequence_of_pairs.starting_value.0 + sequence_of_pairs.number_of_pairs - 1
+        }
+    };
+    rank as i32
+}
+
+/// What a combo adds to the score of a partition that includes it
+fn get_combo_score(combo: &ValidCardCombo) -> i32 {
+    let bomb_bonus = if combo.is_bomb() { BOMB_BONUS } else { 0 };
+    get_combo_rank(combo) - AVERAGE_COMBO_RANK + bomb_bonus - COMBO_PENALTY
+}
+
+struct HandDecomposer {
+    /// Every combo in the hand, with its cards and score
+    combos: Vec<(CardSet, i32, ValidCardCombo)>,
+
+    /// Indexes into `combos`, grouped by the position of each combo's lowest card in a CardSet
+    combos_by_lowest_card: Vec<Vec<usize>>,
+
+    /// Fewest combos the cards can be split into, and the best score of any such split
+    best_partitions: HashMap<CardSet, (usize, i32)>,
+}
+
+impl HandDecomposer {
+    fn get_best_partition(&mut self, cards: CardSet) -> (usize, i32) {
+        if cards.is_empty() {
+            return (0, 0);
+        }
+        if let Some(best_partition) = self.best_partitions.get(&cards) {
+            return *best_partition;
+        }
+
+        let best_partition = self
+            .get_next_combos(cards)
+            .into_iter()
+            .map(|i| {
+                let (combo_cards, combo_score, _) = self.combos[i];
+                let (number_of_combos, score) = self.get_best_partition(cards - combo_cards);
+                (number_of_combos + 1, score + combo_score)
+            })
+            .min_by_key(|(number_of_combos, score)| (*number_of_combos, -score))
+            .expect("Any card can be played as a single");
+        self.best_partitions.insert(cards, best_partition);
+        best_partition
+    }
+
+    /// Combos made of the remaining cards that include the lowest remaining card,
+    /// so that every partition is only found once
+    fn get_next_combos(&self, cards: CardSet) -> Vec<usize> {
+        self.combos_by_lowest_card[cards.0.trailing_zeros() as usize]
+            .iter()
+            .copied()
+            .filter(|i| self.combos[*i].0.is_subset(&cards))
+            .collect()
+    }
+
+    /// Adds every partition of the remaining cards into as few combos as possible,
+    /// trying the best-scoring combos first
+    fn add_best_partitions(
+        &mut self,
+        cards: CardSet,
+        combos_so_far: &mut Vec<usize>,
+        partitions: &mut Vec<HandPartition>,
+    ) {
+        if partitions.len() >= MAX_HAND_PARTITIONS {
+            return;
+        }
+        if cards.is_empty() {
+            partitions.push(self.to_hand_partition(combos_so_far));
+            return;
+        }
+
+        let (fewest_combos, _) = self.get_best_partition(cards);
+        let mut next_combos: Vec<(usize, i32)> = Vec::new();
+        for i in self.get_next_combos(cards) {
+            let (combo_cards, combo_score, _) = self.combos[i];
+            let (number_of_combos, score) = self.get_best_partition(cards - combo_cards);
+            if number_of_combos + 1 == fewest_combos {
+                next_combos.push((i, score + combo_score));
+            }
+        }
+        next_combos.sort_by_key(|(_, score)| -score);
+
+        for (i, _) in next_combos {
+            combos_so_far.push(i);
+            self.add_best_partitions(cards - self.combos[i].0, combos_so_far, partitions);
+            combos_so_far.pop();
+        }
+    }
+
+    fn to_hand_partition(&self, combo_indexes: &[usize]) -> HandPartition {
+        let combos: Vec<ValidCardCombo> = combo_indexes
+            .iter()
+            .map(|i| self.combos[*i].2.clone())
+            .collect();
+        let isolated_cards = combos
+            .iter()
+            .filter(|combo| matches!(combo, ValidCardCombo::Single(_)))
+            .map(|combo| combo.cards()[0].clone())
+            .filter(|card| !card.suit.is_special())
+            .collect();
+
+        HandPartition {
+            has_bomb: combos.iter().any(|combo| combo.is_bomb()),
+            score: combo_indexes.iter().map(|i| self.combos[*i].1).sum(),
+            isolated_cards,
+            combos,
+        }
+    }
+}
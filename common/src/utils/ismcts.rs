@@ -0,0 +1,729 @@
+use crate::{
+    bot::get_card_rank, get_card_combination, get_current_time_ms, get_user_can_pass, legal_plays,
+    next_combo_beats_prev, CTSMsg, Card, CardSet, CardSuit, CardTrade, CardValue, GameRules,
+    PublicGameStage, PublicGameState, PublicPlay, TichuCallStatus, ValidCardCombo, DOG, DRAGON,
+    MAH_JONG, MAX_CARDS_IN_HAND,
+};
+use rand::{prelude::SliceRandom, rngs::SmallRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::mem::{discriminant, Discriminant};
+
+/// Everything a single user has seen of the game so far.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct Observation {
+    /// The game from the user's point of view (i.e. the user is the `current_user`)
+    pub game_state: PublicGameState,
+
+    /// Cards the user passed to the other users at the start of this round
+    pub cards_traded_away: Vec<CardTrade>,
+}
+
+impl From<PublicGameState> for Observation {
+    fn from(game_state: PublicGameState) -> Self {
+        Observation {
+            game_state,
+            cards_traded_away: Vec::new(),
+        }
+    }
+}
+
+/// How long to search for an action.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub enum SearchBudget {
+    /// Simulate exactly this many rounds, so the same seed always produces the same action
+    Iterations(u32),
+
+    /// Keep simulating rounds until this many milliseconds have passed
+    Milliseconds(u64),
+}
+
+/// How likely a hand is to go out first, and what calling Tichu on it is worth on average.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct TichuAdvice {
+    /// Fraction of simulated rounds in which the user went out first
+    pub probability_of_going_out_first: f64,
+
+    /// Points a call adds to the user's team on average (negative if calling loses points)
+    pub expected_value_of_calling: f64,
+
+    /// What the call is worth under the game's rules: a Grand Tichu before the rest of the cards
+    /// are dealt, or a Small Tichu for a full hand
+    pub tichu_value: i32,
+
+    pub number_of_simulations: u32,
+}
+
+/// How strongly the search favors trying rarely-simulated plays over the best play found so far
+const EXPLORATION_CONSTANT: f64 = 0.7;
+
+/// Simulated rounds are cut off after this many plays and passes (a round rarely needs 100)
+const MAX_SIMULATION_STEPS: usize = 500;
+
+/// Chooses a play with information-set Monte Carlo search.
+///
+/// Every iteration deals the cards the user can't see at random, consistent with everything the user
+/// has seen: its own hand, the cards it traded away, and every card played into `table` and `tricks`.
+/// The rest of the round is then simulated for one of the user's legal plays, chosen with UCB1,
+/// and the play that was simulated the most is returned.
+///
+/// Only acts in the Play stage. Returns None when the user has nothing to do.
+pub fn get_ismcts_action(
+    observation: &Observation,
+    search_budget: &SearchBudget,
+    seed: u64,
+) -> Option<CTSMsg> {
+    let game_state = &observation.game_state;
+    let play_state = if let PublicGameStage::Play(play_state) = &game_state.stage {
+        play_state
+    } else {
+        return None;
+    };
+    let user_id = &game_state.current_user.user_id;
+    let seats = get_seats(play_state);
+    let user_seat = seats.iter().position(|seat| seat == user_id)?;
+
+    if let Some(dragon_winner_user_id) = &play_state.dragon_winner_user_id {
+        if dragon_winner_user_id != user_id {
+            return None;
+        }
+        let recipient_seat = get_dragon_recipient_seat(game_state, play_state, &seats, user_seat);
+        return Some(CTSMsg::GiveDragon {
+            user_id: seats[recipient_seat].clone(),
+        });
+    }
+
+    if play_state.turn_user_id != *user_id {
+        return None;
+    }
+
+    let hand = &game_state.current_user.hand;
+    let table_top = play_state.table.last();
+    let wished_for_card_value = play_state.wished_for_card_value.as_ref();
+    let mut actions: Vec<Option<ValidCardCombo>> = Vec::new();
+    let mut action_keys = Vec::new();
+    for combo in legal_plays(hand, table_top, wished_for_card_value) {
+        // plays that only differ in suit are nearly always equally good, so only one is searched
+        let action_key = get_combo_key(&combo);
+        if !action_keys.contains(&action_key) {
+            action_keys.push(action_key);
+            actions.push(Some(combo));
+        }
+    }
+    if get_user_can_pass(user_id, table_top, hand, wished_for_card_value) {
+        actions.push(None);
+    }
+
+    let action = match actions.len() {
+        0 => None,
+        1 => actions.pop().flatten(),
+        _ => {
+            let i = search(
+                observation,
+                &seats,
+                user_seat,
+                &actions,
+                search_budget,
+                seed,
+            );
+            actions.swap_remove(i)
+        }
+    };
+
+    Some(match action {
+        Some(combo) => {
+            let cards = combo.cards().clone();
+            let wished_for_card_value = if cards.contains(&MAH_JONG) {
+                get_wish(hand)
+            } else {
+                None
+            };
+            CTSMsg::PlayCards {
+                cards,
+                wished_for_card_value,
+            }
+        }
+        None => CTSMsg::Pass,
+    })
+}
+
+/// Estimates whether to call Tichu by simulating rounds with the unseen cards dealt at random.
+///
+/// Works for the cards dealt before Grand Tichu is called (if the rules allow it), or for a full
+/// hand of 14 cards (Small Tichu) before the user has played anything.
+/// Trades aren't simulated, and the other users never call Tichu.
+///
+/// Returns None for any other number of cards, or if the hand has invalid or duplicate cards.
+pub fn get_tichu_advice(
+    hand: &[Card],
+    rules: &GameRules,
+    search_budget: &SearchBudget,
+    seed: u64,
+) -> Option<TichuAdvice> {
+    let hand_cards: CardSet = hand
+        .iter()
+        .filter(|card| CardSet::full().contains(card))
+        .collect();
+    if hand_cards.len() != hand.len() {
+        return None;
+    }
+    let tichu_value = if hand.len() == MAX_CARDS_IN_HAND {
+        rules.small_tichu_value
+    } else if rules.grand_tichu_enabled && hand.len() == rules.num_cards_before_grand_tichu {
+        rules.grand_tichu_value
+    } else {
+        return None;
+    };
+
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let start_time_ms = get_current_time_ms();
+    let mut iteration = 0;
+    let mut rounds_gone_out_first = 0;
+    // at least one round is always simulated
+    while iteration == 0 || get_is_within_budget(search_budget, iteration, start_time_ms) {
+        let mut simulation = Simulation::deal(hand_cards, rules, &mut rng);
+        simulation.play_out();
+        if simulation.first_seat_out == Some(0) {
+            rounds_gone_out_first += 1;
+        }
+        iteration += 1;
+    }
+
+    let probability_of_going_out_first = rounds_gone_out_first as f64 / iteration as f64;
+    Some(TichuAdvice {
+        probability_of_going_out_first,
+        // the call doesn't change how the round is played: it only wins or loses its value
+        expected_value_of_calling: tichu_value as f64
+            * (2.0 * probability_of_going_out_first - 1.0),
+        tichu_value,
+        number_of_simulations: iteration as u32,
+    })
+}
+
+fn get_is_within_budget(
+    search_budget: &SearchBudget,
+    iteration: usize,
+    start_time_ms: f64,
+) -> bool {
+    match search_budget {
+        SearchBudget::Iterations(iterations) => iteration < *iterations as usize,
+        SearchBudget::Milliseconds(ms) => get_current_time_ms() - start_time_ms < *ms as f64,
+    }
+}
+
+/// Returns the index of the action that was simulated the most
+fn search(
+    observation: &Observation,
+    seats: &[String; 4],
+    user_seat: usize,
+    actions: &[Option<ValidCardCombo>],
+    search_budget: &SearchBudget,
+    seed: u64,
+) -> usize {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut visits = vec![0u32; actions.len()];
+    let mut total_rewards = vec![0.0; actions.len()];
+    let start_time_ms = get_current_time_ms();
+
+    let mut iteration = 0;
+    // every action is simulated at least once
+    while iteration < actions.len() || get_is_within_budget(search_budget, iteration, start_time_ms)
+    {
+        let i = if iteration < actions.len() {
+            iteration
+        } else {
+            let log_iterations = (iteration as f64).ln();
+            (0..actions.len())
+                .max_by(|&a, &b| {
+                    let ucb = |i: usize| {
+                        total_rewards[i] / visits[i] as f64
+                            + EXPLORATION_CONSTANT * (log_iterations / visits[i] as f64).sqrt()
+                    };
+                    ucb(a).total_cmp(&ucb(b))
+                })
+                .expect("There should be more than one action")
+        };
+
+        let mut simulation = Simulation::determinize(observation, seats, user_seat, &mut rng);
+        simulation.step(user_seat, actions[i].clone());
+        simulation.play_out();
+
+        visits[i] += 1;
+        total_rewards[i] += simulation.get_reward(user_seat);
+        iteration += 1;
+    }
+
+    (0..actions.len())
+        .max_by(|&a, &b| {
+            visits[a].cmp(&visits[b]).then(
+                (total_rewards[a] / visits[a] as f64)
+                    .total_cmp(&(total_rewards[b] / visits[b] as f64)),
+            )
+        })
+        .expect("There should be more than one action")
+}
+
+/// A compact copy of a round in the Play stage where every hand is known.
+///
+/// Users are identified by seat, in turn order, so teammates are always 2 seats apart.
+/// Simulated users don't bomb, and don't have to fulfill wishes.
+#[derive(Debug, Clone)]
+struct Simulation {
+    hands: [Vec<Card>; 4],
+    table_top: Option<ValidCardCombo>,
+    /// Seat of the user who played the combo on top of the table
+    trick_seat: usize,
+    trick_points: i32,
+    passes_since_last_play: usize,
+    turn_seat: usize,
+    points_won: [i32; 4],
+    first_seat_out: Option<usize>,
+    /// Points each seat gains if they go out first, or loses otherwise
+    tichu_bets: [i32; 4],
+    double_victory_value: i32,
+}
+
+impl Simulation {
+    /// Deals the cards the user can't see at random, consistent with what they have seen
+    fn determinize(
+        observation: &Observation,
+        seats: &[String; 4],
+        user_seat: usize,
+        rng: &mut SmallRng,
+    ) -> Self {
+        let game_state = &observation.game_state;
+        let play_state = if let PublicGameStage::Play(play_state) = &game_state.stage {
+            play_state
+        } else {
+            panic!("Can only simulate rounds in the Play stage");
+        };
+        let get_seat = |user_id: &str| seats.iter().position(|seat| seat == user_id);
+
+        let mut seen_cards = CardSet::from(&game_state.current_user.hand);
+        for combo in get_played_combos(game_state, play_state) {
+            seen_cards = seen_cards | CardSet::from(combo.cards());
+        }
+        let mut hidden_cards = (!seen_cards).to_cards();
+
+        let hand_sizes = get_hand_sizes(game_state, play_state, seats, user_seat);
+        let mut hands: [Vec<Card>; 4] = Default::default();
+        hands[user_seat] = game_state.current_user.hand.clone();
+
+        // cards traded away stay with whoever received them until they're played
+        for card_trade in observation.cards_traded_away.iter() {
+            if let Some(seat) = get_seat(&card_trade.to_user_id) {
+                if let Some(i) = hidden_cards
+                    .iter()
+                    .position(|card| *card == card_trade.card)
+                {
+                    if seat != user_seat && hands[seat].len() < hand_sizes[seat] {
+                        hands[seat].push(hidden_cards.swap_remove(i));
+                    }
+                }
+            }
+        }
+
+        hidden_cards.shuffle(rng);
+        for seat in 0..4 {
+            if seat != user_seat {
+                while hands[seat].len() < hand_sizes[seat] {
+                    if let Some(card) = hidden_cards.pop() {
+                        hands[seat].push(card);
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let mut points_won = [0; 4];
+        for participant in game_state.participants.iter() {
+            if let Some(seat) = get_seat(&participant.user_id) {
+                points_won[seat] = participant
+                    .tricks
+                    .iter()
+                    .map(|combo| get_cards_points(combo.cards()))
+                    .sum();
+            }
+        }
+
+        let mut tichu_bets = [0; 4];
+        for (tichus, value) in [
+            (&play_state.small_tichus, game_state.rules.small_tichu_value),
+            (&play_state.grand_tichus, game_state.rules.grand_tichu_value),
+        ] {
+            for tichu in tichus.iter() {
+                if tichu.tichu_call_status == TichuCallStatus::Called {
+                    if let Some(seat) = get_seat(&tichu.user_id) {
+                        tichu_bets[seat] += value;
+                    }
+                }
+            }
+        }
+
+        let table_top = play_state.table.last().cloned();
+        Simulation {
+            hands,
+            trick_seat: table_top
+                .as_ref()
+                .and_then(|combo| get_seat(combo.user_id()))
+                .unwrap_or(user_seat),
+            table_top,
+            trick_points: play_state
+                .table
+                .iter()
+                .map(|combo| get_cards_points(combo.cards()))
+                .sum(),
+            passes_since_last_play: play_state.passes.iter().filter(|pass| pass.passed).count(),
+            turn_seat: get_seat(&play_state.turn_user_id).unwrap_or(user_seat),
+            points_won,
+            first_seat_out: play_state
+                .first_user_out
+                .as_ref()
+                .and_then(|user_id| get_seat(user_id)),
+            tichu_bets,
+            double_victory_value: game_state.rules.double_victory_value,
+        }
+    }
+
+    /// Deals a new round in which the user sits in seat 0 and holds the given cards,
+    /// topped up to a full hand from the rest of the deck
+    fn deal(hand: CardSet, rules: &GameRules, rng: &mut SmallRng) -> Self {
+        let mut hidden_cards = (!hand).to_cards();
+        hidden_cards.shuffle(rng);
+
+        let mut hands: [Vec<Card>; 4] = Default::default();
+        hands[0] = hand.to_cards();
+        for hand in hands.iter_mut() {
+            while hand.len() < MAX_CARDS_IN_HAND {
+                match hidden_cards.pop() {
+                    Some(card) => hand.push(card),
+                    None => break,
+                }
+            }
+        }
+
+        // the Mah Jong leads the first trick
+        let lead_seat = hands
+            .iter()
+            .position(|hand| hand.contains(&MAH_JONG))
+            .unwrap_or_default();
+        Simulation {
+            hands,
+            table_top: None,
+            trick_seat: lead_seat,
+            trick_points: 0,
+            passes_since_last_play: 0,
+            turn_seat: lead_seat,
+            points_won: [0; 4],
+            first_seat_out: None,
+            tichu_bets: [0; 4],
+            double_victory_value: rules.double_victory_value,
+        }
+    }
+
+    fn get_is_in_play(&self, seat: usize) -> bool {
+        !self.hands[seat].is_empty()
+    }
+
+    fn get_round_is_over(&self) -> bool {
+        let seats_in_play: Vec<usize> = (0..4).filter(|&seat| self.get_is_in_play(seat)).collect();
+        seats_in_play.len() <= 1
+            || (seats_in_play.len() == 2 && seats_in_play[0] % 2 == seats_in_play[1] % 2)
+    }
+
+    /// The first seat after `seat` that is still in play, if any
+    fn get_next_seat_in_play(&self, seat: usize) -> usize {
+        (1..=4)
+            .map(|offset| (seat + offset) % 4)
+            .find(|&next_seat| self.get_is_in_play(next_seat))
+            .unwrap_or(seat)
+    }
+
+    /// Plays the combo for the seat whose turn it is, or passes if None
+    fn step(&mut self, seat: usize, combo: Option<ValidCardCombo>) {
+        let combo = if let Some(combo) = combo {
+            combo
+        } else {
+            self.pass(seat);
+            return;
+        };
+
+        let played_cards = CardSet::from(combo.cards());
+        self.hands[seat].retain(|card| !played_cards.contains(card));
+        if self.hands[seat].is_empty() && self.first_seat_out.is_none() {
+            self.first_seat_out = Some(seat);
+        }
+        self.trick_points += get_cards_points(combo.cards());
+        self.trick_seat = seat;
+        self.passes_since_last_play = 0;
+
+        let is_dog = combo.cards().contains(&DOG);
+        self.table_top = Some(combo);
+
+        if self.get_round_is_over() {
+            self.win_trick();
+        } else if is_dog {
+            // the Dog is never beaten: it passes the lead to the user's teammate
+            self.points_won[seat] += self.trick_points;
+            self.trick_points = 0;
+            self.table_top = None;
+            let teammate_seat = (seat + 2) % 4;
+            self.turn_seat = if self.get_is_in_play(teammate_seat) {
+                teammate_seat
+            } else {
+                self.get_next_seat_in_play(teammate_seat)
+            };
+        } else {
+            self.turn_seat = self.get_next_seat_in_play(seat);
+        }
+    }
+
+    fn pass(&mut self, seat: usize) {
+        self.passes_since_last_play += 1;
+        let number_of_other_seats_in_play = (0..4)
+            .filter(|&other_seat| other_seat != self.trick_seat && self.get_is_in_play(other_seat))
+            .count();
+        if self.table_top.is_some() && self.passes_since_last_play >= number_of_other_seats_in_play
+        {
+            self.win_trick();
+        } else {
+            self.turn_seat = self.get_next_seat_in_play(seat);
+        }
+    }
+
+    fn win_trick(&mut self) {
+        let won_with_dragon =
+            matches!(&self.table_top, Some(combo) if combo.cards().contains(&DRAGON));
+        let receiving_seat = if won_with_dragon {
+            // Dragon tricks go to an opponent
+            let opponent_seat = (self.trick_seat + 1) % 4;
+            if self.get_is_in_play(opponent_seat) {
+                opponent_seat
+            } else {
+                (self.trick_seat + 3) % 4
+            }
+        } else {
+            self.trick_seat
+        };
+
+        self.points_won[receiving_seat] += self.trick_points;
+        self.trick_points = 0;
+        self.table_top = None;
+        self.passes_since_last_play = 0;
+        self.turn_seat = if self.get_is_in_play(self.trick_seat) {
+            self.trick_seat
+        } else {
+            self.get_next_seat_in_play(self.trick_seat)
+        };
+    }
+
+    /// Plays the rest of the round with a fast, simple strategy for every seat
+    fn play_out(&mut self) {
+        for _ in 0..MAX_SIMULATION_STEPS {
+            if self.get_round_is_over() {
+                return;
+            }
+            let seat = self.turn_seat;
+            let combo = self.get_rollout_play(seat);
+            self.step(seat, combo);
+        }
+    }
+
+    /// Leads with the lowest cards, beats singles, pairs and trios as cheaply as possible,
+    /// and never overtakes a teammate
+    fn get_rollout_play(&self, seat: usize) -> Option<ValidCardCombo> {
+        let hand = &self.hands[seat];
+        let table_top = if let Some(table_top) = &self.table_top {
+            table_top
+        } else {
+            return get_lead(hand, self.get_is_in_play((seat + 2) % 4));
+        };
+
+        if self.trick_seat % 2 == seat % 2 {
+            return None;
+        }
+
+        let length = match table_top {
+            ValidCardCombo::Single(_) => 1,
+            ValidCardCombo::Pair(_) => 2,
+            ValidCardCombo::Trio(_) => 3,
+            _ => return None,
+        };
+        let mut candidates: Vec<Vec<Card>> = if length == 1 {
+            hand.iter()
+                .filter(|card| **card != DOG)
+                .map(|card| vec![card.clone()])
+                .collect()
+        } else {
+            get_value_groups(hand)
+                .into_iter()
+                .filter(|group| group.len() >= length)
+                .map(|group| group[..length].to_vec())
+                .collect()
+        };
+        candidates.sort_by_key(|cards| get_card_rank(&cards[0]));
+        candidates.into_iter().find_map(|cards| {
+            get_card_combination(Some(table_top), &cards, "")
+                .filter(|combo| next_combo_beats_prev(&Some(table_top), combo))
+        })
+    }
+
+    /// How much better the round went for the seat's team than for their opponents
+    fn get_reward(&self, seat: usize) -> f64 {
+        let seats_in_play: Vec<usize> = (0..4).filter(|&seat| self.get_is_in_play(seat)).collect();
+        let mut team_points = [0; 2];
+
+        if seats_in_play.len() == 2 && seats_in_play[0] % 2 == seats_in_play[1] % 2 {
+            // double victory: cards aren't counted
+            team_points[(seats_in_play[0] + 1) % 2] += self.double_victory_value;
+        } else {
+            let mut points_won = self.points_won;
+            if let (Some(&last_seat), Some(first_seat_out)) =
+                (seats_in_play.first(), self.first_seat_out)
+            {
+                // last user's hand goes to the opponents, and their tricks go to the first user out
+                points_won[(last_seat + 1) % 4] += get_cards_points(&self.hands[last_seat]);
+                points_won[first_seat_out] += points_won[last_seat];
+                points_won[last_seat] = 0;
+            }
+            for (seat, points) in points_won.iter().enumerate() {
+                team_points[seat % 2] += points;
+            }
+        }
+
+        for (bet_seat, bet) in self.tichu_bets.iter().enumerate() {
+            team_points[bet_seat % 2] += if self.first_seat_out == Some(bet_seat) {
+                *bet
+            } else {
+                -bet
+            };
+        }
+
+        (team_points[seat % 2] - team_points[(seat + 1) % 2]) as f64 / 200.0
+    }
+}
+
+/// User ids in turn order: teammates are 2 seats apart
+fn get_seats(play_state: &PublicPlay) -> [String; 4] {
+    [
+        play_state.teams[0].user_ids[0].clone(),
+        play_state.teams[1].user_ids[0].clone(),
+        play_state.teams[0].user_ids[1].clone(),
+        play_state.teams[1].user_ids[1].clone(),
+    ]
+}
+
+fn get_played_combos<'a>(
+    game_state: &'a PublicGameState,
+    play_state: &'a PublicPlay,
+) -> impl Iterator<Item = &'a ValidCardCombo> {
+    play_state.table.iter().chain(
+        game_state
+            .participants
+            .iter()
+            .flat_map(|participant| participant.tricks.iter()),
+    )
+}
+
+/// Every user starts the Play stage with 14 cards, so users' hand sizes follow from what they've played
+fn get_hand_sizes(
+    game_state: &PublicGameState,
+    play_state: &PublicPlay,
+    seats: &[String; 4],
+    user_seat: usize,
+) -> [usize; 4] {
+    let mut hand_sizes = [0; 4];
+    for (seat, user_id) in seats.iter().enumerate() {
+        hand_sizes[seat] = if seat == user_seat {
+            game_state.current_user.hand.len()
+        } else if !play_state.users_in_play.contains(user_id) {
+            0
+        } else {
+            let number_of_cards_played: usize = get_played_combos(game_state, play_state)
+                .filter(|combo| combo.user_id() == user_id)
+                .map(|combo| combo.cards().len())
+                .sum();
+            MAX_CARDS_IN_HAND.saturating_sub(number_of_cards_played)
+        };
+    }
+    hand_sizes
+}
+
+/// Gives the Dragon to the opponent with the most cards left, who is least likely to go out soon
+fn get_dragon_recipient_seat(
+    game_state: &PublicGameState,
+    play_state: &PublicPlay,
+    seats: &[String; 4],
+    user_seat: usize,
+) -> usize {
+    let hand_sizes = get_hand_sizes(game_state, play_state, seats, user_seat);
+    let opponent_seats = [(user_seat + 1) % 4, (user_seat + 3) % 4];
+    *opponent_seats
+        .iter()
+        .max_by_key(|&&seat| hand_sizes[seat])
+        .expect("There should always be 2 opponents")
+}
+
+fn get_lead(hand: &[Card], teammate_is_in_play: bool) -> Option<ValidCardCombo> {
+    let cards = if hand.contains(&MAH_JONG) {
+        vec![MAH_JONG]
+    } else if hand.contains(&DOG) && (teammate_is_in_play || hand.len() == 1) {
+        vec![DOG]
+    } else if let Some(group) = get_value_groups(hand).into_iter().next() {
+        group
+    } else {
+        // only special cards are left
+        let mut special_cards: Vec<&Card> = hand.iter().filter(|card| **card != DOG).collect();
+        special_cards.sort_by_key(|card| get_card_rank(card));
+        vec![special_cards
+            .first()
+            .copied()
+            .or_else(|| hand.first())?
+            .clone()]
+    };
+    get_card_combination(None, &cards, "")
+}
+
+/// Cards grouped by value, lowest value first, without the special cards
+fn get_value_groups(hand: &[Card]) -> Vec<Vec<Card>> {
+    let mut groups: Vec<Vec<Card>> = Vec::new();
+    let mut normal_cards: Vec<&Card> = hand.iter().filter(|card| !card.suit.is_special()).collect();
+    normal_cards.sort_by_key(|card| card.value.0);
+    for card in normal_cards {
+        match groups.last_mut() {
+            Some(group) if group[0].value == card.value => group.push(card.clone()),
+            _ => groups.push(vec![card.clone()]),
+        }
+    }
+    groups
+}
+
+fn get_combo_key(combo: &ValidCardCombo) -> (Discriminant<ValidCardCombo>, Vec<u8>) {
+    let mut ranks: Vec<u8> = combo.cards().iter().map(get_card_rank).collect();
+    ranks.sort();
+    (discriminant(combo), ranks)
+}
+
+fn get_cards_points(cards: &[Card]) -> i32 {
+    cards
+        .iter()
+        .map(|card| match card.suit {
+            CardSuit::Dragon => 25,
+            CardSuit::Phoenix => -25,
+            _ => match card.value.0 {
+                5 => 5,
+                10 | 13 => 10,
+                _ => 0,
+            },
+        })
+        .sum()
+}
+
+/// Wishes for the highest card the user doesn't have, to draw it out of the opponents' hands
+fn get_wish(hand: &[Card]) -> Option<CardValue> {
+    (2..=14).rev().map(CardValue).find(|value| {
+        !hand
+            .iter()
+            .any(|card| card.value == *value && !card.suit.is_special())
+    })
+}
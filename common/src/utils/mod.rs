@@ -1,5 +1,9 @@
 mod card_utils;
+mod hand_decomposition;
+mod ismcts;
 mod string_utils;
 
 pub use card_utils::*;
+pub use hand_decomposition::*;
+pub use ismcts::*;
 pub use string_utils::*;
@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod test_bots {
+    use common::{
+        get_bot_action, get_timeout_action, BotDifficulty, CTSMsg, PrivateGameStage,
+        PrivateGameState, TichuError, DOG, MAH_JONG,
+    };
+    use std::collections::HashMap;
+
+    /// Carries out a bot's request the same way the server would for a human
+    fn handle_bot_action(
+        game_state: &PrivateGameState,
+        user_id: &str,
+        msg: CTSMsg,
+    ) -> Result<PrivateGameState, TichuError> {
+        match msg {
+            CTSMsg::CallGrandTichu(call_grand_tichu_request) => {
+                game_state.call_grand_tichu(&call_grand_tichu_request, user_id)
+            }
+            CTSMsg::SubmitTrade(submit_trade) => game_state.submit_trade(user_id, &submit_trade),
+            CTSMsg::PlayCards {
+                cards,
+                wished_for_card_value,
+            } => game_state.play_cards(user_id, cards, wished_for_card_value),
+            CTSMsg::Pass => game_state.pass(user_id),
+            CTSMsg::GiveDragon {
+                user_id: recipient_user_id,
+            } => game_state.give_dragon(user_id, &recipient_user_id),
+            msg => panic!("Bots should never send {:?}", msg),
+        }
+    }
+
+    /// A game where the owner and 3 bots have formed teams
+    fn bot_game_state() -> PrivateGameState {
+        let mut game_state = PrivateGameState::new("1".into(), "User 1".into(), &HashMap::new());
+        for bot_difficulty in [
+            BotDifficulty::Easy,
+            BotDifficulty::Normal,
+            BotDifficulty::Normal,
+        ] {
+            game_state = game_state.add_bot("1", bot_difficulty).unwrap();
+        }
+        game_state
+    }
+
+    /// Lets every seat (including the owner's) be played by a bot until the game ends
+    fn play_game_with_bots(mut game_state: PrivateGameState) -> PrivateGameState {
+        let mut seed = 0;
+        while !matches!(game_state.stage, PrivateGameStage::Score(_)) {
+            let actions: Vec<(String, CTSMsg)> = game_state
+                .participants
+                .iter()
+                .filter_map(|participant| {
+                    let observation = game_state.get_observation(&participant.user_id).unwrap();
+                    let bot_difficulty = participant
+                        .bot_difficulty
+                        .clone()
+                        .unwrap_or(BotDifficulty::Normal);
+                    get_bot_action(&observation, &bot_difficulty, seed)
+                        .map(|msg| (participant.user_id.clone(), msg))
+                })
+                .collect();
+
+            assert!(!actions.is_empty(), "Some bot should always have a move");
+            for (user_id, msg) in actions {
+                game_state = handle_bot_action(&game_state, &user_id, msg.clone())
+                    .unwrap_or_else(|err| panic!("{:?} was rejected: {:?}", msg, err));
+            }
+
+            seed += 1;
+            assert!(seed < 100_000, "Game should have ended");
+        }
+        game_state
+    }
+
+    /// Lets the server act for every user who is being waited on, as if they had all run out of time
+    fn time_out_every_user(game_state: &PrivateGameState, seed: u64) -> PrivateGameState {
+        let mut game_state = game_state.clone();
+        for participant in game_state.participants.clone() {
+            let observation = game_state.get_observation(&participant.user_id).unwrap();
+            if let Some(msg) = get_timeout_action(&observation, seed) {
+                game_state = handle_bot_action(&game_state, &participant.user_id, msg.clone())
+                    .unwrap_or_else(|err| panic!("{:?} was rejected: {:?}", msg, err));
+            }
+        }
+        game_state
+    }
+
+    #[test]
+    fn it_should_only_let_the_owner_add_bots() {
+        let game_state = PrivateGameState::new("1".into(), "User 1".into(), &HashMap::new())
+            .add_user("2".into(), "User 2".into())
+            .unwrap();
+
+        assert_eq!(
+            game_state.add_bot("2", BotDifficulty::Easy),
+            Err(TichuError::NotOwner)
+        );
+        let game_state = game_state.add_bot("1", BotDifficulty::Easy).unwrap();
+        assert_eq!(game_state.participants[2].display_name, "Bot 1");
+        assert_eq!(
+            game_state.participants[2].bot_difficulty,
+            Some(BotDifficulty::Easy)
+        );
+    }
+
+    #[test]
+    fn it_should_form_teams_once_the_4th_seat_is_filled() {
+        let game_state = bot_game_state();
+
+        assert!(matches!(game_state.stage, PrivateGameStage::Teams(_)));
+        assert_eq!(
+            game_state.add_bot("1", BotDifficulty::Normal),
+            Err(TichuError::GameFull)
+        );
+    }
+
+    #[test]
+    fn it_should_never_make_bots_the_owner() {
+        let game_state = bot_game_state();
+
+        assert_eq!(game_state.reassign_owner(), Err(TichuError::UserNotFound));
+    }
+
+    #[test]
+    fn it_should_play_a_whole_game_with_only_legal_moves() {
+        let game_state = bot_game_state().start_grand_tichu("1").unwrap();
+        let game_state = play_game_with_bots(game_state);
+
+        assert!(!game_state.round_summaries.is_empty());
+        assert_eq!(
+            PrivateGameState::replay(&game_state.events).unwrap(),
+            game_state
+        );
+    }
+
+    #[test]
+    fn it_should_decline_grand_tichu_and_trade_when_time_runs_out() {
+        let game_state = bot_game_state().start_grand_tichu("1").unwrap();
+        let game_state = time_out_every_user(&game_state, 0);
+        assert!(matches!(game_state.stage, PrivateGameStage::Trade(_)));
+
+        let game_state = time_out_every_user(&game_state, 1);
+        assert!(matches!(game_state.stage, PrivateGameStage::Play(_)));
+    }
+
+    #[test]
+    fn it_should_lead_with_the_smallest_play_and_pass_when_time_runs_out() {
+        let game_state = bot_game_state().start_grand_tichu("1").unwrap();
+        let game_state = time_out_every_user(&game_state, 0);
+        let game_state = time_out_every_user(&game_state, 1);
+        let turn_user_id = match &game_state.stage {
+            PrivateGameStage::Play(play_state) => play_state.turn_user_id.clone(),
+            _ => panic!("Game should be in the Play stage"),
+        };
+
+        let observation = game_state.get_observation(&turn_user_id).unwrap();
+        let cards = match get_timeout_action(&observation, 0) {
+            Some(CTSMsg::PlayCards { cards, .. }) => cards,
+            msg => panic!("Leading user should play a card, not {:?}", msg),
+        };
+        assert!(cards == vec![MAH_JONG] || cards == vec![DOG]);
+
+        let game_state = game_state.play_cards(&turn_user_id, cards, None).unwrap();
+        let next_turn_user_id = match &game_state.stage {
+            PrivateGameStage::Play(play_state) => play_state.turn_user_id.clone(),
+            _ => panic!("Game should be in the Play stage"),
+        };
+        if next_turn_user_id != turn_user_id {
+            let observation = game_state.get_observation(&next_turn_user_id).unwrap();
+            assert_eq!(get_timeout_action(&observation, 0), Some(CTSMsg::Pass));
+        }
+        let observation = game_state.get_observation(&turn_user_id).unwrap();
+        assert_eq!(get_timeout_action(&observation, 0), None);
+    }
+
+    #[test]
+    fn it_should_finish_a_game_when_every_user_runs_out_of_time() {
+        let mut game_state = bot_game_state().start_grand_tichu("1").unwrap();
+        let mut seed = 0;
+        while !matches!(game_state.stage, PrivateGameStage::Score(_)) {
+            game_state = time_out_every_user(&game_state, seed);
+            seed += 1;
+            assert!(seed < 100_000, "Game should have ended");
+        }
+
+        assert_eq!(
+            PrivateGameState::replay(&game_state.events).unwrap(),
+            game_state
+        );
+    }
+
+    #[test]
+    fn it_should_restart_the_turn_clock_after_every_event() {
+        let mut game_state = bot_game_state().start_grand_tichu("1").unwrap();
+        game_state.turn_deadline = Some(1_000);
+        assert_eq!(
+            game_state.to_public_game_state("1").unwrap().turn_deadline,
+            Some(1_000)
+        );
+
+        let game_state = game_state.call_small_tichu("1").unwrap();
+        assert_eq!(game_state.turn_deadline, None);
+    }
+}
@@ -0,0 +1,228 @@
+use common::{get_card_combination, next_combo_beats_prev, Card, CardSet, Deck, ValidCardCombo};
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use std::collections::BTreeSet;
+
+/// Generates a hand of `number_of_cards` cards, and a different hand of 10 cards to play a combo
+/// from, out of the lowest `number_of_values` values plus the special cards,
+/// so that most hands can form several multi-card combos
+fn random_hands(
+    rng: &mut ChaCha8Rng,
+    number_of_cards: usize,
+    number_of_values: u8,
+) -> (Vec<Card>, Vec<Card>) {
+    let mut cards: Vec<Card> = Deck::new()
+        .0
+        .into_iter()
+        .filter(|card| card.suit.is_special() || card.value.0 < 2 + number_of_values)
+        .collect();
+    cards.shuffle(rng);
+    let other_hand = cards
+        .split_off(number_of_cards)
+        .into_iter()
+        .take(10)
+        .collect();
+    (cards, other_hand)
+}
+
+/// Every combo that can be played, found the slow way: by trying every subset of the hand
+fn get_every_playable_subset(
+    hand: &[Card],
+    table_top: Option<&ValidCardCombo>,
+) -> BTreeSet<CardSet> {
+    (1..=hand.len())
+        .flat_map(|number_of_cards| hand.iter().cloned().combinations(number_of_cards))
+        .filter(|cards| {
+            get_card_combination(table_top, cards, "").is_some_and(|combo| {
+                let is_same_length =
+                    table_top.is_none_or(|table_top| table_top.cards().len() == cards.len());
+                (is_same_length || combo.is_bomb()) && next_combo_beats_prev(&table_top, &combo)
+            })
+        })
+        .map(|cards| CardSet::from(&cards))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_card_set {
+    use common::{Card, CardSet, CardSuit, CardValue, Deck, DOG, DRAGON, PHOENIX};
+
+    fn card(suit: CardSuit, value: u8) -> Card {
+        Card {
+            suit,
+            value: CardValue(value),
+        }
+    }
+
+    #[test]
+    fn it_should_hold_every_card_of_the_deck() {
+        let deck = Deck::new().0;
+        let card_set = CardSet::from(&deck);
+
+        assert_eq!(card_set, CardSet::full());
+        assert_eq!(card_set.len(), 56);
+        for card in deck.iter() {
+            assert!(card_set.contains(card));
+            assert_eq!(CardSet::from(card).to_cards(), vec![card.clone()]);
+        }
+        assert_eq!(
+            card_set.iter().collect::<Vec<Card>>().len(),
+            56,
+            "Every card should be listed once"
+        );
+    }
+
+    #[test]
+    fn it_should_list_cards_by_value_and_special_cards_last() {
+        let card_set = CardSet::from(&vec![
+            DRAGON,
+            card(CardSuit::Star, 3),
+            card(CardSuit::Sword, 14),
+            card(CardSuit::Jade, 3),
+        ]);
+
+        assert_eq!(
+            card_set.to_cards(),
+            vec![
+                card(CardSuit::Jade, 3),
+                card(CardSuit::Star, 3),
+                card(CardSuit::Sword, 14),
+                DRAGON
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_never_contain_cards_outside_of_the_deck() {
+        let card_set = CardSet::full();
+
+        assert!(!card_set.contains(&card(CardSuit::Sword, 15)));
+        assert!(!card_set.contains(&card(CardSuit::Jade, 1)));
+        assert!(!card_set.contains(&card(CardSuit::Dog, 2)));
+    }
+
+    #[test]
+    fn it_should_combine_sets() {
+        let mut hand = CardSet::from(&vec![card(CardSuit::Sword, 5), DOG, PHOENIX]);
+        let played = CardSet::from(&vec![DOG]);
+
+        assert_eq!(
+            hand - played,
+            CardSet::from(&vec![card(CardSuit::Sword, 5), PHOENIX])
+        );
+        assert_eq!(hand & played, played);
+        assert_eq!((hand | !hand), CardSet::full());
+        assert!(played.is_subset(&hand));
+        assert!(!hand.is_subset(&played));
+
+        assert!(hand.remove(&DOG));
+        assert!(!hand.remove(&DOG));
+        assert!(hand.insert(&DOG));
+        assert!(!hand.insert(&DOG));
+    }
+
+    #[test]
+    fn it_should_group_cards_by_value_and_suit() {
+        let hand = CardSet::from(&vec![
+            card(CardSuit::Sword, 5),
+            card(CardSuit::Star, 5),
+            card(CardSuit::Star, 9),
+            PHOENIX,
+        ]);
+
+        assert_eq!(hand.count_of_value(&CardValue(5)), 2);
+        assert_eq!(hand.count_of_value(&CardValue(0)), 0);
+        assert_eq!(hand.get_value_counts()[9], 1);
+        assert_eq!(
+            hand & CardSet::of_suit(&CardSuit::Star),
+            CardSet::from(&vec![card(CardSuit::Star, 5), card(CardSuit::Star, 9)])
+        );
+        assert!((hand & CardSet::of_suit(&CardSuit::Star)).is_one_suit());
+        assert!(!hand.is_one_suit());
+        assert_eq!(hand.combinations(2).len(), 6);
+    }
+}
+
+#[cfg(test)]
+mod test_legal_plays_against_every_subset {
+    use crate::{get_every_playable_subset, random_hands};
+    use common::{
+        get_user_can_play_wished_for_card, legal_plays, CardSet, CardValue, ValidCardCombo,
+    };
+    use rand::{seq::SliceRandom, Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::BTreeSet;
+
+    const NUM_HANDS: u64 = 500;
+
+    #[test]
+    fn it_should_find_every_playable_combo() {
+        for seed in 0..NUM_HANDS {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let number_of_values = rng.gen_range(5..=13);
+            let number_of_cards = rng.gen_range(1..=12);
+            let (hand, other_hand) = random_hands(&mut rng, number_of_cards, number_of_values);
+
+            // lead, or follow a combo that was played from another hand
+            let table_top: Option<ValidCardCombo> = if rng.gen() {
+                None
+            } else {
+                legal_plays(&other_hand, None, None)
+                    .choose(&mut rng)
+                    .cloned()
+            };
+
+            let legal_plays: Vec<CardSet> = legal_plays(&hand, table_top.as_ref(), None)
+                .iter()
+                .map(|combo| CardSet::from(combo.cards()))
+                .collect();
+            let distinct_legal_plays: BTreeSet<CardSet> = legal_plays.iter().cloned().collect();
+
+            assert_eq!(
+                legal_plays.len(),
+                distinct_legal_plays.len(),
+                "Combos should be listed once for seed {seed}"
+            );
+            assert_eq!(
+                distinct_legal_plays,
+                get_every_playable_subset(&hand, table_top.as_ref()),
+                "Hand {:?} on {:?} (seed {seed})",
+                hand,
+                table_top
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_know_whether_the_wish_can_be_fulfilled() {
+        for seed in 0..NUM_HANDS {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let number_of_values = rng.gen_range(5..=13);
+            let number_of_cards = rng.gen_range(1..=12);
+            let (hand, other_hand) = random_hands(&mut rng, number_of_cards, number_of_values);
+            let table_top = legal_plays(&other_hand, None, None)
+                .choose(&mut rng)
+                .cloned();
+            let wished_for_card_value = CardValue(rng.gen_range(2..2 + number_of_values));
+
+            let wished_for_cards = CardSet::of_value(&wished_for_card_value);
+            let can_play_wish = get_every_playable_subset(&hand, table_top.as_ref())
+                .into_iter()
+                .any(|card_set| !(card_set & wished_for_cards).is_empty());
+
+            assert_eq!(
+                get_user_can_play_wished_for_card(
+                    table_top.as_ref(),
+                    &hand,
+                    &wished_for_card_value
+                ),
+                can_play_wish,
+                "Hand {:?} on {:?} with a wish for {} (seed {seed})",
+                hand,
+                table_top,
+                wished_for_card_value
+            );
+        }
+    }
+}
@@ -6234,3 +6234,208 @@ mod test_get_user_can_play_wished_for_card {
         ));
     }
 }
+
+#[cfg(test)]
+mod test_validate_cards_in_hand {
+    use common::{validate_cards_in_hand, Card, CardSuit, CardValue, TichuError, DRAGON};
+
+    fn hand() -> Vec<Card> {
+        vec![
+            Card {
+                suit: CardSuit::Jade,
+                value: CardValue(2),
+            },
+            Card {
+                suit: CardSuit::Sword,
+                value: CardValue(2),
+            },
+            Card {
+                suit: CardSuit::Star,
+                value: CardValue(14),
+            },
+        ]
+    }
+
+    #[test]
+    fn it_should_accept_cards_in_hand() {
+        assert_eq!(validate_cards_in_hand(&hand(), &hand()[..2]), Ok(()));
+        assert_eq!(validate_cards_in_hand(&hand(), &[]), Ok(()));
+    }
+
+    #[test]
+    fn it_should_reject_cards_not_in_hand() {
+        assert_eq!(
+            validate_cards_in_hand(&hand(), &[hand()[0].clone(), DRAGON]),
+            Err(TichuError::CardNotInHand(DRAGON))
+        );
+
+        // same value, different suit
+        let card = Card {
+            suit: CardSuit::Pagoda,
+            value: CardValue(2),
+        };
+        assert_eq!(
+            validate_cards_in_hand(&hand(), std::slice::from_ref(&card)),
+            Err(TichuError::CardNotInHand(card))
+        );
+    }
+
+    #[test]
+    fn it_should_reject_duplicate_cards() {
+        assert_eq!(
+            validate_cards_in_hand(&hand(), &[hand()[2].clone(), hand()[2].clone()]),
+            Err(TichuError::DuplicateCard(hand()[2].clone()))
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_legal_plays {
+    use common::{
+        get_card_combination, get_user_can_pass, legal_plays, Card, CardSuit, CardValue,
+        ValidCardCombo, DOG, DRAGON, MAH_JONG, PHOENIX,
+    };
+
+    fn card(suit: CardSuit, value: u8) -> Card {
+        Card {
+            suit,
+            value: CardValue(value),
+        }
+    }
+
+    fn combo(cards: Vec<Card>) -> ValidCardCombo {
+        get_card_combination(None, &cards, "1").expect("Cards should form a valid combo")
+    }
+
+    fn played_cards(legal_plays: &[ValidCardCombo]) -> Vec<Vec<Card>> {
+        legal_plays
+            .iter()
+            .map(|combo| combo.cards().clone())
+            .collect()
+    }
+
+    #[test]
+    fn it_should_list_every_combo_when_leading() {
+        let hand = vec![
+            card(CardSuit::Sword, 2),
+            card(CardSuit::Jade, 2),
+            card(CardSuit::Sword, 3),
+            DOG,
+        ];
+        let legal_plays = legal_plays(&hand, None, None);
+
+        // 4 singles and 1 pair: the Dog can't be combined with other cards
+        assert_eq!(legal_plays.len(), 5);
+        assert_eq!(
+            legal_plays
+                .iter()
+                .filter(|combo| matches!(combo, ValidCardCombo::Single(_)))
+                .count(),
+            4
+        );
+        assert!(played_cards(&legal_plays)
+            .contains(&vec![card(CardSuit::Sword, 2), card(CardSuit::Jade, 2)]));
+    }
+
+    #[test]
+    fn it_should_only_list_combos_that_beat_the_table() {
+        let hand = vec![
+            card(CardSuit::Sword, 4),
+            card(CardSuit::Sword, 9),
+            card(CardSuit::Jade, 9),
+            DOG,
+            DRAGON,
+        ];
+        let table_top = combo(vec![card(CardSuit::Star, 8)]);
+        let legal_plays = legal_plays(&hand, Some(&table_top), None);
+
+        assert_eq!(
+            played_cards(&legal_plays),
+            vec![
+                vec![card(CardSuit::Sword, 9)],
+                vec![card(CardSuit::Jade, 9)],
+                vec![DRAGON]
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_complete_combos_with_the_phoenix() {
+        let hand = vec![card(CardSuit::Sword, 5), PHOENIX, MAH_JONG];
+        let table_top = combo(vec![card(CardSuit::Sword, 4), card(CardSuit::Jade, 4)]);
+        assert_eq!(
+            played_cards(&legal_plays(&hand, Some(&table_top), None)),
+            vec![vec![card(CardSuit::Sword, 5), PHOENIX]]
+        );
+
+        // the Phoenix can't beat the Dragon
+        let table_top = combo(vec![DRAGON]);
+        assert!(legal_plays(&hand, Some(&table_top), None).is_empty());
+    }
+
+    #[test]
+    fn it_should_list_bombs_on_any_combo() {
+        let hand = vec![
+            card(CardSuit::Sword, 2),
+            card(CardSuit::Jade, 2),
+            card(CardSuit::Pagoda, 2),
+            card(CardSuit::Star, 2),
+            card(CardSuit::Sword, 3),
+        ];
+        let table_top = combo(vec![card(CardSuit::Star, 14)]);
+        let legal_plays = legal_plays(&hand, Some(&table_top), None);
+
+        assert_eq!(legal_plays.len(), 1);
+        assert!(matches!(legal_plays[0], ValidCardCombo::BombOf4(_)));
+    }
+
+    #[test]
+    fn it_should_only_list_combos_that_fulfill_the_wish() {
+        let hand = vec![card(CardSuit::Sword, 7), card(CardSuit::Sword, 9)];
+        let table_top = combo(vec![card(CardSuit::Star, 3)]);
+        let wish = CardValue(7);
+
+        assert_eq!(
+            played_cards(&legal_plays(&hand, Some(&table_top), Some(&wish))),
+            vec![vec![card(CardSuit::Sword, 7)]]
+        );
+        assert!(!get_user_can_pass(
+            "2",
+            Some(&table_top),
+            &hand,
+            Some(&wish)
+        ));
+
+        // the wish can't be fulfilled, so any play is allowed
+        let table_top = combo(vec![card(CardSuit::Star, 8)]);
+        assert_eq!(
+            played_cards(&legal_plays(&hand, Some(&table_top), Some(&wish))),
+            vec![vec![card(CardSuit::Sword, 9)]]
+        );
+        assert!(get_user_can_pass("2", Some(&table_top), &hand, Some(&wish)));
+        assert!(get_user_can_pass("2", Some(&table_top), &hand, None));
+    }
+
+    #[test]
+    fn it_should_not_let_the_leading_user_pass() {
+        let hand = vec![card(CardSuit::Sword, 7), card(CardSuit::Sword, 9)];
+
+        assert!(!get_user_can_pass("2", None, &hand, None));
+        assert!(!get_user_can_pass("2", None, &hand, Some(&CardValue(8))));
+    }
+
+    #[test]
+    fn it_should_let_users_take_their_own_trick_despite_the_wish() {
+        let hand = vec![card(CardSuit::Sword, 7), card(CardSuit::Sword, 9)];
+        let table_top = combo(vec![card(CardSuit::Star, 3)]);
+        let wish = CardValue(7);
+
+        assert!(get_user_can_pass("1", Some(&table_top), &hand, Some(&wish)));
+        assert!(!get_user_can_pass(
+            "2",
+            Some(&table_top),
+            &hand,
+            Some(&wish)
+        ));
+    }
+}
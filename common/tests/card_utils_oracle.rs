@@ -0,0 +1,612 @@
+//! Checks `get_card_combination` and `next_combo_beats_prev` against a slow but simple reference
+//! implementation, using randomly generated hands and combos.
+//!
+//! Every generator is seeded, so a failing case can always be reproduced.
+
+use common::{Card, CardSuit, CardValue, Deck, ValidCardCombo, PHOENIX};
+use rand::{seq::SliceRandom, Rng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::BTreeMap;
+
+const STANDARD_SUITS: [CardSuit; 4] = [
+    CardSuit::Sword,
+    CardSuit::Jade,
+    CardSuit::Pagoda,
+    CardSuit::Star,
+];
+
+/// Singles are worth half steps, so that a Phoenix can sit between 2 standard cards
+const DOG_HALF_STEPS: u8 = 0;
+const MAH_JONG_HALF_STEPS: u8 = 2;
+const DRAGON_HALF_STEPS: u8 = 100;
+
+/// What a set of cards can be played as, without any of the engine's shortcuts.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+enum ReferenceCombo {
+    Single {
+        half_steps: u8,
+    },
+    Pair {
+        value: u8,
+    },
+    Trio {
+        value: u8,
+    },
+    BombOf4 {
+        value: u8,
+    },
+    FullHouse {
+        trio_value: u8,
+    },
+    Sequence {
+        number_of_cards: u8,
+        starting_value: u8,
+    },
+    SequenceBomb {
+        number_of_cards: u8,
+        starting_value: u8,
+    },
+    SequenceOfPairs {
+        number_of_pairs: u8,
+        starting_value: u8,
+    },
+}
+
+/// Works out what `cards` can be played as by trying the Phoenix as every standard value.
+///
+/// When there is more than one way to play the cards, the highest one is chosen.
+fn get_reference_combo(prev: Option<&ReferenceCombo>, cards: &[Card]) -> Option<ReferenceCombo> {
+    if let [card] = cards {
+        let half_steps = match card.suit {
+            CardSuit::Dog => DOG_HALF_STEPS,
+            CardSuit::MahJong => MAH_JONG_HALF_STEPS,
+            CardSuit::Dragon => DRAGON_HALF_STEPS,
+            // half a step above the single it's played on
+            CardSuit::Phoenix => match prev {
+                None => MAH_JONG_HALF_STEPS + 1,
+                Some(ReferenceCombo::Single { half_steps })
+                    if *half_steps < DRAGON_HALF_STEPS && half_steps % 2 == 0 =>
+                {
+                    half_steps.max(&MAH_JONG_HALF_STEPS) + 1
+                }
+                Some(_) => return None,
+            },
+            _ => card.value.0 * 2,
+        };
+        return Some(ReferenceCombo::Single { half_steps });
+    }
+
+    let includes_phoenix = cards.contains(&PHOENIX);
+    let standard_cards: Vec<&Card> = cards.iter().filter(|card| **card != PHOENIX).collect();
+    if cards.is_empty() || standard_cards.iter().any(|card| card.suit.is_special()) {
+        return None;
+    }
+
+    let standard_values: Vec<u8> = standard_cards.iter().map(|card| card.value.0).collect();
+    let phoenix_values: Vec<Option<u8>> = if includes_phoenix {
+        // only a Sequence can start with the Phoenix as a 1, since no other card has that value
+        (1..=14).map(Some).collect()
+    } else {
+        vec![None]
+    };
+    let all_same_suit = standard_cards
+        .iter()
+        .all(|card| card.suit == standard_cards[0].suit);
+
+    phoenix_values
+        .into_iter()
+        .filter_map(|phoenix_value| {
+            let mut values = standard_values.clone();
+            values.extend(phoenix_value);
+            values.sort();
+            classify_values(
+                &values,
+                !includes_phoenix && all_same_suit,
+                includes_phoenix,
+            )
+        })
+        .max()
+}
+
+/// Classifies standard card values by counting them
+fn classify_values(
+    values: &[u8],
+    is_one_suit: bool,
+    includes_phoenix: bool,
+) -> Option<ReferenceCombo> {
+    let mut counts: BTreeMap<u8, usize> = BTreeMap::new();
+    for value in values {
+        *counts.entry(*value).or_default() += 1;
+    }
+    let distinct_values: Vec<u8> = counts.keys().copied().collect();
+    let are_adjacent = distinct_values
+        .windows(2)
+        .all(|pair| pair[0] + 1 == pair[1]);
+    let len = values.len();
+
+    match (len, counts.len()) {
+        (2, 1) => Some(ReferenceCombo::Pair { value: values[0] }),
+        (3, 1) => Some(ReferenceCombo::Trio { value: values[0] }),
+        (4, 1) if !includes_phoenix => Some(ReferenceCombo::BombOf4 { value: values[0] }),
+        (5, 2) => counts
+            .iter()
+            .find(|(_, count)| **count == 3)
+            .map(|(value, _)| ReferenceCombo::FullHouse { trio_value: *value }),
+        (5.., _) if counts.len() == len && are_adjacent => {
+            if is_one_suit {
+                Some(ReferenceCombo::SequenceBomb {
+                    number_of_cards: len as u8,
+                    starting_value: values[0],
+                })
+            } else {
+                Some(ReferenceCombo::Sequence {
+                    number_of_cards: len as u8,
+                    starting_value: values[0],
+                })
+            }
+        }
+        (4.., _)
+            if len.is_multiple_of(2)
+                && counts.values().all(|count| *count == 2)
+                && are_adjacent =>
+        {
+            Some(ReferenceCombo::SequenceOfPairs {
+                number_of_pairs: (len / 2) as u8,
+                starting_value: values[0],
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Whether `next` can be played on top of `prev`, according to the rules as written
+fn get_reference_beats(prev: &ReferenceCombo, next: &ReferenceCombo) -> bool {
+    use ReferenceCombo::*;
+
+    match (prev, next) {
+        (
+            SequenceBomb {
+                number_of_cards: prev_number_of_cards,
+                starting_value: prev_starting_value,
+            },
+            SequenceBomb {
+                number_of_cards,
+                starting_value,
+            },
+        ) => (number_of_cards, starting_value) > (prev_number_of_cards, prev_starting_value),
+        (_, SequenceBomb { .. }) => true,
+        (SequenceBomb { .. }, _) => false,
+        (BombOf4 { value: prev_value }, BombOf4 { value }) => value > prev_value,
+        (_, BombOf4 { .. }) => true,
+        (BombOf4 { .. }, _) => false,
+        (
+            Single {
+                half_steps: prev_half_steps,
+            },
+            Single { half_steps },
+        ) => half_steps > prev_half_steps,
+        (Pair { value: prev_value }, Pair { value }) => value > prev_value,
+        (Trio { value: prev_value }, Trio { value }) => value > prev_value,
+        (
+            FullHouse {
+                trio_value: prev_trio_value,
+            },
+            FullHouse { trio_value },
+        ) => trio_value > prev_trio_value,
+        (
+            Sequence {
+                number_of_cards: prev_number_of_cards,
+                starting_value: prev_starting_value,
+            },
+            Sequence {
+                number_of_cards,
+                starting_value,
+            },
+        ) => number_of_cards == prev_number_of_cards && starting_value > prev_starting_value,
+        (
+            SequenceOfPairs {
+                number_of_pairs: prev_number_of_pairs,
+                starting_value: prev_starting_value,
+            },
+            SequenceOfPairs {
+                number_of_pairs,
+                starting_value,
+            },
+        ) => number_of_pairs == prev_number_of_pairs && starting_value > prev_starting_value,
+        _ => false,
+    }
+}
+
+/// Describes a combo from the engine in the same terms as the reference implementation
+fn to_reference_combo(combo: &ValidCardCombo) -> ReferenceCombo {
+    match combo {
+        ValidCardCombo::Single(single) => ReferenceCombo::Single {
+            half_steps: match single.cards[0].suit {
+                CardSuit::Dog => DOG_HALF_STEPS,
+                CardSuit::MahJong => MAH_JONG_HALF_STEPS,
+                CardSuit::Dragon => DRAGON_HALF_STEPS,
+                CardSuit::Phoenix => single.value.0 * 2 + 1,
+                _ => single.value.0 * 2,
+            },
+        },
+        ValidCardCombo::Pair(pair) => ReferenceCombo::Pair {
+            value: pair.value.0,
+        },
+        ValidCardCombo::Trio(trio) => ReferenceCombo::Trio {
+            value: trio.value.0,
+        },
+        ValidCardCombo::BombOf4(bomb_of_4) => ReferenceCombo::BombOf4 {
+            value: bomb_of_4.value.0,
+        },
+        ValidCardCombo::FullHouse(full_house) => ReferenceCombo::FullHouse {
+            trio_value: full_house.trio_value.0,
+        },
+        ValidCardCombo::Sequence(sequence) => ReferenceCombo::Sequence {
+            number_of_cards: sequence.number_of_cards,
+            starting_value: sequence.starting_value.0,
+        },
+        ValidCardCombo::SequenceBomb(sequence_bomb) => ReferenceCombo::SequenceBomb {
+            number_of_cards: sequence_bomb.number_of_cards,
+            starting_value: sequence_bomb.starting_value.0,
+        },
+        ValidCardCombo::SequenceOfPairs(sequence_of_pairs) => ReferenceCombo::SequenceOfPairs {
+            number_of_pairs: sequence_of_pairs.number_of_pairs,
+            starting_value: sequence_of_pairs.starting_value.0,
+        },
+    }
+}
+
+/// Generates `number_of_cards` different cards from anywhere in the deck
+fn random_cards(rng: &mut ChaCha8Rng, number_of_cards: usize) -> Vec<Card> {
+    let mut cards = Deck::new().0;
+    cards.shuffle(rng);
+    cards.truncate(number_of_cards);
+    cards
+}
+
+/// Generates `number_of_values` adjacent standard values, with `cards_per_value` cards of each
+fn random_adjacent_cards(
+    rng: &mut ChaCha8Rng,
+    number_of_values: u8,
+    cards_per_value: usize,
+    is_one_suit: bool,
+) -> Vec<Card> {
+    let starting_value = rng.gen_range(2..=(15 - number_of_values));
+    let one_suit = STANDARD_SUITS.choose(rng).unwrap().clone();
+    let mut cards = Vec::new();
+    for value in starting_value..(starting_value + number_of_values) {
+        let mut suits = if is_one_suit {
+            vec![one_suit.clone()]
+        } else {
+            STANDARD_SUITS.to_vec()
+        };
+        suits.shuffle(rng);
+        cards.extend(suits.into_iter().take(cards_per_value).map(|suit| Card {
+            suit,
+            value: CardValue(value),
+        }));
+    }
+    cards
+}
+
+/// Generates the cards for a random valid combo made of standard cards (before any Phoenix is added)
+fn random_combo_cards(rng: &mut ChaCha8Rng) -> Vec<Card> {
+    match rng.gen_range(0..8) {
+        0 => random_adjacent_cards(rng, 1, 1, false),
+        1 => random_adjacent_cards(rng, 1, 2, false),
+        2 => random_adjacent_cards(rng, 1, 3, false),
+        3 => random_adjacent_cards(rng, 1, 4, false),
+        4 => {
+            let mut cards = random_adjacent_cards(rng, 1, 3, false);
+            let pair = loop {
+                let pair = random_adjacent_cards(rng, 1, 2, false);
+                if pair[0].value != cards[0].value {
+                    break pair;
+                }
+            };
+            cards.extend(pair);
+            cards
+        }
+        5 => {
+            let number_of_cards = rng.gen_range(5..=13);
+            random_adjacent_cards(rng, number_of_cards, 1, false)
+        }
+        6 => {
+            let number_of_cards = rng.gen_range(5..=13);
+            random_adjacent_cards(rng, number_of_cards, 1, true)
+        }
+        _ => {
+            let number_of_pairs = rng.gen_range(2..=7);
+            random_adjacent_cards(rng, number_of_pairs, 2, false)
+        }
+    }
+}
+
+/// Generates a random valid combo, which uses the Phoenix half of the time.
+///
+/// The Phoenix either replaces one of the cards, or is added on.
+fn random_combo(rng: &mut ChaCha8Rng, prev: Option<&ValidCardCombo>) -> Option<ValidCardCombo> {
+    let mut cards = random_combo_cards(rng);
+    if rng.gen() {
+        if rng.gen() && cards.len() > 1 {
+            let i = rng.gen_range(0..cards.len());
+            cards[i] = PHOENIX;
+        } else {
+            cards.push(PHOENIX);
+        }
+    }
+    if cards.len() == 1 && rng.gen_range(0..4) == 0 {
+        cards = random_cards(rng, 1);
+    }
+    cards.shuffle(rng);
+    common::get_card_combination(prev, &cards, "1")
+}
+
+/// Checks the engine's combination against the reference for the same cards
+fn assert_same_combo(prev: Option<&ValidCardCombo>, cards: &[Card]) {
+    let combo = common::get_card_combination(prev, &cards.to_vec(), "1");
+    let reference_prev = prev.map(to_reference_combo);
+    let reference_combo = get_reference_combo(reference_prev.as_ref(), cards);
+
+    assert_eq!(
+        combo.as_ref().map(to_reference_combo),
+        reference_combo,
+        "\n\nCards: {:?}\nPrevious combo: {:?}\nCombo received: {:?}\n\n",
+        cards,
+        prev,
+        combo
+    );
+}
+
+#[cfg(test)]
+mod test_get_card_combination_against_reference {
+    use crate::{assert_same_combo, random_cards, random_combo_cards};
+    use common::{get_card_combination, Deck, PHOENIX};
+    use itertools::Itertools;
+    use rand::{seq::SliceRandom, Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn it_should_agree_on_every_combination_of_up_to_3_cards() {
+        let deck = Deck::new().0;
+        for number_of_cards in 1..=3 {
+            for cards in deck.iter().cloned().combinations(number_of_cards) {
+                assert_same_combo(None, &cards);
+            }
+        }
+    }
+
+    #[test]
+    fn it_should_agree_on_random_cards() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..20_000 {
+            let number_of_cards = rng.gen_range(4..=14);
+            assert_same_combo(None, &random_cards(&mut rng, number_of_cards));
+        }
+    }
+
+    #[test]
+    fn it_should_agree_on_random_combos() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for _ in 0..20_000 {
+            let mut cards = random_combo_cards(&mut rng);
+            cards.shuffle(&mut rng);
+            assert_same_combo(None, &cards);
+        }
+    }
+
+    #[test]
+    fn it_should_agree_on_every_placement_of_the_phoenix() {
+        let mut rng = ChaCha8Rng::seed_from_u64(2);
+        for _ in 0..5_000 {
+            let cards = random_combo_cards(&mut rng);
+
+            // in place of each card
+            for i in 0..cards.len() {
+                let mut cards = cards.clone();
+                cards[i] = PHOENIX;
+                cards.shuffle(&mut rng);
+                assert_same_combo(None, &cards);
+            }
+
+            // in addition to the other cards
+            let mut cards = cards.clone();
+            cards.push(PHOENIX);
+            cards.shuffle(&mut rng);
+            assert_same_combo(None, &cards);
+        }
+    }
+
+    #[test]
+    fn it_should_agree_on_a_phoenix_played_on_any_single() {
+        for card in Deck::new().0 {
+            let prev = get_card_combination(None, &vec![card], "1").unwrap();
+            assert_same_combo(Some(&prev), &[PHOENIX]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_next_combo_beats_prev_against_reference {
+    use crate::{get_reference_beats, random_combo, to_reference_combo};
+    use common::{next_combo_beats_prev, ValidCardCombo};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn it_should_agree_on_random_combos() {
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        let mut number_of_checks = 0;
+        while number_of_checks < 50_000 {
+            let prev = if let Some(prev) = random_combo(&mut rng, None) {
+                prev
+            } else {
+                continue;
+            };
+            let next = if let Some(next) = random_combo(&mut rng, Some(&prev)) {
+                next
+            } else {
+                continue;
+            };
+
+            // both combos have to be playable in the same game
+            if prev.cards().iter().any(|card| next.cards().contains(card)) {
+                continue;
+            }
+            // nothing can be played on the Dog: it passes the lead
+            if matches!(&prev, ValidCardCombo::Single(single) if single.cards[0].suit == common::CardSuit::Dog)
+            {
+                continue;
+            }
+
+            assert_eq!(
+                next_combo_beats_prev(&Some(&prev), &next),
+                get_reference_beats(&to_reference_combo(&prev), &to_reference_combo(&next)),
+                "\n\nPrevious combo: {:?}\nNext combo: {:?}\n\n",
+                prev,
+                next
+            );
+            number_of_checks += 1;
+        }
+    }
+
+    #[test]
+    fn it_should_let_any_combo_be_led() {
+        let mut rng = ChaCha8Rng::seed_from_u64(4);
+        for _ in 0..1_000 {
+            if let Some(combo) = random_combo(&mut rng, None) {
+                assert!(next_combo_beats_prev(&None, &combo));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_combo_ordering {
+    use crate::{random_combo, to_reference_combo};
+    use common::{CardSuit, ValidCardCombo};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::BTreeMap;
+
+    /// Checks that `cmp` is a total order on `combos`, and that it agrees with
+    /// `partial_cmp`, `eq` and (optionally) the reference ordering.
+    fn assert_total_order<T: Ord + std::fmt::Debug>(
+        combos: &[(T, ValidCardCombo)],
+        should_match_reference: bool,
+    ) {
+        for (a, a_combo) in combos {
+            assert_eq!(a.cmp(a), std::cmp::Ordering::Equal, "{:?}", a);
+            for (b, b_combo) in combos {
+                let ordering = a.cmp(b);
+                assert_eq!(ordering, b.cmp(a).reverse(), "{:?} vs {:?}", a, b);
+                assert_eq!(a.partial_cmp(b), Some(ordering), "{:?} vs {:?}", a, b);
+                assert_eq!(a == b, ordering.is_eq(), "{:?} vs {:?}", a, b);
+                if should_match_reference {
+                    assert_eq!(
+                        ordering,
+                        to_reference_combo(a_combo).cmp(&to_reference_combo(b_combo)),
+                        "{:?} vs {:?}",
+                        a,
+                        b
+                    );
+                }
+
+                for (c, _) in combos {
+                    if a <= b && b <= c {
+                        assert!(a <= c, "{:?} <= {:?} <= {:?}", a, b, c);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Groups random combos by type and number of cards, since only those can be compared
+    fn random_combos_by_shape(seed: u64) -> BTreeMap<(u8, usize), Vec<ValidCardCombo>> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut combos_by_shape: BTreeMap<(u8, usize), Vec<ValidCardCombo>> = BTreeMap::new();
+        for _ in 0..3_000 {
+            if let Some(combo) = random_combo(&mut rng, None) {
+                let kind = match &combo {
+                    ValidCardCombo::Single(_) => 0,
+                    ValidCardCombo::Pair(_) => 1,
+                    ValidCardCombo::SequenceOfPairs(_) => 2,
+                    ValidCardCombo::Trio(_) => 3,
+                    ValidCardCombo::BombOf4(_) => 4,
+                    ValidCardCombo::SequenceBomb(_) => 5,
+                    ValidCardCombo::FullHouse(_) => 6,
+                    ValidCardCombo::Sequence(_) => 7,
+                };
+                let combos = combos_by_shape
+                    .entry((kind, combo.cards().len()))
+                    .or_default();
+                // keep every group small enough to check every triple
+                if combos.len() < 40 {
+                    combos.push(combo);
+                }
+            }
+        }
+        combos_by_shape
+    }
+
+    #[test]
+    fn it_should_order_every_type_of_combo_consistently() {
+        for (_, combos) in random_combos_by_shape(5) {
+            let mut singles = Vec::new();
+            let mut pairs = Vec::new();
+            let mut sequences_of_pairs = Vec::new();
+            let mut trios = Vec::new();
+            let mut bombs_of_4 = Vec::new();
+            let mut sequence_bombs = Vec::new();
+            let mut full_houses = Vec::new();
+            let mut sequences = Vec::new();
+            for combo in combos {
+                match &combo {
+                    ValidCardCombo::Single(single) => {
+                        // there is only one of each special card
+                        let is_duplicate_special_card =
+                            singles
+                                .iter()
+                                .any(|(other, _): &(common::Single, ValidCardCombo)| {
+                                    single.cards[0].suit.is_special()
+                                        && other.cards[0].suit == single.cards[0].suit
+                                });
+                        if !is_duplicate_special_card {
+                            singles.push((single.clone(), combo.clone()));
+                        }
+                    }
+                    ValidCardCombo::Pair(pair) => pairs.push((pair.clone(), combo.clone())),
+                    ValidCardCombo::SequenceOfPairs(sequence_of_pairs) => {
+                        sequences_of_pairs.push((sequence_of_pairs.clone(), combo.clone()))
+                    }
+                    ValidCardCombo::Trio(trio) => trios.push((trio.clone(), combo.clone())),
+                    ValidCardCombo::BombOf4(bomb_of_4) => {
+                        bombs_of_4.push((bomb_of_4.clone(), combo.clone()))
+                    }
+                    ValidCardCombo::SequenceBomb(sequence_bomb) => {
+                        sequence_bombs.push((sequence_bomb.clone(), combo.clone()))
+                    }
+                    ValidCardCombo::FullHouse(full_house) => {
+                        full_houses.push((full_house.clone(), combo.clone()))
+                    }
+                    ValidCardCombo::Sequence(sequence) => {
+                        sequences.push((sequence.clone(), combo.clone()))
+                    }
+                }
+            }
+
+            // the Phoenix outranks every standard single, whatever value it was given
+            let has_phoenix = singles
+                .iter()
+                .any(|(single, _)| single.cards[0].suit == CardSuit::Phoenix);
+            assert_total_order(&singles, !has_phoenix);
+            assert_total_order(&pairs, true);
+            assert_total_order(&sequences_of_pairs, true);
+            assert_total_order(&trios, true);
+            assert_total_order(&bombs_of_4, true);
+            assert_total_order(&sequence_bombs, true);
+            assert_total_order(&full_houses, true);
+            assert_total_order(&sequences, true);
+        }
+    }
+}
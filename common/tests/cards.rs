@@ -1397,6 +1397,14 @@ mod test_deck {
             })
         );
     }
+    #[test]
+    fn shuffle_with_seed_should_always_produce_the_same_order() {
+        let deck = Deck::new().shuffle_with_seed(42);
+        assert_eq!(deck, Deck::new().shuffle_with_seed(42));
+        assert_ne!(deck, Deck::new().shuffle_with_seed(43));
+        assert_ne!(deck, Deck::new());
+        assert_eq!(deck.0.len(), 56);
+    }
 }
 
 #[cfg(test)]
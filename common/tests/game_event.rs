@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod test_replay {
+    use common::{
+        CallGrandTichuRequest, CardTrade, GameEvent, PrivateGameStage, PrivateGameState,
+        TeamOption, TichuError, MAH_JONG,
+    };
+    use std::collections::HashMap;
+
+    fn get_turn_user_id(game_state: &PrivateGameState) -> String {
+        if let PrivateGameStage::Play(play_state) = &game_state.stage {
+            play_state.turn_user_id.clone()
+        } else {
+            panic!("Game should be in the Play stage");
+        }
+    }
+
+    /// Plays a game from creation up until the first card is led
+    fn started_game_state() -> PrivateGameState {
+        let user_ids = ["1", "2", "3", "4"];
+        let mut game_state = PrivateGameState::new("1".into(), "User 1".into(), &HashMap::new());
+        for user_id in &user_ids[1..] {
+            game_state = game_state
+                .add_user(user_id.to_string(), format!("User {}", user_id))
+                .unwrap();
+        }
+        game_state = game_state
+            .rename_team(&TeamOption::TeamA, "1", "Dragons")
+            .unwrap()
+            .start_grand_tichu("1")
+            .unwrap();
+        for user_id in user_ids {
+            game_state = game_state
+                .call_grand_tichu(&CallGrandTichuRequest::Decline, user_id)
+                .unwrap();
+        }
+
+        // every user gives their first 3 cards to the other 3 users
+        for (i, user_id) in user_ids.iter().enumerate() {
+            let hand = game_state
+                .get_user_by_user_id(user_id)
+                .unwrap()
+                .hand
+                .clone();
+            let trade = [1, 2, 3].map(|offset| CardTrade {
+                from_user_id: user_id.to_string(),
+                card: hand[offset - 1].clone(),
+                to_user_id: user_ids[(i + offset) % 4].to_string(),
+            });
+            game_state = game_state.submit_trade(user_id, &trade).unwrap();
+        }
+
+        game_state
+    }
+
+    #[test]
+    fn it_should_reproduce_the_game_state_exactly() {
+        let mut game_state = started_game_state();
+        let leader_user_id = get_turn_user_id(&game_state);
+        game_state = game_state
+            .play_cards(&leader_user_id, vec![MAH_JONG], None)
+            .unwrap();
+        // the trick is won once all 4 users in play have passed on it
+        for _ in 0..4 {
+            let turn_user_id = get_turn_user_id(&game_state);
+            game_state = game_state.pass(&turn_user_id).unwrap();
+        }
+
+        assert_eq!(
+            game_state.events.last(),
+            Some(&GameEvent::TrickWon {
+                receiving_user_id: leader_user_id
+            })
+        );
+        assert_eq!(
+            PrivateGameState::replay(&game_state.events).unwrap(),
+            game_state
+        );
+    }
+
+    #[test]
+    fn it_should_record_the_shuffled_deck() {
+        let mut game_state = PrivateGameState::new("1".into(), "User 1".into(), &HashMap::new());
+        for user_id in ["2", "3", "4"] {
+            game_state = game_state
+                .add_user(user_id.into(), format!("User {}", user_id))
+                .unwrap();
+        }
+        let game_state = game_state.start_grand_tichu("1").unwrap();
+
+        assert!(matches!(
+            game_state.events.last(),
+            Some(GameEvent::CardsDealt { .. })
+        ));
+        assert!(game_state
+            .participants
+            .iter()
+            .all(|participant| participant.hand.len() == 9));
+        assert_eq!(
+            PrivateGameState::replay(&game_state.events).unwrap(),
+            game_state
+        );
+    }
+
+    #[test]
+    fn it_should_only_replay_logs_that_start_with_game_created() {
+        let game_state = started_game_state();
+
+        assert!(matches!(
+            PrivateGameState::replay(&game_state.events[1..]),
+            Err(TichuError::Internal(_))
+        ));
+        assert!(matches!(
+            PrivateGameState::replay(&[]),
+            Err(TichuError::Internal(_))
+        ));
+        assert!(matches!(
+            game_state.apply(&game_state.events[0]),
+            Err(TichuError::Internal(_))
+        ));
+    }
+}
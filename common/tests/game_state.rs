@@ -0,0 +1,1347 @@
+use common::{
+    Card, CardSuit, CardTrade, CardValue, Deck, GameRules, ImmutableTeam, PassWithUserId,
+    PrivateGameStage, PrivateGameState, PrivatePlay, PrivateTrade, PrivateUser, Single,
+    SubmitTrade, TichuCallStatus, UserIdWithTichuCallStatus, UserRole, ValidCardCombo, DRAGON,
+};
+
+/// Builds a game in the Play stage where Team A (users "1" and "2") have both gone out
+/// and Team B (users "3" and "4") still have cards in their hands.
+///
+/// User "2" played the last Single on the table, and it is user "3"'s turn.
+fn double_victory_game_state(
+    team_scores: [i32; 2],
+    small_tichus: [TichuCallStatus; 4],
+    grand_tichus: [TichuCallStatus; 4],
+) -> PrivateGameState {
+    let user_ids = ["1", "2", "3", "4"];
+    let make_tichus = |statuses: [TichuCallStatus; 4]| {
+        let [status_1, status_2, status_3, status_4] = statuses;
+        [
+            UserIdWithTichuCallStatus {
+                user_id: "1".into(),
+                tichu_call_status: status_1,
+            },
+            UserIdWithTichuCallStatus {
+                user_id: "2".into(),
+                tichu_call_status: status_2,
+            },
+            UserIdWithTichuCallStatus {
+                user_id: "3".into(),
+                tichu_call_status: status_3,
+            },
+            UserIdWithTichuCallStatus {
+                user_id: "4".into(),
+                tichu_call_status: status_4,
+            },
+        ]
+    };
+
+    let participants = user_ids
+        .iter()
+        .map(|user_id| PrivateUser {
+            user_id: user_id.to_string(),
+            role: if *user_id == "1" {
+                UserRole::Owner
+            } else {
+                UserRole::Participant
+            },
+            display_name: format!("User {}", user_id),
+            tricks: vec![],
+            has_played_first_card: true,
+            hand: if *user_id == "3" || *user_id == "4" {
+                vec![Card {
+                    suit: CardSuit::Sword,
+                    value: CardValue(user_id.parse::<u8>().unwrap()),
+                }]
+            } else {
+                vec![]
+            },
+            bot_difficulty: None,
+        })
+        .collect();
+
+    let play_state = PrivatePlay {
+        small_tichus: make_tichus(small_tichus),
+        grand_tichus: make_tichus(grand_tichus),
+        teams: [
+            ImmutableTeam {
+                id: "a".into(),
+                team_name: "Team A".into(),
+                user_ids: ["1".into(), "2".into()],
+                score: team_scores[0],
+            },
+            ImmutableTeam {
+                id: "b".into(),
+                team_name: "Team B".into(),
+                user_ids: ["3".into(), "4".into()],
+                score: team_scores[1],
+            },
+        ],
+        table: vec![ValidCardCombo::Single(Single {
+            cards: vec![Card {
+                suit: CardSuit::Jade,
+                value: CardValue(14),
+            }],
+            value: CardValue(14),
+            user_id: "2".into(),
+        })],
+        turn_user_id: "3".into(),
+        dragon_winner_user_id: None,
+        wished_for_card_value: None,
+        passes: user_ids.map(|user_id| PassWithUserId {
+            user_id: user_id.into(),
+            passed: false,
+        }),
+        users_in_play: vec!["3".into(), "4".into()],
+        first_user_out: Some("1".into()),
+    };
+
+    PrivateGameState {
+        game_id: "game".into(),
+        game_code: "ABC".into(),
+        owner_id: "1".into(),
+        stage: PrivateGameStage::Play(Box::new(play_state)),
+        participants,
+        round_summaries: vec![],
+        seed: 0,
+        rules: GameRules::default(),
+        turn_deadline: None,
+        events: vec![],
+    }
+}
+
+/// Builds a game in the Play stage where user "4" is the only user left in play.
+///
+/// User "1" went out first and has won a trick with a five, user "3" played a King
+/// that is still on the table, and user "4" holds a two and has won the Dragon.
+fn one_user_left_game_state(
+    team_scores: [i32; 2],
+    small_tichus: [TichuCallStatus; 4],
+    grand_tichus: [TichuCallStatus; 4],
+) -> PrivateGameState {
+    let mut game_state = double_victory_game_state(team_scores, small_tichus, grand_tichus);
+
+    for participant in game_state.participants.iter_mut() {
+        match participant.user_id.as_str() {
+            "1" => {
+                participant.tricks = vec![ValidCardCombo::Single(Single {
+                    cards: vec![Card {
+                        suit: CardSuit::Pagoda,
+                        value: CardValue(5),
+                    }],
+                    value: CardValue(5),
+                    user_id: "1".into(),
+                })]
+            }
+            "3" => participant.hand.clear(),
+            "4" => {
+                participant.hand = vec![Card {
+                    suit: CardSuit::Sword,
+                    value: CardValue(2),
+                }];
+                participant.tricks = vec![ValidCardCombo::Single(Single {
+                    cards: vec![DRAGON],
+                    value: DRAGON.value.clone(),
+                    user_id: "4".into(),
+                })];
+            }
+            _ => {}
+        }
+    }
+
+    if let PrivateGameStage::Play(play_state) = &mut game_state.stage {
+        play_state.table = vec![ValidCardCombo::Single(Single {
+            cards: vec![Card {
+                suit: CardSuit::Jade,
+                value: CardValue(13),
+            }],
+            value: CardValue(13),
+            user_id: "3".into(),
+        })];
+        play_state.turn_user_id = "4".into();
+        play_state.users_in_play = vec!["4".into()];
+    }
+
+    game_state
+}
+
+/// Builds a game in the Trade stage where user "1" holds a two, three, and four of Jade
+/// and nobody has submitted a trade yet.
+fn trade_game_state() -> PrivateGameState {
+    let mut game_state = double_victory_game_state(
+        [0, 0],
+        same_tichu_statuses(TichuCallStatus::Undecided),
+        same_tichu_statuses(TichuCallStatus::Declined),
+    );
+
+    if let PrivateGameStage::Play(play_state) = game_state.stage.clone() {
+        game_state.stage = PrivateGameStage::Trade(Box::new(PrivateTrade {
+            small_tichus: play_state.small_tichus,
+            grand_tichus: play_state.grand_tichus,
+            teams: play_state.teams,
+            deck: Deck(vec![]),
+            trades: [None, None, None, None],
+        }));
+    }
+
+    game_state.participants[0].hand = (2..=4)
+        .map(|value| Card {
+            suit: CardSuit::Jade,
+            value: CardValue(value),
+        })
+        .collect();
+
+    game_state
+}
+
+/// User "1" trades one card to each other user
+fn trade_from_user_1(cards: [Card; 3]) -> SubmitTrade {
+    let [card_2, card_3, card_4] = cards;
+    [("2", card_2), ("3", card_3), ("4", card_4)].map(|(to_user_id, card)| CardTrade {
+        from_user_id: "1".into(),
+        card,
+        to_user_id: to_user_id.into(),
+    })
+}
+
+/// Builds a game in the Play stage where user "1" led the Dragon and every other user has passed.
+///
+/// Every user is still in play, and it is user "1"'s turn.
+fn dragon_trick_game_state() -> PrivateGameState {
+    let mut game_state = double_victory_game_state(
+        [0, 0],
+        same_tichu_statuses(TichuCallStatus::Undecided),
+        same_tichu_statuses(TichuCallStatus::Declined),
+    );
+
+    for participant in game_state.participants.iter_mut() {
+        participant.hand = vec![Card {
+            suit: CardSuit::Pagoda,
+            value: CardValue(participant.user_id.parse::<u8>().unwrap() + 1),
+        }];
+    }
+
+    if let PrivateGameStage::Play(play_state) = &mut game_state.stage {
+        play_state.table = vec![ValidCardCombo::Single(Single {
+            cards: vec![DRAGON],
+            value: DRAGON.value.clone(),
+            user_id: "1".into(),
+        })];
+        play_state.turn_user_id = "1".into();
+        play_state.users_in_play = vec!["1".into(), "3".into(), "2".into(), "4".into()];
+        play_state.first_user_out = None;
+        for pass in play_state.passes.iter_mut() {
+            pass.passed = pass.user_id != "1";
+        }
+    }
+
+    game_state
+}
+
+/// Every user has the same call status
+fn same_tichu_statuses(status: TichuCallStatus) -> [TichuCallStatus; 4] {
+    [(); 4].map(|_| status.clone())
+}
+
+/// Both remaining users pass, which ends the round
+fn finish_round(game_state: PrivateGameState) -> PrivateGameState {
+    game_state
+        .pass("3")
+        .and_then(|game_state| game_state.pass("4"))
+        .expect("Passing should end the round")
+}
+
+#[cfg(test)]
+mod test_round_over_tichu_scoring {
+    use crate::{double_victory_game_state, finish_round};
+    use common::{PrivateGameStage, TichuCallStatus};
+
+    #[test]
+    fn it_should_add_no_tichu_points_when_nobody_called() {
+        let game_state = finish_round(double_victory_game_state(
+            [0, 0],
+            [
+                TichuCallStatus::Declined,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Declined,
+            ],
+            [
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+            ],
+        ));
+
+        if let PrivateGameStage::GrandTichu(grand_tichu) = &game_state.stage {
+            assert_eq!(grand_tichu.teams[0].score, 200);
+            assert_eq!(grand_tichu.teams[1].score, 0);
+        } else {
+            panic!("Expected a new round to start, got {:?}", game_state.stage);
+        }
+    }
+
+    #[test]
+    fn it_should_score_achieved_and_failed_calls_into_the_next_round() {
+        let game_state = finish_round(double_victory_game_state(
+            [0, 0],
+            [
+                TichuCallStatus::Called,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Undecided,
+            ],
+            [
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Called,
+                TichuCallStatus::Declined,
+            ],
+        ));
+
+        if let PrivateGameStage::GrandTichu(grand_tichu) = &game_state.stage {
+            // double victory + Small Tichu
+            assert_eq!(grand_tichu.teams[0].score, 300);
+            // failed Grand Tichu
+            assert_eq!(grand_tichu.teams[1].score, -200);
+        } else {
+            panic!("Expected a new round to start, got {:?}", game_state.stage);
+        }
+    }
+
+    #[test]
+    fn it_should_keep_settled_calls_in_the_round_summary_when_the_next_round_starts() {
+        let game_state = finish_round(double_victory_game_state(
+            [0, 0],
+            [
+                TichuCallStatus::Called,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Called,
+                TichuCallStatus::Undecided,
+            ],
+            [
+                TichuCallStatus::Declined,
+                TichuCallStatus::Called,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+            ],
+        ));
+
+        if let PrivateGameStage::GrandTichu(grand_tichu) = &game_state.stage {
+            assert!(grand_tichu
+                .small_tichus
+                .iter()
+                .chain(grand_tichu.grand_tichus.iter())
+                .all(|tichu| tichu.tichu_call_status == TichuCallStatus::Undecided));
+        } else {
+            panic!("Expected a new round to start, got {:?}", game_state.stage);
+        }
+
+        let round_summary = game_state
+            .round_summaries
+            .last()
+            .expect("The finished round should be recorded");
+        assert_eq!(
+            round_summary
+                .small_tichus
+                .clone()
+                .map(|tichu| tichu.tichu_call_status),
+            [
+                TichuCallStatus::Achieved,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Failed,
+                TichuCallStatus::Undecided,
+            ]
+        );
+        assert_eq!(
+            round_summary
+                .grand_tichus
+                .clone()
+                .map(|tichu| tichu.tichu_call_status),
+            [
+                TichuCallStatus::Declined,
+                TichuCallStatus::Failed,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_fail_calls_made_by_users_who_did_not_go_out_first() {
+        let game_state = finish_round(double_victory_game_state(
+            [0, 0],
+            [
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Called,
+                TichuCallStatus::Called,
+                TichuCallStatus::Undecided,
+            ],
+            [
+                TichuCallStatus::Declined,
+                TichuCallStatus::Called,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+            ],
+        ));
+
+        if let PrivateGameStage::GrandTichu(grand_tichu) = &game_state.stage {
+            // user 2 went out second: +200 - 100 - 200
+            assert_eq!(grand_tichu.teams[0].score, -100);
+            // user 3 didn't go out at all
+            assert_eq!(grand_tichu.teams[1].score, -100);
+        } else {
+            panic!("Expected a new round to start, got {:?}", game_state.stage);
+        }
+    }
+
+    #[test]
+    fn it_should_carry_settled_calls_into_the_score_stage() {
+        let game_state = finish_round(double_victory_game_state(
+            [900, 0],
+            [
+                TichuCallStatus::Called,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Called,
+                TichuCallStatus::Undecided,
+            ],
+            [
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Called,
+            ],
+        ));
+
+        if let PrivateGameStage::Score(score) = &game_state.stage {
+            assert_eq!(score.teams[0].score, 1200);
+            assert_eq!(score.teams[1].score, -300);
+            assert_eq!(
+                score.small_tichus[0].tichu_call_status,
+                TichuCallStatus::Achieved
+            );
+            assert_eq!(
+                score.small_tichus[1].tichu_call_status,
+                TichuCallStatus::Undecided
+            );
+            assert_eq!(
+                score.small_tichus[2].tichu_call_status,
+                TichuCallStatus::Failed
+            );
+            assert_eq!(
+                score.grand_tichus[3].tichu_call_status,
+                TichuCallStatus::Failed
+            );
+            assert_eq!(
+                score.grand_tichus[0].tichu_call_status,
+                TichuCallStatus::Declined
+            );
+        } else {
+            panic!("Expected the game to be over, got {:?}", game_state.stage);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_round_summaries {
+    use crate::{
+        double_victory_game_state, finish_round, one_user_left_game_state, same_tichu_statuses,
+    };
+    use common::{PrivateGameStage, PublicGameStage, TichuCallStatus};
+
+    #[test]
+    fn it_should_record_a_double_victory_round() {
+        let game_state = finish_round(double_victory_game_state(
+            [0, 50],
+            [
+                TichuCallStatus::Called,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Undecided,
+            ],
+            [
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Called,
+                TichuCallStatus::Declined,
+            ],
+        ));
+
+        assert_eq!(game_state.round_summaries.len(), 1);
+        let summary = &game_state.round_summaries[0];
+        assert_eq!(summary.round_number, 1);
+        assert_eq!(summary.double_victory_team_id, Some("a".into()));
+        assert_eq!(summary.first_user_out, Some("1".into()));
+        assert_eq!(summary.last_user_out, None);
+
+        let [team_a, team_b] = &summary.teams;
+        assert_eq!(team_a.team_id, "a");
+        assert_eq!(team_a.card_points, 0);
+        assert_eq!(team_a.double_victory_points, 200);
+        assert_eq!(team_a.tichu_points, 100);
+        assert_eq!(team_a.running_total, 300);
+        assert_eq!(team_b.double_victory_points, 0);
+        assert_eq!(team_b.tichu_points, -200);
+        assert_eq!(team_b.round_total(), -200);
+        assert_eq!(team_b.running_total, -150);
+
+        assert_eq!(
+            summary.small_tichus[0].tichu_call_status,
+            TichuCallStatus::Achieved
+        );
+        assert_eq!(
+            summary.grand_tichus[2].tichu_call_status,
+            TichuCallStatus::Failed
+        );
+    }
+
+    #[test]
+    fn it_should_record_card_points_and_last_user_out() {
+        let game_state = one_user_left_game_state(
+            [0, 0],
+            [
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Called,
+            ],
+            [
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+            ],
+        )
+        .pass("4")
+        .expect("Passing should end the round");
+
+        assert_eq!(game_state.round_summaries.len(), 1);
+        let summary = &game_state.round_summaries[0];
+        assert_eq!(summary.double_victory_team_id, None);
+        assert_eq!(summary.last_user_out, Some("4".into()));
+
+        let [team_a, team_b] = &summary.teams;
+        // five in tricks + Dragon from the last user's tricks
+        assert_eq!(team_a.card_points, 30);
+        assert_eq!(team_a.tichu_points, 0);
+        assert_eq!(team_a.running_total, 30);
+        // King won by user 3
+        assert_eq!(team_b.card_points, 10);
+        assert_eq!(team_b.tichu_points, -100);
+        assert_eq!(team_b.running_total, -90);
+    }
+
+    #[test]
+    fn it_should_append_to_earlier_rounds() {
+        let first_round = finish_round(double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        ));
+
+        let mut second_round = double_victory_game_state(
+            [200, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+        second_round.round_summaries = first_round.round_summaries.clone();
+        let game_state = finish_round(second_round);
+
+        assert_eq!(game_state.round_summaries.len(), 2);
+        assert_eq!(
+            game_state.round_summaries[0],
+            first_round.round_summaries[0]
+        );
+        assert_eq!(game_state.round_summaries[1].round_number, 2);
+        assert_eq!(game_state.round_summaries[1].teams[0].running_total, 400);
+    }
+
+    #[test]
+    fn it_should_expose_round_summaries_in_every_stage() {
+        let first_round = finish_round(double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        ));
+
+        // between rounds, while the next round's cards are being dealt
+        let public_game_state = first_round
+            .to_public_game_state("3")
+            .expect("User 3 should be in the game");
+        assert!(matches!(
+            public_game_state.stage,
+            PublicGameStage::GrandTichu(_)
+        ));
+        assert_eq!(
+            public_game_state.round_summaries,
+            first_round.round_summaries
+        );
+
+        let mut game_state = double_victory_game_state(
+            [200, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+        game_state.round_summaries = first_round.round_summaries.clone();
+
+        let public_game_state = game_state
+            .to_public_game_state("3")
+            .expect("User 3 should be in the game");
+        assert!(matches!(public_game_state.stage, PublicGameStage::Play(_)));
+        assert_eq!(
+            public_game_state.round_summaries,
+            first_round.round_summaries
+        );
+    }
+
+    #[test]
+    fn it_should_carry_round_summaries_into_the_score_stage() {
+        let first_round = finish_round(double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        ));
+
+        let mut final_round = double_victory_game_state(
+            [900, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+        final_round.round_summaries = first_round.round_summaries;
+        let game_state = finish_round(final_round);
+
+        if let PrivateGameStage::Score(score) = &game_state.stage {
+            assert_eq!(score.round_summaries.len(), 2);
+            assert_eq!(score.round_summaries[1].teams[0].running_total, 1100);
+        } else {
+            panic!("Expected the game to be over, got {:?}", game_state.stage);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_cards_in_hand {
+    use crate::{
+        double_victory_game_state, same_tichu_statuses, trade_from_user_1, trade_game_state,
+    };
+    use common::{
+        Card, CardSuit, CardValue, PrivateGameStage, TichuCallStatus, TichuError, DRAGON,
+    };
+
+    #[test]
+    fn it_should_reject_playing_a_card_that_is_not_in_hand() {
+        let game_state = double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+
+        // user 3 only holds the three of Swords
+        let result = game_state.play_cards("3", vec![DRAGON], None);
+        let error = result.expect_err("Playing a Dragon that isn't in hand should fail");
+        assert_eq!(error, TichuError::CardNotInHand(DRAGON));
+    }
+
+    #[test]
+    fn it_should_reject_playing_the_same_card_twice() {
+        let game_state = double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+
+        let card = Card {
+            suit: CardSuit::Sword,
+            value: CardValue(3),
+        };
+        let result = game_state.play_cards("3", vec![card.clone(), card.clone()], None);
+        let error = result.expect_err("Playing a pair made from one card should fail");
+        assert_eq!(error, TichuError::DuplicateCard(card));
+    }
+
+    #[test]
+    fn it_should_accept_a_trade_of_cards_in_hand() {
+        let hand = trade_game_state().participants[0].hand.clone();
+        let game_state = trade_game_state()
+            .submit_trade(
+                "1",
+                &trade_from_user_1([hand[0].clone(), hand[1].clone(), hand[2].clone()]),
+            )
+            .expect("Trading cards in hand should succeed");
+
+        assert!(game_state.participants[0].hand.is_empty());
+        if let PrivateGameStage::Trade(trade_state) = &game_state.stage {
+            assert!(trade_state.trades[0].is_some());
+        } else {
+            panic!("Expected the Trade stage, got {:?}", game_state.stage);
+        }
+    }
+
+    #[test]
+    fn it_should_reject_trading_a_card_that_is_not_in_hand() {
+        let hand = trade_game_state().participants[0].hand.clone();
+        let result = trade_game_state().submit_trade(
+            "1",
+            &trade_from_user_1([hand[0].clone(), hand[1].clone(), DRAGON]),
+        );
+        let error = result.expect_err("Trading a Dragon that isn't in hand should fail");
+        assert_eq!(error, TichuError::CardNotInHand(DRAGON));
+    }
+
+    #[test]
+    fn it_should_reject_trading_the_same_card_twice() {
+        let hand = trade_game_state().participants[0].hand.clone();
+        let result = trade_game_state().submit_trade(
+            "1",
+            &trade_from_user_1([hand[0].clone(), hand[0].clone(), hand[1].clone()]),
+        );
+        let error = result.expect_err("Trading one card twice should fail");
+        assert_eq!(error, TichuError::DuplicateCard(hand[0].clone()));
+    }
+
+    #[test]
+    fn it_should_reject_trading_on_behalf_of_another_user() {
+        let hand = trade_game_state().participants[0].hand.clone();
+        let mut submit_trade =
+            trade_from_user_1([hand[0].clone(), hand[1].clone(), hand[2].clone()]);
+        submit_trade[2].from_user_id = "4".into();
+
+        assert_eq!(
+            trade_game_state()
+                .submit_trade("1", &submit_trade)
+                .unwrap_err(),
+            TichuError::TradeNotFromUser
+        );
+    }
+
+    #[test]
+    fn it_should_reject_a_second_trade_from_the_same_user() {
+        let mut game_state = trade_game_state();
+        game_state.participants[0].hand = (2..=7)
+            .map(|value| Card {
+                suit: CardSuit::Jade,
+                value: CardValue(value),
+            })
+            .collect();
+        let hand = game_state.participants[0].hand.clone();
+        let game_state = game_state
+            .submit_trade(
+                "1",
+                &trade_from_user_1([hand[0].clone(), hand[1].clone(), hand[2].clone()]),
+            )
+            .unwrap();
+
+        assert_eq!(
+            game_state
+                .submit_trade(
+                    "1",
+                    &trade_from_user_1([hand[3].clone(), hand[4].clone(), hand[5].clone()])
+                )
+                .unwrap_err(),
+            TichuError::TradeAlreadySubmitted
+        );
+    }
+
+    #[test]
+    fn it_should_reject_trading_two_cards_to_the_same_user() {
+        let hand = trade_game_state().participants[0].hand.clone();
+        let mut submit_trade =
+            trade_from_user_1([hand[0].clone(), hand[1].clone(), hand[2].clone()]);
+        submit_trade[2].to_user_id = "2".into();
+
+        assert_eq!(
+            trade_game_state()
+                .submit_trade("1", &submit_trade)
+                .unwrap_err(),
+            TichuError::DuplicateTradeRecipient
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_give_dragon {
+    use crate::dragon_trick_game_state;
+    use common::{PrivateGameStage, PrivateGameState, TichuError, DRAGON};
+
+    fn get_dragon_winner_user_id(game_state: &PrivateGameState) -> Option<String> {
+        if let PrivateGameStage::Play(play_state) = &game_state.stage {
+            play_state.dragon_winner_user_id.clone()
+        } else {
+            panic!("Expected the Play stage, got {:?}", game_state.stage);
+        }
+    }
+
+    #[test]
+    fn it_should_wait_for_the_winner_to_choose_a_recipient() {
+        let game_state = dragon_trick_game_state()
+            .pass("1")
+            .expect("Winner's pass should be accepted");
+
+        assert_eq!(get_dragon_winner_user_id(&game_state), Some("1".into()));
+        if let PrivateGameStage::Play(play_state) = &game_state.stage {
+            assert!(play_state.table.last().unwrap().cards().contains(&DRAGON));
+        }
+
+        // play is paused until the Dragon is given away
+        assert_eq!(
+            game_state.pass("3").unwrap_err(),
+            TichuError::DragonNotGivenAway
+        );
+        let card = game_state.participants[0].hand[0].clone();
+        assert_eq!(
+            game_state.play_cards("1", vec![card], None).unwrap_err(),
+            TichuError::DragonNotGivenAway
+        );
+    }
+
+    #[test]
+    fn it_should_give_the_trick_to_the_chosen_opponent() {
+        let game_state = dragon_trick_game_state()
+            .pass("1")
+            .and_then(|game_state| game_state.give_dragon("1", "4"))
+            .expect("Giving the Dragon to an opponent should be accepted");
+
+        assert_eq!(get_dragon_winner_user_id(&game_state), None);
+        let recipient = game_state.get_user_by_user_id("4").unwrap();
+        assert!(recipient.tricks[0].cards().contains(&DRAGON));
+        assert!(game_state
+            .get_user_by_user_id("1")
+            .unwrap()
+            .tricks
+            .is_empty());
+
+        if let PrivateGameStage::Play(play_state) = &game_state.stage {
+            assert!(play_state.table.is_empty());
+            // winner keeps the lead
+            assert_eq!(play_state.turn_user_id, "1");
+            assert!(play_state.passes.iter().all(|pass| !pass.passed));
+        }
+    }
+
+    #[test]
+    fn it_should_reject_giving_the_dragon_to_a_teammate() {
+        let game_state = dragon_trick_game_state().pass("1").unwrap();
+
+        for recipient_user_id in ["2", "1", "not a user"] {
+            assert_eq!(
+                game_state.give_dragon("1", recipient_user_id).unwrap_err(),
+                TichuError::RecipientNotOpponent
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_only_let_the_winner_give_the_dragon_away() {
+        assert_eq!(
+            dragon_trick_game_state().give_dragon("1", "3").unwrap_err(),
+            TichuError::NotDragonWinner
+        );
+
+        let game_state = dragon_trick_game_state().pass("1").unwrap();
+        assert_eq!(
+            game_state.give_dragon("3", "1").unwrap_err(),
+            TichuError::NotDragonWinner
+        );
+        assert_eq!(
+            game_state.give_dragon("2", "3").unwrap_err(),
+            TichuError::NotDragonWinner
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_tichu_errors {
+    use crate::{double_victory_game_state, same_tichu_statuses};
+    use common::{
+        Card, CardSuit, CardValue, PrivateGameStage, PrivateGameState, TichuCallStatus, TichuError,
+    };
+    use std::collections::HashMap;
+
+    fn full_game_state() -> PrivateGameState {
+        let mut game_state = PrivateGameState::new("1".into(), "User 1".into(), &HashMap::new());
+        for user_id in ["2", "3", "4"] {
+            game_state = game_state
+                .add_user(user_id.into(), format!("User {}", user_id))
+                .expect("Adding up to 4 users should be accepted");
+        }
+        game_state
+    }
+
+    fn sword(value: u8) -> Card {
+        Card {
+            suit: CardSuit::Sword,
+            value: CardValue(value),
+        }
+    }
+
+    #[test]
+    fn it_should_reject_a_fifth_user() {
+        assert_eq!(
+            full_game_state()
+                .add_user("5".into(), "User 5".into())
+                .unwrap_err(),
+            TichuError::GameFull
+        );
+    }
+
+    #[test]
+    fn it_should_only_let_the_owner_start_the_game() {
+        assert_eq!(
+            full_game_state().start_grand_tichu("2").unwrap_err(),
+            TichuError::NotOwner
+        );
+    }
+
+    #[test]
+    fn it_should_reject_passing_outside_of_the_play_stage() {
+        assert_eq!(
+            full_game_state().pass("1").unwrap_err(),
+            TichuError::WrongStage
+        );
+    }
+
+    #[test]
+    fn it_should_reject_playing_out_of_turn() {
+        let game_state = double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+
+        assert_eq!(
+            game_state
+                .play_cards("4", vec![sword(4)], None)
+                .unwrap_err(),
+            TichuError::NotYourTurn
+        );
+    }
+
+    #[test]
+    fn it_should_reject_passing_out_of_turn() {
+        let game_state = double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+
+        assert_eq!(game_state.pass("4").unwrap_err(), TichuError::NotYourTurn);
+    }
+
+    #[test]
+    fn it_should_reject_passing_on_the_lead() {
+        let mut game_state = double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+        if let PrivateGameStage::Play(play_state) = &mut game_state.stage {
+            play_state.table.clear();
+        }
+
+        assert_eq!(
+            game_state.pass("3").unwrap_err(),
+            TichuError::CannotPassOnLead
+        );
+    }
+
+    #[test]
+    fn it_should_reject_a_combo_that_does_not_beat_the_table() {
+        let game_state = double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+
+        // the three of Swords can't beat the Ace on the table
+        assert_eq!(
+            game_state
+                .play_cards("3", vec![sword(3)], None)
+                .unwrap_err(),
+            TichuError::ComboDoesNotBeatTable
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_seeded_dealing {
+    use crate::{double_victory_game_state, finish_round, same_tichu_statuses};
+    use common::{sort_cards_for_hand, Deck, PrivateGameState, TichuCallStatus};
+    use std::collections::HashMap;
+
+    fn started_game_state(seed: u64) -> PrivateGameState {
+        let mut game_state =
+            PrivateGameState::new_with_seed("1".into(), "User 1".into(), &HashMap::new(), seed);
+        for user_id in ["2", "3", "4"] {
+            game_state = game_state
+                .add_user(user_id.into(), format!("User {}", user_id))
+                .unwrap();
+        }
+        game_state.start_grand_tichu("1").unwrap()
+    }
+
+    #[test]
+    fn it_should_deal_the_same_hands_for_the_same_seed() {
+        let game_state = started_game_state(7);
+
+        assert_eq!(game_state.participants, started_game_state(7).participants);
+        assert_ne!(game_state.participants, started_game_state(8).participants);
+    }
+
+    #[test]
+    fn it_should_deal_each_round_with_its_own_seed() {
+        let game_state = double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+        let first_round_seed = game_state.get_round_seed();
+        let game_state = finish_round(game_state);
+        let second_round_seed = game_state.get_round_seed();
+        assert_ne!(first_round_seed, second_round_seed);
+
+        // the first user is dealt the top 9 cards of the second round's deck
+        let mut deck = Deck::new().shuffle_with_seed(second_round_seed);
+        let mut first_user_hand = deck.draw(9);
+        sort_cards_for_hand(&mut first_user_hand);
+        assert_eq!(game_state.participants[0].hand, first_user_hand);
+    }
+}
+
+#[cfg(test)]
+mod test_game_rules {
+    use crate::{double_victory_game_state, finish_round, same_tichu_statuses};
+    use common::{
+        GameEnd, GameRules, PrivateGameStage, PrivateGameState, TichuCallStatus, TichuError,
+        MAX_CARDS_IN_HAND,
+    };
+    use std::collections::HashMap;
+
+    fn teams_game_state() -> PrivateGameState {
+        let mut game_state = PrivateGameState::new("1".into(), "User 1".into(), &HashMap::new());
+        for user_id in ["2", "3", "4"] {
+            game_state = game_state
+                .add_user(user_id.into(), format!("User {}", user_id))
+                .unwrap();
+        }
+        game_state
+    }
+
+    #[test]
+    fn it_should_only_let_the_owner_update_rules_before_the_game_starts() {
+        let rules = GameRules {
+            game_end: GameEnd::Rounds(4),
+            ..GameRules::default()
+        };
+        let game_state = teams_game_state();
+
+        assert_eq!(
+            game_state.update_rules("2", &rules).unwrap_err(),
+            TichuError::NotOwner
+        );
+
+        let game_state = game_state.update_rules("1", &rules).unwrap();
+        assert_eq!(game_state.rules, rules);
+        assert_eq!(game_state.to_public_game_state("2").unwrap().rules, rules);
+
+        let game_state = game_state.start_grand_tichu("1").unwrap();
+        assert_eq!(
+            game_state
+                .update_rules("1", &GameRules::default())
+                .unwrap_err(),
+            TichuError::WrongStage
+        );
+    }
+
+    #[test]
+    fn it_should_reject_rules_that_make_the_game_unplayable() {
+        let game_state = teams_game_state();
+        for rules in [
+            GameRules {
+                game_end: GameEnd::TargetScore(0),
+                ..GameRules::default()
+            },
+            GameRules {
+                game_end: GameEnd::Rounds(0),
+                ..GameRules::default()
+            },
+            GameRules {
+                small_tichu_value: -100,
+                ..GameRules::default()
+            },
+            GameRules {
+                num_cards_before_grand_tichu: MAX_CARDS_IN_HAND,
+                ..GameRules::default()
+            },
+            GameRules {
+                turn_time_limit_secs: Some(0),
+                ..GameRules::default()
+            },
+        ] {
+            assert_eq!(
+                game_state.update_rules("1", &rules).unwrap_err(),
+                TichuError::InvalidRules
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_deal_the_number_of_cards_set_in_the_rules() {
+        let rules = GameRules {
+            num_cards_before_grand_tichu: 8,
+            ..GameRules::default()
+        };
+        let game_state = teams_game_state()
+            .update_rules("1", &rules)
+            .unwrap()
+            .start_grand_tichu("1")
+            .unwrap();
+
+        assert!(matches!(game_state.stage, PrivateGameStage::GrandTichu(_)));
+        for participant in game_state.participants.iter() {
+            assert_eq!(participant.hand.len(), 8);
+        }
+    }
+
+    #[test]
+    fn it_should_deal_every_card_at_once_without_grand_tichu() {
+        let rules = GameRules {
+            grand_tichu_enabled: false,
+            ..GameRules::default()
+        };
+        let game_state = teams_game_state()
+            .update_rules("1", &rules)
+            .unwrap()
+            .start_grand_tichu("1")
+            .unwrap();
+
+        assert!(matches!(game_state.stage, PrivateGameStage::Trade(_)));
+        for participant in game_state.participants.iter() {
+            assert_eq!(participant.hand.len(), MAX_CARDS_IN_HAND);
+        }
+    }
+
+    #[test]
+    fn it_should_score_with_the_values_set_in_the_rules() {
+        let mut game_state = double_victory_game_state(
+            [0, 0],
+            [
+                TichuCallStatus::Called,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Undecided,
+                TichuCallStatus::Undecided,
+            ],
+            [
+                TichuCallStatus::Declined,
+                TichuCallStatus::Declined,
+                TichuCallStatus::Called,
+                TichuCallStatus::Declined,
+            ],
+        );
+        game_state.rules = GameRules {
+            small_tichu_value: 50,
+            grand_tichu_value: 150,
+            double_victory_value: 300,
+            ..GameRules::default()
+        };
+        let game_state = finish_round(game_state);
+
+        let round_summary = &game_state.round_summaries[0];
+        assert_eq!(round_summary.teams[0].double_victory_points, 300);
+        assert_eq!(round_summary.teams[0].tichu_points, 50);
+        assert_eq!(round_summary.teams[0].running_total, 350);
+        assert_eq!(round_summary.teams[1].tichu_points, -150);
+    }
+
+    #[test]
+    fn it_should_end_the_game_after_a_fixed_number_of_rounds() {
+        let mut game_state = double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+        let next_round_game_state = finish_round(game_state.clone());
+        assert!(matches!(
+            next_round_game_state.stage,
+            PrivateGameStage::GrandTichu(_)
+        ));
+
+        game_state.rules = GameRules {
+            game_end: GameEnd::Rounds(1),
+            ..GameRules::default()
+        };
+        let game_state = finish_round(game_state);
+        assert!(matches!(game_state.stage, PrivateGameStage::Score(_)));
+    }
+
+    #[test]
+    fn it_should_end_the_game_once_a_team_passes_the_target_score() {
+        let mut game_state = double_victory_game_state(
+            [400, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+        game_state.rules = GameRules {
+            game_end: GameEnd::TargetScore(500),
+            ..GameRules::default()
+        };
+        let game_state = finish_round(game_state);
+
+        assert!(matches!(game_state.stage, PrivateGameStage::Score(_)));
+    }
+
+    #[test]
+    fn it_should_keep_playing_while_teams_are_tied() {
+        let mut game_state = double_victory_game_state(
+            [0, 200],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        );
+        game_state.rules = GameRules {
+            game_end: GameEnd::Rounds(1),
+            ..GameRules::default()
+        };
+        let game_state = finish_round(game_state);
+
+        assert!(matches!(game_state.stage, PrivateGameStage::GrandTichu(_)));
+    }
+}
+
+#[cfg(test)]
+mod test_admin_commands {
+    use crate::{double_victory_game_state, same_tichu_statuses};
+    use common::{
+        AdminCommand, AdminStage, Card, CardSuit, CardValue, PrivateGameStage, PrivateGameState,
+        TichuCallStatus, TichuError, DOG, MAH_JONG, MAX_CARDS_IN_HAND,
+    };
+    use std::collections::HashMap;
+
+    fn teams_game_state() -> PrivateGameState {
+        let mut game_state = PrivateGameState::new("1".into(), "User 1".into(), &HashMap::new());
+        for user_id in ["2", "3", "4"] {
+            game_state = game_state
+                .add_user(user_id.into(), format!("User {}", user_id))
+                .unwrap();
+        }
+        game_state
+    }
+
+    fn pagoda(value: u8) -> Card {
+        Card {
+            suit: CardSuit::Pagoda,
+            value: CardValue(value),
+        }
+    }
+
+    #[test]
+    fn it_should_jump_to_the_trade_stage_with_full_hands() {
+        let game_state = teams_game_state()
+            .run_admin_command(&AdminCommand::JumpToStage(AdminStage::Trade))
+            .unwrap();
+
+        assert!(matches!(game_state.stage, PrivateGameStage::Trade(_)));
+        for participant in game_state.participants.iter() {
+            assert_eq!(participant.hand.len(), MAX_CARDS_IN_HAND);
+        }
+    }
+
+    #[test]
+    fn it_should_only_jump_from_the_teams_stage() {
+        let game_state = teams_game_state()
+            .run_admin_command(&AdminCommand::JumpToStage(AdminStage::GrandTichu))
+            .unwrap();
+
+        assert_eq!(
+            game_state
+                .run_admin_command(&AdminCommand::JumpToStage(AdminStage::Play))
+                .unwrap_err(),
+            TichuError::WrongStage
+        );
+    }
+
+    #[test]
+    fn it_should_deal_the_chosen_cards_and_fill_up_the_rest() {
+        let hands = [
+            vec![pagoda(2), pagoda(3)],
+            vec![pagoda(4)],
+            Vec::new(),
+            vec![pagoda(5)],
+        ];
+        let game_state = teams_game_state()
+            .run_admin_command(&AdminCommand::DealHands {
+                hands: hands.clone(),
+            })
+            .unwrap();
+
+        assert!(matches!(game_state.stage, PrivateGameStage::Play(_)));
+        for (participant, hand) in game_state.participants.iter().zip(hands.iter()) {
+            assert_eq!(participant.hand.len(), MAX_CARDS_IN_HAND);
+            assert!(hand.iter().all(|card| participant.hand.contains(card)));
+        }
+    }
+
+    #[test]
+    fn it_should_reject_dealing_the_same_card_twice() {
+        assert_eq!(
+            teams_game_state()
+                .run_admin_command(&AdminCommand::DealHands {
+                    hands: [vec![pagoda(2)], vec![pagoda(2)], Vec::new(), Vec::new()],
+                })
+                .unwrap_err(),
+            TichuError::DuplicateCard(pagoda(2))
+        );
+    }
+
+    #[test]
+    fn it_should_reject_a_hand_with_too_many_cards() {
+        let hand = (2..=14).map(pagoda).chain([MAH_JONG, DOG]);
+        assert_eq!(
+            teams_game_state()
+                .run_admin_command(&AdminCommand::DealHands {
+                    hands: [hand.collect(), Vec::new(), Vec::new(), Vec::new()],
+                })
+                .unwrap_err(),
+            TichuError::InvalidHand
+        );
+    }
+
+    #[test]
+    fn it_should_overwrite_the_team_scores() {
+        let game_state = double_victory_game_state(
+            [0, 0],
+            same_tichu_statuses(TichuCallStatus::Undecided),
+            same_tichu_statuses(TichuCallStatus::Declined),
+        )
+        .run_admin_command(&AdminCommand::SetScores { scores: [950, -50] })
+        .unwrap();
+
+        if let PrivateGameStage::Play(play_state) = &game_state.stage {
+            assert_eq!(play_state.teams[0].score, 950);
+            assert_eq!(play_state.teams[1].score, -50);
+        } else {
+            panic!("Setting scores should not change the stage");
+        }
+    }
+
+    #[test]
+    fn it_should_play_out_the_round() {
+        let game_state = teams_game_state()
+            .run_admin_command(&AdminCommand::JumpToStage(AdminStage::Play))
+            .unwrap()
+            .run_admin_command(&AdminCommand::EndRound)
+            .unwrap();
+
+        assert_eq!(game_state.round_summaries.len(), 1);
+        assert!(matches!(game_state.stage, PrivateGameStage::GrandTichu(_)));
+    }
+
+    #[test]
+    fn it_should_only_end_a_round_in_progress() {
+        assert_eq!(
+            teams_game_state()
+                .run_admin_command(&AdminCommand::EndRound)
+                .unwrap_err(),
+            TichuError::WrongStage
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use common::{Card, CardSuit, CardValue};
+
+fn card(suit: CardSuit, value: u8) -> Card {
+    Card {
+        suit,
+        value: CardValue(value),
+    }
+}
+
+#[cfg(test)]
+mod test_decompose_hand {
+    use crate::card;
+    use common::{
+        decompose_hand, sort_cards_by_combos, CardSet, CardSuit, Deck, ValidCardCombo, DOG, DRAGON,
+        MAX_HAND_PARTITIONS, PHOENIX,
+    };
+    use rand::{seq::SliceRandom, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn it_should_find_the_fewest_combos_to_go_out() {
+        let hand = vec![
+            card(CardSuit::Sword, 3),
+            card(CardSuit::Jade, 4),
+            card(CardSuit::Sword, 5),
+            card(CardSuit::Star, 6),
+            card(CardSuit::Sword, 7),
+            card(CardSuit::Pagoda, 9),
+            card(CardSuit::Jade, 9),
+            DRAGON,
+        ];
+        let partitions = decompose_hand(&hand);
+
+        assert_eq!(partitions[0].combos.len(), 3);
+        assert!(matches!(
+            partitions[0].combos[0],
+            ValidCardCombo::Sequence(_)
+        ));
+        assert!(matches!(partitions[0].combos[1], ValidCardCombo::Pair(_)));
+        assert_eq!(partitions[0].combos[2].cards(), &vec![DRAGON]);
+        assert!(partitions[0].isolated_cards.is_empty());
+        assert!(!partitions[0].has_bomb);
+    }
+
+    #[test]
+    fn it_should_report_isolated_cards() {
+        let hand = vec![
+            card(CardSuit::Sword, 2),
+            card(CardSuit::Jade, 7),
+            card(CardSuit::Pagoda, 9),
+            card(CardSuit::Jade, 9),
+            card(CardSuit::Star, 13),
+            DOG,
+        ];
+        let partitions = decompose_hand(&hand);
+
+        assert_eq!(partitions.len(), 1);
+        assert_eq!(partitions[0].combos.len(), 5);
+        assert_eq!(
+            partitions[0].isolated_cards,
+            vec![
+                card(CardSuit::Sword, 2),
+                card(CardSuit::Jade, 7),
+                card(CardSuit::Star, 13)
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_report_bombs() {
+        let hand = vec![
+            card(CardSuit::Sword, 5),
+            card(CardSuit::Jade, 5),
+            card(CardSuit::Pagoda, 5),
+            card(CardSuit::Star, 5),
+            card(CardSuit::Star, 11),
+        ];
+        let partitions = decompose_hand(&hand);
+
+        assert_eq!(partitions.len(), 1);
+        assert!(partitions[0].has_bomb);
+        assert!(partitions[0].combos[0].is_bomb());
+    }
+
+    #[test]
+    fn it_should_use_the_phoenix_as_a_wildcard() {
+        let hand = vec![
+            card(CardSuit::Sword, 4),
+            card(CardSuit::Jade, 5),
+            card(CardSuit::Sword, 7),
+            card(CardSuit::Star, 8),
+            PHOENIX,
+        ];
+        let partitions = decompose_hand(&hand);
+
+        assert_eq!(partitions.len(), 1);
+        assert_eq!(partitions[0].combos.len(), 1);
+        assert!(matches!(
+            partitions[0].combos[0],
+            ValidCardCombo::Sequence(_)
+        ));
+    }
+
+    #[test]
+    fn it_should_prefer_keeping_bombs_when_going_out_takes_as_many_turns() {
+        // a bomb and the Phoenix, or a trio and a pair (completed with the Phoenix)
+        let hand = vec![
+            card(CardSuit::Sword, 5),
+            card(CardSuit::Jade, 5),
+            card(CardSuit::Pagoda, 5),
+            card(CardSuit::Star, 5),
+            PHOENIX,
+        ];
+        let partitions = decompose_hand(&hand);
+
+        assert!(partitions.len() > 1);
+        assert!(partitions[0].has_bomb);
+        assert_eq!(partitions[0].combos[1].cards(), &vec![PHOENIX]);
+        assert!(partitions[1..].iter().all(|partition| !partition.has_bomb
+            && partition.combos.len() == 2
+            && partition.score < partitions[0].score));
+    }
+
+    #[test]
+    fn it_should_split_random_hands_into_every_card_exactly_once() {
+        for seed in 0..100 {
+            let mut hand = Deck::new().0;
+            hand.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+            hand.truncate(14);
+            let partitions = decompose_hand(&hand);
+
+            assert!(!partitions.is_empty() && partitions.len() <= MAX_HAND_PARTITIONS);
+            for partition in partitions.iter() {
+                let mut cards = CardSet::EMPTY;
+                for combo in partition.combos.iter() {
+                    let combo_cards = CardSet::from(combo.cards());
+                    assert!((cards & combo_cards).is_empty(), "Seed {seed}");
+                    cards = cards | combo_cards;
+                }
+                assert_eq!(cards, CardSet::from(&hand), "Seed {seed}");
+                assert_eq!(partition.combos.len(), partitions[0].combos.len());
+            }
+        }
+    }
+
+    #[test]
+    fn it_should_sort_cards_by_combos() {
+        let mut hand = vec![
+            DRAGON,
+            card(CardSuit::Sword, 3),
+            card(CardSuit::Pagoda, 9),
+            card(CardSuit::Jade, 3),
+            card(CardSuit::Star, 9),
+            card(CardSuit::Star, 2),
+        ];
+        sort_cards_by_combos(&mut hand);
+
+        assert_eq!(
+            hand,
+            vec![
+                card(CardSuit::Star, 2),
+                card(CardSuit::Sword, 3),
+                card(CardSuit::Jade, 3),
+                card(CardSuit::Pagoda, 9),
+                card(CardSuit::Star, 9),
+                DRAGON,
+            ]
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use common::{
+    get_bot_action, BotDifficulty, CTSMsg, CallGrandTichuRequest, CardTrade, CardValue,
+    PrivateGameStage, PrivateGameState, TeamOption, TichuError,
+};
+use rand::{seq::SliceRandom, Rng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashMap;
+
+/// A game where 4 users have joined and been split into teams
+fn teams_game_state(seed: u64) -> PrivateGameState {
+    let mut game_state =
+        PrivateGameState::new_with_seed("1".into(), "User 1".into(), &HashMap::new(), seed);
+    for user_id in ["2", "3", "4"] {
+        game_state = game_state
+            .add_user(user_id.into(), format!("User {user_id}"))
+            .unwrap();
+    }
+    game_state
+}
+
+/// A game in the Trade stage where nobody has traded yet
+fn trade_game_state() -> PrivateGameState {
+    let mut game_state = teams_game_state(0).start_grand_tichu("1").unwrap();
+    for user_id in ["1", "2", "3", "4"] {
+        game_state = game_state
+            .call_grand_tichu(&CallGrandTichuRequest::Decline, user_id)
+            .unwrap();
+    }
+    game_state
+}
+
+/// A game in the Play stage where nobody has played any cards yet
+fn play_game_state() -> PrivateGameState {
+    let mut game_state = trade_game_state();
+    for (i, user_id) in ["1", "2", "3", "4"].iter().enumerate() {
+        let hand = game_state
+            .get_user_by_user_id(user_id)
+            .unwrap()
+            .hand
+            .clone();
+        let trade = [1, 2, 3].map(|offset| CardTrade {
+            from_user_id: user_id.to_string(),
+            card: hand[offset - 1].clone(),
+            to_user_id: (((i + offset) % 4) + 1).to_string(),
+        });
+        game_state = game_state.submit_trade(user_id, &trade).unwrap();
+    }
+    game_state
+}
+
+/// Makes a random request on behalf of a random user: often legal, but usually not
+fn make_random_request(
+    game_state: &PrivateGameState,
+    rng: &mut ChaCha8Rng,
+) -> Result<PrivateGameState, TichuError> {
+    let user_ids: Vec<String> = game_state
+        .participants
+        .iter()
+        .map(|participant| participant.user_id.clone())
+        .collect();
+    let user_id = user_ids.choose(rng).unwrap().clone();
+    let other_user_id = user_ids.choose(rng).unwrap().clone();
+    let hand = game_state
+        .get_user_by_user_id(&user_id)
+        .unwrap()
+        .hand
+        .clone();
+    let team_option = if rng.gen() {
+        TeamOption::TeamA
+    } else {
+        TeamOption::TeamB
+    };
+
+    // half of the time, someone plays like a bot would, so that the game moves along
+    if rng.gen() {
+        let bot_seed = rng.gen();
+        let action = user_ids.iter().find_map(|user_id| {
+            let observation = game_state.get_observation(user_id).ok()?;
+            get_bot_action(&observation, &BotDifficulty::Easy, bot_seed).map(|msg| (user_id, msg))
+        });
+        if let Some((bot_user_id, msg)) = action {
+            // now and then, someone else makes the request, usually out of turn
+            let user_id = if rng.gen_ratio(1, 4) {
+                &other_user_id
+            } else {
+                bot_user_id
+            };
+            return match msg {
+                CTSMsg::CallGrandTichu(call_grand_tichu_request) => {
+                    game_state.call_grand_tichu(&call_grand_tichu_request, user_id)
+                }
+                CTSMsg::SubmitTrade(submit_trade) => {
+                    game_state.submit_trade(user_id, &submit_trade)
+                }
+                CTSMsg::PlayCards {
+                    cards,
+                    wished_for_card_value,
+                } => game_state.play_cards(user_id, cards, wished_for_card_value),
+                CTSMsg::Pass => game_state.pass(user_id),
+                CTSMsg::GiveDragon {
+                    user_id: recipient_user_id,
+                } => game_state.give_dragon(user_id, &recipient_user_id),
+                msg => panic!("Bots should never send {:?}", msg),
+            };
+        }
+    }
+
+    match rng.gen_range(0..12) {
+        0 => game_state.start_grand_tichu(&user_id),
+        1 => game_state.move_to_team(&team_option, &user_id),
+        2 => game_state.rename_team(&team_option, &user_id, "Renamed"),
+        3 => {
+            let call_grand_tichu_request = if rng.gen() {
+                CallGrandTichuRequest::Call
+            } else {
+                CallGrandTichuRequest::Decline
+            };
+            game_state.call_grand_tichu(&call_grand_tichu_request, &user_id)
+        }
+        4 => game_state.call_small_tichu(&user_id),
+        5 => {
+            if hand.is_empty() {
+                return Err(TichuError::WrongStage);
+            }
+            let trade = [(); 3].map(|_| CardTrade {
+                from_user_id: user_id.clone(),
+                card: hand.choose(rng).unwrap().clone(),
+                to_user_id: user_ids.choose(rng).unwrap().clone(),
+            });
+            game_state.submit_trade(&user_id, &trade)
+        }
+        6 => {
+            let mut cards = hand;
+            cards.shuffle(rng);
+            cards.truncate(rng.gen_range(1..=5));
+            let wished_for_card_value = if rng.gen() {
+                Some(CardValue(rng.gen_range(2..=14)))
+            } else {
+                None
+            };
+            game_state.play_cards(&user_id, cards, wished_for_card_value)
+        }
+        7 => game_state.pass(&user_id),
+        8 => game_state.give_dragon(&user_id, &other_user_id),
+        9 => game_state.remove_user(&user_id),
+        10 => {
+            // someone who already traded trades again, this time to the right users
+            let senders: Vec<String> = match &game_state.stage {
+                PrivateGameStage::Trade(trade_state) => trade_state
+                    .trades
+                    .iter()
+                    .flatten()
+                    .map(|trade| trade[0].from_user_id.clone())
+                    .collect(),
+                _ => return Err(TichuError::WrongStage),
+            };
+            let sender = senders.choose(rng).ok_or(TichuError::WrongStage)?;
+            let hand = &game_state.get_user_by_user_id(sender).unwrap().hand;
+            let recipients: Vec<&String> = user_ids.iter().filter(|id| *id != sender).collect();
+            if hand.len() < 3 {
+                return Err(TichuError::WrongStage);
+            }
+            let trade = [0, 1, 2].map(|i| CardTrade {
+                from_user_id: sender.clone(),
+                card: hand[i].clone(),
+                to_user_id: recipients[i].clone(),
+            });
+            game_state.submit_trade(sender, &trade)
+        }
+        _ => game_state.reassign_owner(),
+    }
+}
+
+#[cfg(test)]
+mod test_check_invariants {
+    use crate::{play_game_state, teams_game_state, trade_game_state};
+    use common::{CardTrade, GameEvent, InvariantViolation, PrivateGameStage};
+
+    #[test]
+    fn it_should_accept_a_game_that_was_just_dealt() {
+        assert_eq!(teams_game_state(0).check_invariants(), Ok(()));
+        assert_eq!(play_game_state().check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn it_should_report_a_card_in_two_places() {
+        let mut game_state = play_game_state();
+        let card = game_state.participants[0].hand[0].clone();
+        game_state.participants[1].hand[0] = card.clone();
+
+        assert_eq!(
+            game_state.check_invariants(),
+            Err(vec![InvariantViolation::DuplicateCard(card)])
+        );
+    }
+
+    #[test]
+    fn it_should_report_lost_cards() {
+        let mut game_state = play_game_state();
+        game_state.participants[0].hand.pop();
+
+        assert_eq!(
+            game_state.check_invariants(),
+            Err(vec![InvariantViolation::WrongNumberOfCards {
+                expected: 56,
+                found: 55
+            }])
+        );
+    }
+
+    #[test]
+    fn it_should_report_a_turn_or_dragon_winner_that_makes_no_sense() {
+        let mut game_state = play_game_state();
+        if let PrivateGameStage::Play(play_state) = &mut game_state.stage {
+            play_state.turn_user_id = "5".into();
+            play_state.dragon_winner_user_id = Some("1".into());
+        }
+
+        assert_eq!(
+            game_state.check_invariants(),
+            Err(vec![
+                InvariantViolation::TurnUserNotInPlay("5".into()),
+                InvariantViolation::DragonWinnerWithoutDragon("1".into())
+            ])
+        );
+    }
+
+    #[test]
+    fn it_should_report_a_user_with_two_trades() {
+        let mut game_state = trade_game_state();
+        let hand = game_state.participants[0].hand.clone();
+        let trade = [1, 2, 3].map(|offset| CardTrade {
+            from_user_id: "1".into(),
+            card: hand[offset - 1].clone(),
+            to_user_id: (offset + 1).to_string(),
+        });
+        if let PrivateGameStage::Trade(trade_state) = &mut game_state.stage {
+            trade_state.trades[0] = Some(trade.clone());
+            trade_state.trades[1] = Some(trade);
+        }
+
+        assert!(game_state
+            .check_invariants()
+            .unwrap_err()
+            .contains(&InvariantViolation::DuplicateTradeSender("1".into())));
+    }
+
+    #[test]
+    fn it_should_report_a_pass_out_of_turn() {
+        let game_state = play_game_state();
+        let turn_user_id = match &game_state.stage {
+            PrivateGameStage::Play(play_state) => play_state.turn_user_id.clone(),
+            _ => panic!("Game should be in the Play stage"),
+        };
+        let other_user_id = if turn_user_id == "1" { "2" } else { "1" };
+
+        assert_eq!(
+            game_state.check_event_invariants(&GameEvent::Passed {
+                user_id: other_user_id.into()
+            }),
+            Err(vec![InvariantViolation::PassOutOfTurn(
+                other_user_id.into()
+            )])
+        );
+        assert_eq!(
+            game_state.check_event_invariants(&GameEvent::Passed {
+                user_id: turn_user_id
+            }),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_should_report_teams_that_dont_match_the_participants() {
+        let mut game_state = teams_game_state(0);
+        game_state.participants.pop();
+
+        assert_eq!(
+            game_state.check_invariants(),
+            Err(vec![InvariantViolation::TeamsDontMatchParticipants])
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_random_requests {
+    use crate::{make_random_request, teams_game_state};
+    use common::{PrivateGameStage, PrivateGameState, TichuError};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+    use std::env;
+
+    /// Can be raised with the `RANDOM_GAMES` environment variable for a longer run
+    const DEFAULT_NUM_GAMES: u64 = 50;
+
+    /// Random play rarely gets a team to 1000 points, so games are cut short
+    const MAX_ROUNDS_PER_GAME: usize = 2;
+
+    /// A game that takes more requests than this is assumed to be stuck
+    const MAX_REQUESTS_PER_GAME: usize = 100_000;
+
+    #[test]
+    fn it_should_keep_its_invariants_no_matter_what_is_requested() {
+        let num_games = env::var("RANDOM_GAMES")
+            .ok()
+            .and_then(|num_games| num_games.parse().ok())
+            .unwrap_or(DEFAULT_NUM_GAMES);
+
+        for seed in 0..num_games {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let mut game_state = teams_game_state(seed);
+
+            let mut requests = 0;
+            while !matches!(game_state.stage, PrivateGameStage::Score(_))
+                && game_state.round_summaries.len() < MAX_ROUNDS_PER_GAME
+            {
+                let result = make_random_request(&game_state, &mut rng);
+                // the engine turns invariant violations into Internal errors, so they must never appear
+                if let Err(TichuError::Internal(message)) = &result {
+                    panic!("Game with seed {seed} hit an internal error: {message}");
+                }
+                if let Ok(new_game_state) = result {
+                    assert_eq!(
+                        new_game_state.check_invariants(),
+                        Ok(()),
+                        "Game with seed {seed} broke its invariants: {:?}",
+                        new_game_state.events.last()
+                    );
+
+                    // a request can lead to several events, each checked against the state before it
+                    let mut previous_game_state = game_state.clone();
+                    for event in new_game_state.events[game_state.events.len()..].iter() {
+                        assert_eq!(
+                            previous_game_state.check_event_invariants(event),
+                            Ok(()),
+                            "Game with seed {seed} accepted {event:?}"
+                        );
+                        previous_game_state = previous_game_state.apply(event).unwrap();
+                    }
+                    game_state = new_game_state;
+                }
+
+                requests += 1;
+                assert!(
+                    requests < MAX_REQUESTS_PER_GAME,
+                    "Game with seed {seed} never ended"
+                );
+            }
+
+            assert_eq!(
+                PrivateGameState::replay(&game_state.events).as_ref(),
+                Ok(&game_state)
+            );
+        }
+    }
+}
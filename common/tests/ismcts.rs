@@ -0,0 +1,466 @@
+use common::{
+    CTSMsg, CallGrandTichuRequest, Card, CardSuit, CardTrade, CardValue, GameRules, ImmutableTeam,
+    PassWithUserId, PrivateGameStage, PrivateGameState, PrivatePlay, PrivateUser, TichuCallStatus,
+    UserIdWithTichuCallStatus, UserRole,
+};
+use std::collections::HashMap;
+
+fn card(suit: CardSuit, value: u8) -> Card {
+    Card {
+        suit,
+        value: CardValue(value),
+    }
+}
+
+/// Carries out a request the same way the server would
+fn handle_request(game_state: &PrivateGameState, user_id: &str, msg: CTSMsg) -> PrivateGameState {
+    match msg.clone() {
+        CTSMsg::PlayCards {
+            cards,
+            wished_for_card_value,
+        } => game_state.play_cards(user_id, cards, wished_for_card_value),
+        CTSMsg::Pass => game_state.pass(user_id),
+        CTSMsg::GiveDragon {
+            user_id: recipient_user_id,
+        } => game_state.give_dragon(user_id, &recipient_user_id),
+        msg => panic!("Search should never send {:?}", msg),
+    }
+    .unwrap_or_else(|err| panic!("{:?} was rejected: {:?}", msg, err))
+}
+
+/// Plays a game from creation up until the first card is led.
+///
+/// Every user gives their first 3 cards to the other 3 users.
+fn started_game_state() -> PrivateGameState {
+    let user_ids = ["1", "2", "3", "4"];
+    let mut game_state =
+        PrivateGameState::new_with_seed("1".into(), "User 1".into(), &HashMap::new(), 7);
+    for user_id in &user_ids[1..] {
+        game_state = game_state
+            .add_user(user_id.to_string(), format!("User {}", user_id))
+            .unwrap();
+    }
+    game_state = game_state.start_grand_tichu("1").unwrap();
+    for user_id in user_ids {
+        game_state = game_state
+            .call_grand_tichu(&CallGrandTichuRequest::Decline, user_id)
+            .unwrap();
+    }
+    for (i, user_id) in user_ids.iter().enumerate() {
+        let hand = game_state
+            .get_user_by_user_id(user_id)
+            .unwrap()
+            .hand
+            .clone();
+        let trade = [1, 2, 3].map(|offset| CardTrade {
+            from_user_id: user_id.to_string(),
+            card: hand[offset - 1].clone(),
+            to_user_id: user_ids[(i + offset) % 4].to_string(),
+        });
+        game_state = game_state.submit_trade(user_id, &trade).unwrap();
+    }
+    game_state
+}
+
+/// Builds a game in the Play stage where user "1" has called Tichu and leads.
+///
+/// Team A is users "1" and "2", Team B is users "3" and "4", and turn order is 1, 3, 2, 4.
+fn tichu_game_state(hand: Vec<Card>) -> PrivateGameState {
+    let user_ids = ["1", "2", "3", "4"];
+    let mut other_cards =
+        (2..=14).flat_map(|value| [CardSuit::Pagoda, CardSuit::Star].map(|suit| card(suit, value)));
+
+    let participants = user_ids
+        .iter()
+        .map(|user_id| PrivateUser {
+            user_id: user_id.to_string(),
+            role: if *user_id == "1" {
+                UserRole::Owner
+            } else {
+                UserRole::Participant
+            },
+            display_name: format!("User {}", user_id),
+            tricks: vec![],
+            has_played_first_card: true,
+            hand: if *user_id == "1" {
+                hand.clone()
+            } else {
+                other_cards.by_ref().take(8).collect()
+            },
+            bot_difficulty: None,
+        })
+        .collect();
+
+    let tichus = |called_user_id: Option<&str>| {
+        user_ids.map(|user_id| UserIdWithTichuCallStatus {
+            user_id: user_id.into(),
+            tichu_call_status: if called_user_id == Some(user_id) {
+                TichuCallStatus::Called
+            } else {
+                TichuCallStatus::Declined
+            },
+        })
+    };
+
+    let play_state = PrivatePlay {
+        small_tichus: tichus(Some("1")),
+        grand_tichus: tichus(None),
+        teams: [
+            ImmutableTeam {
+                id: "a".into(),
+                team_name: "Team A".into(),
+                user_ids: ["1".into(), "2".into()],
+                score: 0,
+            },
+            ImmutableTeam {
+                id: "b".into(),
+                team_name: "Team B".into(),
+                user_ids: ["3".into(), "4".into()],
+                score: 0,
+            },
+        ],
+        table: vec![],
+        turn_user_id: "1".into(),
+        dragon_winner_user_id: None,
+        wished_for_card_value: None,
+        passes: user_ids.map(|user_id| PassWithUserId {
+            user_id: user_id.into(),
+            passed: false,
+        }),
+        users_in_play: vec!["1".into(), "3".into(), "2".into(), "4".into()],
+        first_user_out: None,
+    };
+
+    PrivateGameState {
+        game_id: "game".into(),
+        game_code: "ABC".into(),
+        owner_id: "1".into(),
+        stage: PrivateGameStage::Play(Box::new(play_state)),
+        participants,
+        round_summaries: vec![],
+        seed: 0,
+        rules: GameRules::default(),
+        turn_deadline: None,
+        events: vec![],
+    }
+}
+
+#[cfg(test)]
+mod test_get_ismcts_action {
+    use crate::{card, handle_request, started_game_state, tichu_game_state};
+    use common::{
+        get_bot_action, get_ismcts_action, BotDifficulty, CTSMsg, CardSuit, PrivateGameStage,
+        SearchBudget,
+    };
+
+    #[test]
+    fn it_should_only_act_on_its_turn() {
+        let game_state = started_game_state();
+        let turn_user_id = if let PrivateGameStage::Play(play_state) = &game_state.stage {
+            play_state.turn_user_id.clone()
+        } else {
+            panic!("Game should be in the Play stage");
+        };
+
+        for participant in game_state.participants.iter() {
+            let observation = game_state.get_observation(&participant.user_id).unwrap();
+            let action = get_ismcts_action(&observation, &SearchBudget::Iterations(10), 0);
+            assert_eq!(action.is_some(), participant.user_id == turn_user_id);
+        }
+    }
+
+    #[test]
+    fn it_should_always_choose_the_same_action_for_the_same_seed() {
+        let game_state = started_game_state();
+        let turn_user_id = if let PrivateGameStage::Play(play_state) = &game_state.stage {
+            play_state.turn_user_id.clone()
+        } else {
+            panic!("Game should be in the Play stage");
+        };
+        let observation = game_state.get_observation(&turn_user_id).unwrap();
+
+        assert_eq!(
+            get_ismcts_action(&observation, &SearchBudget::Iterations(50), 3),
+            get_ismcts_action(&observation, &SearchBudget::Iterations(50), 3)
+        );
+    }
+
+    #[test]
+    fn it_should_go_out_to_make_its_tichu() {
+        let game_state = tichu_game_state(vec![card(CardSuit::Sword, 2), card(CardSuit::Jade, 2)]);
+        let observation = game_state.get_observation("1").unwrap();
+
+        assert_eq!(
+            get_ismcts_action(&observation, &SearchBudget::Iterations(200), 0),
+            Some(CTSMsg::PlayCards {
+                cards: vec![card(CardSuit::Sword, 2), card(CardSuit::Jade, 2)],
+                wished_for_card_value: None,
+            })
+        );
+    }
+
+    #[test]
+    fn it_should_stop_searching_once_its_time_is_up() {
+        let game_state = started_game_state();
+        let turn_user_id = if let PrivateGameStage::Play(play_state) = &game_state.stage {
+            play_state.turn_user_id.clone()
+        } else {
+            panic!("Game should be in the Play stage");
+        };
+        let observation = game_state.get_observation(&turn_user_id).unwrap();
+
+        assert!(matches!(
+            get_ismcts_action(&observation, &SearchBudget::Milliseconds(20), 0),
+            Some(CTSMsg::PlayCards { .. })
+        ));
+    }
+
+    #[test]
+    fn it_should_play_a_whole_round_with_only_legal_moves() {
+        let mut game_state = started_game_state();
+        let mut seed = 0;
+        while game_state.round_summaries.is_empty() {
+            let mut requests = Vec::new();
+            for participant in game_state.participants.iter() {
+                let observation = game_state.get_observation(&participant.user_id).unwrap();
+                // user "1" searches, while the others play like Normal bots
+                let action = if participant.user_id == "1" {
+                    get_ismcts_action(&observation, &SearchBudget::Iterations(20), seed)
+                } else {
+                    get_bot_action(&observation, &BotDifficulty::Normal, seed)
+                };
+                if let Some(action) = action {
+                    requests.push((participant.user_id.clone(), action));
+                }
+            }
+
+            assert!(!requests.is_empty(), "Someone should always have a move");
+            for (user_id, request) in requests {
+                game_state = handle_request(&game_state, &user_id, request);
+            }
+            seed += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_get_observation {
+    use crate::started_game_state;
+    use common::{GameEvent, PrivateGameState};
+
+    #[test]
+    fn it_should_include_the_cards_traded_away_this_round() {
+        let game_state = started_game_state();
+        let submitted_trade = game_state
+            .events
+            .iter()
+            .find_map(|event| match event {
+                GameEvent::TradeSubmitted {
+                    user_id,
+                    submit_trade,
+                } if user_id == "2" => Some(submit_trade.to_vec()),
+                _ => None,
+            })
+            .unwrap();
+
+        let observation = game_state.get_observation("2").unwrap();
+        assert_eq!(observation.cards_traded_away, submitted_trade);
+        assert_eq!(
+            observation.game_state,
+            game_state.to_public_game_state("2").unwrap()
+        );
+    }
+
+    #[test]
+    fn it_should_not_include_trades_from_before_the_round_was_dealt() {
+        let game_state = PrivateGameState::replay(
+            &started_game_state()
+                .events
+                .into_iter()
+                .take_while(|event| !matches!(event, GameEvent::TradeSubmitted { .. }))
+                .collect::<Vec<GameEvent>>(),
+        )
+        .unwrap();
+
+        assert!(game_state
+            .get_observation("2")
+            .unwrap()
+            .cards_traded_away
+            .is_empty());
+    }
+}
+
+#[cfg(test)]
+mod test_hard_bot {
+    use crate::{handle_request, started_game_state};
+    use common::{get_bot_action, BotDifficulty, PrivateGameStage};
+
+    #[test]
+    fn it_should_lead_with_a_legal_play() {
+        let game_state = started_game_state();
+        let turn_user_id = if let PrivateGameStage::Play(play_state) = &game_state.stage {
+            play_state.turn_user_id.clone()
+        } else {
+            panic!("Game should be in the Play stage");
+        };
+        let observation = game_state.get_observation(&turn_user_id).unwrap();
+
+        let request = get_bot_action(&observation, &BotDifficulty::Hard, 0).unwrap();
+        handle_request(&game_state, &turn_user_id, request);
+    }
+}
+
+#[cfg(test)]
+mod test_get_tichu_advice {
+    use crate::card;
+    use common::{
+        get_tichu_advice, CardSuit, GameRules, SearchBudget, DOG, DRAGON, GRAND_TICHU_VALUE,
+        MAH_JONG, PHOENIX, SMALL_TICHU_VALUE,
+    };
+
+    #[test]
+    fn it_should_advise_calling_with_a_strong_hand() {
+        let hand = vec![
+            DRAGON,
+            PHOENIX,
+            card(CardSuit::Sword, 14),
+            card(CardSuit::Jade, 14),
+            card(CardSuit::Pagoda, 14),
+            card(CardSuit::Star, 14),
+            card(CardSuit::Sword, 13),
+            card(CardSuit::Jade, 13),
+            card(CardSuit::Pagoda, 13),
+            card(CardSuit::Sword, 12),
+            card(CardSuit::Jade, 12),
+            card(CardSuit::Sword, 11),
+            card(CardSuit::Jade, 11),
+            card(CardSuit::Sword, 10),
+        ];
+        let advice = get_tichu_advice(
+            &hand,
+            &GameRules::default(),
+            &SearchBudget::Iterations(200),
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(advice.tichu_value, SMALL_TICHU_VALUE);
+        assert_eq!(advice.number_of_simulations, 200);
+        assert!(advice.probability_of_going_out_first > 0.5);
+        assert!(advice.expected_value_of_calling > 0.0);
+    }
+
+    #[test]
+    fn it_should_advise_against_calling_with_a_weak_hand() {
+        let hand = vec![
+            DOG,
+            MAH_JONG,
+            card(CardSuit::Sword, 2),
+            card(CardSuit::Jade, 3),
+            card(CardSuit::Pagoda, 4),
+            card(CardSuit::Star, 6),
+            card(CardSuit::Sword, 7),
+            card(CardSuit::Jade, 8),
+            card(CardSuit::Pagoda, 2),
+            card(CardSuit::Sword, 9),
+            card(CardSuit::Jade, 10),
+            card(CardSuit::Star, 3),
+            card(CardSuit::Jade, 5),
+            card(CardSuit::Sword, 11),
+        ];
+        let advice = get_tichu_advice(
+            &hand,
+            &GameRules::default(),
+            &SearchBudget::Iterations(200),
+            0,
+        )
+        .unwrap();
+
+        assert!(advice.probability_of_going_out_first < 0.25);
+        assert!(advice.expected_value_of_calling < 0.0);
+    }
+
+    #[test]
+    fn it_should_advise_on_grand_tichu_before_every_card_is_dealt() {
+        let hand = vec![
+            DRAGON,
+            PHOENIX,
+            card(CardSuit::Sword, 14),
+            card(CardSuit::Jade, 14),
+            card(CardSuit::Pagoda, 14),
+            card(CardSuit::Star, 14),
+            card(CardSuit::Sword, 13),
+            card(CardSuit::Jade, 13),
+            card(CardSuit::Pagoda, 13),
+        ];
+        let advice = get_tichu_advice(
+            &hand,
+            &GameRules::default(),
+            &SearchBudget::Iterations(100),
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(advice.tichu_value, GRAND_TICHU_VALUE);
+        assert_eq!(
+            advice.expected_value_of_calling,
+            GRAND_TICHU_VALUE as f64 * (2.0 * advice.probability_of_going_out_first - 1.0)
+        );
+    }
+
+    #[test]
+    fn it_should_always_give_the_same_advice_for_the_same_seed() {
+        let hand: Vec<_> = (2..=14)
+            .map(|value| card(CardSuit::Star, value))
+            .chain([DOG])
+            .collect();
+
+        assert_eq!(
+            get_tichu_advice(
+                &hand,
+                &GameRules::default(),
+                &SearchBudget::Iterations(50),
+                4
+            ),
+            get_tichu_advice(
+                &hand,
+                &GameRules::default(),
+                &SearchBudget::Iterations(50),
+                4
+            )
+        );
+    }
+
+    #[test]
+    fn it_should_only_advise_on_whole_valid_hands() {
+        let duplicate_cards = vec![card(CardSuit::Sword, 5); 14];
+        let invalid_cards = vec![card(CardSuit::Sword, 15); 14];
+        let partial_hand = vec![DRAGON, PHOENIX, card(CardSuit::Sword, 5)];
+
+        for hand in [duplicate_cards, invalid_cards, partial_hand] {
+            assert_eq!(
+                get_tichu_advice(
+                    &hand,
+                    &GameRules::default(),
+                    &SearchBudget::Iterations(10),
+                    0
+                ),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_stop_simulating_once_its_time_is_up() {
+        let hand: Vec<_> = (2..=10).map(|value| card(CardSuit::Jade, value)).collect();
+        let advice = get_tichu_advice(
+            &hand,
+            &GameRules::default(),
+            &SearchBudget::Milliseconds(20),
+            0,
+        )
+        .unwrap();
+
+        assert!(advice.number_of_simulations >= 1);
+    }
+}
@@ -70,7 +70,7 @@ mod test_get_next_user_turn_id {
             teams: teams.clone(),
             table: vec![],
             turn_user_id: user_1.clone(),
-            user_id_to_give_dragon_to: None,
+            dragon_winner_user_id: None,
             wished_for_card_value: None,
             passes: passes.clone(),
             users_in_play: vec![
@@ -89,7 +89,7 @@ mod test_get_next_user_turn_id {
             teams: teams.clone(),
             table: vec![],
             turn_user_id: user_2.clone(),
-            user_id_to_give_dragon_to: None,
+            dragon_winner_user_id: None,
             wished_for_card_value: None,
             passes: passes.clone(),
             users_in_play: vec![
@@ -108,7 +108,7 @@ mod test_get_next_user_turn_id {
             teams: teams.clone(),
             table: vec![],
             turn_user_id: user_3.clone(),
-            user_id_to_give_dragon_to: None,
+            dragon_winner_user_id: None,
             wished_for_card_value: None,
             passes: passes.clone(),
             users_in_play: vec![
@@ -127,7 +127,7 @@ mod test_get_next_user_turn_id {
             teams: teams.clone(),
             table: vec![],
             turn_user_id: user_4.clone(),
-            user_id_to_give_dragon_to: None,
+            dragon_winner_user_id: None,
             wished_for_card_value: None,
             passes: passes.clone(),
             users_in_play: vec![user_1.clone(), user_2, user_3.clone(), user_4.clone()],
@@ -143,7 +143,7 @@ mod test_get_next_user_turn_id {
             teams: teams.clone(),
             table: vec![],
             turn_user_id: user_1.clone(),
-            user_id_to_give_dragon_to: None,
+            dragon_winner_user_id: None,
             wished_for_card_value: None,
             passes: passes.clone(),
             users_in_play: vec![user_1.clone(), user_3.clone(), user_4.clone()],
@@ -157,7 +157,7 @@ mod test_get_next_user_turn_id {
             teams: teams.clone(),
             table: vec![],
             turn_user_id: user_3.clone(),
-            user_id_to_give_dragon_to: None,
+            dragon_winner_user_id: None,
             wished_for_card_value: None,
             passes: passes.clone(),
             users_in_play: vec![user_1.clone(), user_3.clone(), user_4.clone()],
@@ -171,7 +171,7 @@ mod test_get_next_user_turn_id {
             teams: teams.clone(),
             table: vec![],
             turn_user_id: user_4.clone(),
-            user_id_to_give_dragon_to: None,
+            dragon_winner_user_id: None,
             wished_for_card_value: None,
             passes: passes.clone(),
             users_in_play: vec![user_1.clone(), user_3.clone(), user_4],
@@ -187,7 +187,7 @@ mod test_get_next_user_turn_id {
             teams: teams.clone(),
             table: vec![],
             turn_user_id: user_1.clone(),
-            user_id_to_give_dragon_to: None,
+            dragon_winner_user_id: None,
             wished_for_card_value: None,
             passes: passes.clone(),
             users_in_play: vec![user_1.clone(), user_3.clone()],
@@ -201,7 +201,7 @@ mod test_get_next_user_turn_id {
             teams: teams.clone(),
             table: vec![],
             turn_user_id: user_3.clone(),
-            user_id_to_give_dragon_to: None,
+            dragon_winner_user_id: None,
             wished_for_card_value: None,
             passes: passes,
             users_in_play: vec![user_1, user_3],
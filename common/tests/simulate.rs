@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod test_simulate {
+    use std::process::{Command, Output};
+
+    fn simulate(args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_simulate"))
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    #[test]
+    fn it_should_play_every_game_to_the_end() {
+        let output = simulate(&["--games", "2", "--seed", "5", "easy", "normal"]);
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(String::from_utf8_lossy(&output.stdout).starts_with("Played 2 games"));
+    }
+
+    #[test]
+    fn it_should_report_the_same_results_for_the_same_seed() {
+        let args = ["--games", "2", "--seed", "9", "normal", "ismcts:5"];
+
+        assert_eq!(simulate(&args).stdout, simulate(&args).stdout);
+    }
+
+    #[test]
+    fn it_should_reject_unknown_strategies() {
+        let output = simulate(&["normal", "expert"]);
+
+        assert_eq!(output.status.code(), Some(2));
+    }
+}
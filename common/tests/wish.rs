@@ -0,0 +1,503 @@
+use common::{
+    get_card_combination, Card, CardSuit, CardValue, GameRules, ImmutableTeam, PassWithUserId,
+    PrivateGameStage, PrivateGameState, PrivatePlay, PrivateUser, TichuCallStatus,
+    UserIdWithTichuCallStatus, UserRole, ValidCardCombo,
+};
+
+fn card(suit: CardSuit, value: u8) -> Card {
+    Card {
+        suit,
+        value: CardValue(value),
+    }
+}
+
+/// Combo previously played by `user_id`
+fn combo(cards: Vec<Card>, user_id: &str) -> ValidCardCombo {
+    get_card_combination(None, &cards, user_id).expect("Cards should form a valid combo")
+}
+
+/// Builds a game in the Play stage where every user is still in play.
+///
+/// Team A is users "1" and "2", Team B is users "3" and "4", and turn order is 1, 3, 2, 4.
+fn wish_game_state(
+    hands: [Vec<Card>; 4],
+    table: Vec<ValidCardCombo>,
+    turn_user_id: &str,
+    wished_for_card_value: Option<u8>,
+) -> PrivateGameState {
+    let user_ids = ["1", "2", "3", "4"];
+
+    let participants = user_ids
+        .iter()
+        .zip(hands)
+        .map(|(user_id, hand)| PrivateUser {
+            user_id: user_id.to_string(),
+            role: if *user_id == "1" {
+                UserRole::Owner
+            } else {
+                UserRole::Participant
+            },
+            display_name: format!("User {}", user_id),
+            tricks: vec![],
+            has_played_first_card: true,
+            hand,
+            bot_difficulty: None,
+        })
+        .collect();
+
+    let undecided = || {
+        user_ids.map(|user_id| UserIdWithTichuCallStatus {
+            user_id: user_id.into(),
+            tichu_call_status: TichuCallStatus::Undecided,
+        })
+    };
+
+    let play_state = PrivatePlay {
+        small_tichus: undecided(),
+        grand_tichus: undecided(),
+        teams: [
+            ImmutableTeam {
+                id: "a".into(),
+                team_name: "Team A".into(),
+                user_ids: ["1".into(), "2".into()],
+                score: 0,
+            },
+            ImmutableTeam {
+                id: "b".into(),
+                team_name: "Team B".into(),
+                user_ids: ["3".into(), "4".into()],
+                score: 0,
+            },
+        ],
+        table,
+        turn_user_id: turn_user_id.into(),
+        dragon_winner_user_id: None,
+        wished_for_card_value: wished_for_card_value.map(CardValue),
+        passes: user_ids.map(|user_id| PassWithUserId {
+            user_id: user_id.into(),
+            passed: false,
+        }),
+        users_in_play: vec!["1".into(), "3".into(), "2".into(), "4".into()],
+        first_user_out: None,
+    };
+
+    PrivateGameState {
+        game_id: "game".into(),
+        game_code: "ABC".into(),
+        owner_id: "1".into(),
+        stage: PrivateGameStage::Play(Box::new(play_state)),
+        participants,
+        round_summaries: vec![],
+        seed: 0,
+        rules: GameRules::default(),
+        turn_deadline: None,
+        events: vec![],
+    }
+}
+
+fn get_wished_for_card_value(game_state: &PrivateGameState) -> Option<CardValue> {
+    if let PrivateGameStage::Play(play_state) = &game_state.stage {
+        play_state.wished_for_card_value.clone()
+    } else {
+        panic!("Expected the Play stage, got {:?}", game_state.stage);
+    }
+}
+
+#[cfg(test)]
+mod test_wish_when_leading {
+    use crate::{card, get_wished_for_card_value, wish_game_state};
+    use common::{CardSuit, CardValue, TichuError};
+
+    #[test]
+    fn it_should_require_leading_the_wished_for_card() {
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 8)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                vec![card(CardSuit::Star, 2), card(CardSuit::Star, 4)],
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+            ],
+            vec![],
+            "1",
+            Some(8),
+        );
+
+        assert_eq!(
+            game_state
+                .play_cards("1", vec![card(CardSuit::Jade, 3)], None)
+                .unwrap_err(),
+            TichuError::MustFulfillWish
+        );
+
+        let game_state = game_state
+            .play_cards("1", vec![card(CardSuit::Jade, 8)], None)
+            .expect("Leading the wished-for card should be accepted");
+        assert_eq!(get_wished_for_card_value(&game_state), None);
+    }
+
+    #[test]
+    fn it_should_allow_any_lead_without_the_wished_for_card() {
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 9)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                vec![card(CardSuit::Star, 2), card(CardSuit::Star, 4)],
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 8)],
+            ],
+            vec![],
+            "1",
+            Some(8),
+        );
+
+        let game_state = game_state
+            .play_cards("1", vec![card(CardSuit::Jade, 3)], None)
+            .expect("User without the wished-for card can lead anything");
+        assert_eq!(get_wished_for_card_value(&game_state), Some(CardValue(8)));
+    }
+}
+
+#[cfg(test)]
+mod test_wish_when_following {
+    use crate::{card, combo, get_wished_for_card_value, wish_game_state};
+    use common::{CardSuit, CardValue, TichuError};
+
+    #[test]
+    fn it_should_require_playing_the_wished_for_card_when_it_beats_the_table() {
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 4)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                vec![card(CardSuit::Star, 8), card(CardSuit::Star, 12)],
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+            ],
+            vec![combo(vec![card(CardSuit::Jade, 5)], "1")],
+            "3",
+            Some(8),
+        );
+
+        // the Queen beats the table, but so does the wished-for eight
+        assert_eq!(
+            game_state
+                .play_cards("3", vec![card(CardSuit::Star, 12)], None)
+                .unwrap_err(),
+            TichuError::MustFulfillWish
+        );
+
+        let game_state = game_state
+            .play_cards("3", vec![card(CardSuit::Star, 8)], None)
+            .expect("Playing the wished-for card should be accepted");
+        assert_eq!(get_wished_for_card_value(&game_state), None);
+    }
+
+    #[test]
+    fn it_should_allow_other_plays_when_the_wished_for_card_cannot_beat_the_table() {
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 4)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                vec![
+                    card(CardSuit::Star, 8),
+                    card(CardSuit::Star, 12),
+                    card(CardSuit::Jade, 12),
+                ],
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+            ],
+            vec![combo(
+                vec![card(CardSuit::Jade, 10), card(CardSuit::Sword, 10)],
+                "1",
+            )],
+            "3",
+            Some(8),
+        );
+
+        // a single eight can't be played on a pair
+        let game_state = game_state
+            .play_cards(
+                "3",
+                vec![card(CardSuit::Star, 12), card(CardSuit::Jade, 12)],
+                None,
+            )
+            .expect("User who can't fulfill the wish can play anything valid");
+        assert_eq!(get_wished_for_card_value(&game_state), Some(CardValue(8)));
+    }
+}
+
+#[cfg(test)]
+mod test_wish_when_passing {
+    use crate::{card, combo, get_wished_for_card_value, wish_game_state};
+    use common::{CardSuit, CardValue, PrivateGameStage, TichuError};
+
+    #[test]
+    fn it_should_reject_passing_when_the_wish_can_be_fulfilled() {
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 4)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                vec![card(CardSuit::Star, 8), card(CardSuit::Star, 12)],
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+            ],
+            vec![combo(vec![card(CardSuit::Jade, 5)], "1")],
+            "3",
+            Some(8),
+        );
+
+        assert_eq!(
+            game_state.pass("3").unwrap_err(),
+            TichuError::MustFulfillWish
+        );
+    }
+
+    #[test]
+    fn it_should_allow_passing_when_the_wish_cannot_be_fulfilled() {
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 4)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                vec![card(CardSuit::Star, 8), card(CardSuit::Star, 12)],
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+            ],
+            vec![combo(vec![card(CardSuit::Jade, 9)], "1")],
+            "3",
+            Some(8),
+        );
+
+        assert!(game_state.pass("3").is_ok());
+    }
+
+    #[test]
+    fn it_should_let_users_take_their_own_trick_while_holding_the_wished_for_card() {
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 8)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                vec![card(CardSuit::Star, 2), card(CardSuit::Star, 4)],
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+            ],
+            vec![combo(vec![card(CardSuit::Jade, 5)], "1")],
+            "3",
+            Some(8),
+        );
+
+        // everyone else passes, so the turn comes back around to the user whose combo is on top
+        let game_state = ["3", "2", "4"]
+            .iter()
+            .fold(game_state, |game_state, user_id| {
+                game_state
+                    .pass(user_id)
+                    .expect("Users without the wished-for card can pass")
+            });
+
+        let game_state = game_state
+            .pass("1")
+            .expect("Users can pass to take their own trick, even holding the wished-for card");
+        if let PrivateGameStage::Play(play_state) = &game_state.stage {
+            assert!(play_state.table.is_empty());
+            assert_eq!(play_state.turn_user_id, "1");
+        } else {
+            panic!("Expected the Play stage, got {:?}", game_state.stage);
+        }
+        assert_eq!(get_wished_for_card_value(&game_state), Some(CardValue(8)));
+
+        // once they lead again, the wish applies as usual
+        assert_eq!(
+            game_state
+                .play_cards("1", vec![card(CardSuit::Jade, 3)], None)
+                .unwrap_err(),
+            TichuError::MustFulfillWish
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_wish_with_bombs {
+    use crate::{card, combo, get_wished_for_card_value, wish_game_state};
+    use common::{CardSuit, CardValue, TichuError};
+
+    fn four_eights() -> Vec<common::Card> {
+        vec![
+            card(CardSuit::Jade, 8),
+            card(CardSuit::Sword, 8),
+            card(CardSuit::Pagoda, 8),
+            card(CardSuit::Star, 8),
+        ]
+    }
+
+    #[test]
+    fn it_should_require_a_bomb_containing_the_wished_for_card() {
+        let mut hand = four_eights();
+        hand.push(card(CardSuit::Jade, 2));
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 4)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                hand,
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+            ],
+            vec![combo(vec![card(CardSuit::Jade, 14)], "1")],
+            "3",
+            Some(8),
+        );
+
+        // a single eight can't beat the Ace, but a bomb of eights can
+        assert_eq!(
+            game_state.pass("3").unwrap_err(),
+            TichuError::MustFulfillWish
+        );
+
+        let game_state = game_state
+            .play_cards("3", four_eights(), None)
+            .expect("Bombing with the wished-for card should be accepted");
+        assert_eq!(get_wished_for_card_value(&game_state), None);
+    }
+
+    #[test]
+    fn it_should_reject_an_in_turn_bomb_without_the_wished_for_card() {
+        let nines = vec![
+            card(CardSuit::Jade, 9),
+            card(CardSuit::Sword, 9),
+            card(CardSuit::Pagoda, 9),
+            card(CardSuit::Star, 9),
+        ];
+        let mut hand = nines.clone();
+        hand.push(card(CardSuit::Jade, 8));
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 4)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                hand,
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+            ],
+            vec![combo(vec![card(CardSuit::Jade, 5)], "1")],
+            "3",
+            Some(8),
+        );
+
+        assert_eq!(
+            game_state.play_cards("3", nines, None).unwrap_err(),
+            TichuError::MustFulfillWish
+        );
+    }
+
+    #[test]
+    fn it_should_clear_the_wish_when_fulfilled_by_an_out_of_turn_bomb() {
+        let mut hand = four_eights();
+        hand.push(card(CardSuit::Jade, 2));
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 4)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+                hand,
+            ],
+            vec![combo(vec![card(CardSuit::Jade, 14)], "1")],
+            "3",
+            Some(8),
+        );
+
+        let game_state = game_state
+            .play_cards("4", four_eights(), None)
+            .expect("Bombing out of turn should be accepted");
+        assert_eq!(get_wished_for_card_value(&game_state), None);
+    }
+
+    #[test]
+    fn it_should_allow_an_out_of_turn_bomb_without_the_wished_for_card() {
+        let nines = vec![
+            card(CardSuit::Jade, 9),
+            card(CardSuit::Sword, 9),
+            card(CardSuit::Pagoda, 9),
+            card(CardSuit::Star, 9),
+        ];
+        let mut hand = nines.clone();
+        hand.push(card(CardSuit::Jade, 8));
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 4)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+                hand,
+            ],
+            vec![combo(vec![card(CardSuit::Jade, 5)], "1")],
+            "3",
+            Some(8),
+        );
+
+        let game_state = game_state
+            .play_cards("4", nines, None)
+            .expect("Bombing out of turn doesn't have to fulfill the wish");
+        assert_eq!(get_wished_for_card_value(&game_state), Some(CardValue(8)));
+    }
+}
+
+#[cfg(test)]
+mod test_wish_with_phoenix {
+    use crate::{card, combo, get_wished_for_card_value, wish_game_state};
+    use common::{CardSuit, CardValue, TichuError, PHOENIX};
+
+    #[test]
+    fn it_should_require_using_the_phoenix_to_complete_a_wished_for_combo() {
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 4)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                vec![
+                    card(CardSuit::Star, 8),
+                    PHOENIX,
+                    card(CardSuit::Star, 12),
+                    card(CardSuit::Jade, 12),
+                ],
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+            ],
+            vec![combo(
+                vec![card(CardSuit::Jade, 7), card(CardSuit::Sword, 7)],
+                "1",
+            )],
+            "3",
+            Some(8),
+        );
+
+        assert_eq!(
+            game_state.pass("3").unwrap_err(),
+            TichuError::MustFulfillWish
+        );
+        assert_eq!(
+            game_state
+                .play_cards(
+                    "3",
+                    vec![card(CardSuit::Star, 12), card(CardSuit::Jade, 12)],
+                    None,
+                )
+                .unwrap_err(),
+            TichuError::MustFulfillWish
+        );
+
+        let game_state = game_state
+            .play_cards("3", vec![card(CardSuit::Star, 8), PHOENIX], None)
+            .expect("A pair of the wished-for card and the Phoenix should be accepted");
+        assert_eq!(get_wished_for_card_value(&game_state), None);
+    }
+
+    #[test]
+    fn it_should_not_treat_the_phoenix_as_the_wished_for_card() {
+        let game_state = wish_game_state(
+            [
+                vec![card(CardSuit::Jade, 3), card(CardSuit::Jade, 4)],
+                vec![card(CardSuit::Sword, 2), card(CardSuit::Sword, 4)],
+                vec![PHOENIX, card(CardSuit::Star, 12)],
+                vec![card(CardSuit::Pagoda, 2), card(CardSuit::Pagoda, 4)],
+            ],
+            vec![combo(vec![card(CardSuit::Jade, 5)], "1")],
+            "3",
+            Some(8),
+        );
+
+        // holding only the Phoenix doesn't oblige the user to fulfill the wish
+        assert!(game_state.pass("3").is_ok());
+
+        // and playing it doesn't fulfill the wish
+        let game_state = game_state
+            .play_cards("3", vec![PHOENIX], None)
+            .expect("Phoenix can be played on a single");
+        assert_eq!(get_wished_for_card_value(&game_state), Some(CardValue(8)));
+    }
+}
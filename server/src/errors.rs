@@ -1,2 +0,0 @@
-pub static USER_ID_NOT_IN_MAP: &str = "Couldn't find user_id in HashMap";
-pub static GAME_ID_NOT_IN_MAP: &str = "Couldn't find game_id in HashMap";
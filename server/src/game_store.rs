@@ -0,0 +1,160 @@
+use common::PrivateGameState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Bump whenever `PrivateGameState` changes in a way that old snapshots can't be read back into.
+/// Snapshots saved under any other version are skipped at startup.
+pub const GAME_SNAPSHOT_SCHEMA_VERSION: u32 = 2;
+
+/// A game, along with what its users need to reconnect to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub game_state: PrivateGameState,
+
+    /// Maps each user's `user_id` to their session token. Bots don't have one.
+    pub session_tokens: HashMap<String, String>,
+}
+
+/// Somewhere to keep a copy of every game in progress, so that games can outlive the server.
+pub trait GameStore: Send + Sync {
+    /// Saves the latest state of a game, replacing any earlier snapshot of it
+    fn save(&self, saved_game: &SavedGame) -> io::Result<()>;
+
+    /// Forgets a game once every user has left it
+    fn delete(&self, game_id: &str) -> io::Result<()>;
+
+    /// Every game that was saved and not deleted
+    fn load_all(&self) -> io::Result<Vec<SavedGame>>;
+}
+
+/// Keeps games in memory only, so they're lost whenever the server stops.
+#[derive(Debug, Default)]
+pub struct InMemoryGameStore {
+    games: Mutex<HashMap<String, SavedGame>>,
+}
+
+impl GameStore for InMemoryGameStore {
+    fn save(&self, saved_game: &SavedGame) -> io::Result<()> {
+        self.games
+            .lock()
+            .expect("Game store lock should not be poisoned")
+            .insert(saved_game.game_state.game_id.clone(), saved_game.clone());
+        Ok(())
+    }
+
+    fn delete(&self, game_id: &str) -> io::Result<()> {
+        self.games
+            .lock()
+            .expect("Game store lock should not be poisoned")
+            .remove(game_id);
+        Ok(())
+    }
+
+    fn load_all(&self) -> io::Result<Vec<SavedGame>> {
+        Ok(self
+            .games
+            .lock()
+            .expect("Game store lock should not be poisoned")
+            .values()
+            .cloned()
+            .collect())
+    }
+}
+
+/// Saves each game as a JSON snapshot named after its `game_id` in the given directory.
+#[derive(Debug)]
+pub struct FileGameStore {
+    dir: PathBuf,
+}
+
+#[derive(Serialize)]
+struct GameSnapshotRef<'a> {
+    schema_version: u32,
+    saved_game: &'a SavedGame,
+}
+
+#[derive(Deserialize)]
+struct GameSnapshotVersion {
+    schema_version: u32,
+}
+
+#[derive(Deserialize)]
+struct GameSnapshot {
+    saved_game: SavedGame,
+}
+
+impl FileGameStore {
+    /// Creates the directory if it doesn't exist yet
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<FileGameStore> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(FileGameStore { dir })
+    }
+
+    fn get_snapshot_path(&self, game_id: &str) -> PathBuf {
+        self.dir.join(format!("{game_id}.json"))
+    }
+
+    fn load(&self, path: &PathBuf) -> Result<SavedGame, String> {
+        let contents = fs::read(path).map_err(|err| err.to_string())?;
+
+        // check the version before anything else, since the rest of the format may have changed
+        let version: GameSnapshotVersion =
+            serde_json::from_slice(&contents).map_err(|err| err.to_string())?;
+        if version.schema_version != GAME_SNAPSHOT_SCHEMA_VERSION {
+            return Err(format!(
+                "Snapshot has schema version {}, but only version {} can be loaded",
+                version.schema_version, GAME_SNAPSHOT_SCHEMA_VERSION
+            ));
+        }
+
+        let snapshot: GameSnapshot =
+            serde_json::from_slice(&contents).map_err(|err| err.to_string())?;
+        Ok(snapshot.saved_game)
+    }
+}
+
+impl GameStore for FileGameStore {
+    fn save(&self, saved_game: &SavedGame) -> io::Result<()> {
+        let snapshot = GameSnapshotRef {
+            schema_version: GAME_SNAPSHOT_SCHEMA_VERSION,
+            saved_game,
+        };
+        let contents = serde_json::to_vec(&snapshot)?;
+
+        // write to a temporary file first, so a crash mid-write never leaves a half-written snapshot
+        let path = self.get_snapshot_path(&saved_game.game_state.game_id);
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(&temp_path, &path)
+    }
+
+    fn delete(&self, game_id: &str) -> io::Result<()> {
+        match fs::remove_file(self.get_snapshot_path(game_id)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    fn load_all(&self) -> io::Result<Vec<SavedGame>> {
+        let mut saved_games = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension() != Some(OsStr::new("json")) {
+                continue;
+            }
+
+            // one unreadable snapshot shouldn't keep every other game from being restored
+            match self.load(&path) {
+                Ok(saved_game) => saved_games.push(saved_game),
+                Err(err) => eprintln!("Skipping game snapshot {}: {err}", path.display()),
+            }
+        }
+        Ok(saved_games)
+    }
+}
@@ -1,13 +1,14 @@
 // #![deny(warnings)]
 extern crate common;
-mod errors;
+mod game_store;
 mod routes;
+mod shutdown;
 
-use common::{PrivateGameState, STCMsg, NO_USER_ID};
-use futures::join;
+use common::{STCMsg, NO_USER_ID};
+use game_store::{FileGameStore, GameStore, InMemoryGameStore};
 use routes::{
     index,
-    ws::{self, send_ws_message},
+    ws::{self, restore_games, send_ws_message, GameCommand},
 };
 use std::collections::HashMap;
 use std::env;
@@ -24,16 +25,24 @@ use warp::Filter;
 /// Maps `user_id`s to websocket connections and `game_codes`
 pub type Connections = Arc<RwLock<HashMap<String, ConnectionData>>>;
 
-/// Maps `game_id`s to game states
-pub type Games = Arc<RwLock<HashMap<String, PrivateGameState>>>;
+/// Maps `game_id`s to the channel of the task running each game
+pub type Games = Arc<RwLock<HashMap<String, mpsc::UnboundedSender<GameCommand>>>>;
 
 /// Maps 4-character `game_code`s -> `game_id`s
 pub type GameCodes = Arc<RwLock<HashMap<String, String>>>;
 
+/// Where games are saved so that they can be restored after a restart
+pub type SharedGameStore = Arc<dyn GameStore>;
+
 #[derive(Debug)]
 pub struct ConnectionData {
     pub user_id: String,
     pub game_id: Option<String>,
+    /// Secret that a client must present to reconnect as this `user_id`, since `user_id`s are public.
+    /// None for bots, which never connect.
+    pub session_token: Option<String>,
+    /// Can send admin commands, because the client connected with the server's admin token
+    pub is_admin: bool,
     /// Used for ping/pong diagnostics
     pub is_alive: Arc<RwLock<bool>>,
     /// Is the user's websocket currently connected?
@@ -53,6 +62,23 @@ async fn main() {
     let games = Games::default();
     let game_codes = GameCodes::default();
 
+    // games only survive a restart if they're saved to disk
+    let game_store: SharedGameStore =
+        if let Ok(game_store_dir) = env::var("GAME_STORE_DIR") {
+            eprintln!("Saving games to {game_store_dir}");
+            Arc::new(FileGameStore::new(game_store_dir).expect(
+                "Could not create the directory in the GAME_STORE_DIR environment variable",
+            ))
+        } else {
+            Arc::new(InMemoryGameStore::default())
+        };
+    restore_games(&connections, &games, &game_codes, &game_store).await;
+
+    // admin commands can only be sent by clients that know the admin token, so without one nobody can
+    let admin_token = env::var("ADMIN_TOKEN")
+        .ok()
+        .filter(|admin_token| !admin_token.is_empty());
+
     let connections_clone = Arc::clone(&connections);
 
     // send ping messages every 5 messages to every websocket
@@ -87,32 +113,61 @@ async fn main() {
         }
     });
 
+    // kept for saving games and notifying clients on shutdown
+    let shutdown_connections = Arc::clone(&connections);
+    let shutdown_games = Arc::clone(&games);
+
     // GET /ws -> websocket upgrade
     let ws_route = warp::path("ws")
         // The `ws()` filter will prepare Websocket handshake...
         .and(warp::ws())
-        // get `user_id` query parameter
-        .and(warp::filters::query::raw().map(|e: String| {
-            let user_id = if let Some(user_id) = e.split_once('=') {
-                user_id.1
-            } else {
-                eprintln!("Error parsing user_id from query parameters. Using NO_USER_ID value instead for user");
-                NO_USER_ID
-            };
-            String::from(user_id)
-        }))
+        // get `user_id`, `session_token` and `admin_token` query parameters
+        .and(
+            warp::query::<HashMap<String, String>>()
+                .map(move |mut query: HashMap<String, String>| {
+                    let user_id = query.remove("user_id").unwrap_or_else(|| {
+                        eprintln!("Error parsing user_id from query parameters. Using NO_USER_ID value instead for user");
+                        String::from(NO_USER_ID)
+                    });
+                    let session_token = query.remove("session_token");
+                    let is_admin = match (query.remove("admin_token"), &admin_token) {
+                        (Some(token), Some(admin_token)) => ws::tokens_match(&token, admin_token),
+                        _ => false,
+                    };
+                    (user_id, session_token, is_admin)
+                })
+                .untuple_one(),
+        )
         // get connections hashmap
         .and(warp::any().map(move || Arc::clone(&connections)))
         // get games hashmap
         .and(warp::any().map(move || Arc::clone(&games)))
         // get game codes hashmap
         .and(warp::any().map(move || Arc::clone(&game_codes)))
+        // get game store
+        .and(warp::any().map(move || Arc::clone(&game_store)))
         // combine filters into a handler function
         .map(
-            |ws: warp::ws::Ws, user_id: String, connections, games, game_codes| {
+            |ws: warp::ws::Ws,
+             user_id: String,
+             session_token: Option<String>,
+             is_admin: bool,
+             connections,
+             games,
+             game_codes,
+             game_store| {
                 // This will call our function if the handshake succeeds.
                 ws.on_upgrade(move |socket| {
-                    ws::handle_ws_upgrade(socket, user_id, connections, games, game_codes)
+                    ws::handle_ws_upgrade(
+                        socket,
+                        user_id,
+                        session_token,
+                        is_admin,
+                        connections,
+                        games,
+                        game_codes,
+                        game_store,
+                    )
                 })
             },
         );
@@ -131,5 +186,12 @@ async fn main() {
     .parse()
     .expect("Could not parse provided PORT environment variable into u16");
 
-    let (_, _) = join!(warp::serve(routes).run(([0, 0, 0, 0], port)), ping_pong);
+    // dropping the server stops it from accepting any new connections
+    tokio::select! {
+        _ = warp::serve(routes).run(([0, 0, 0, 0], port)) => {},
+        _ = shutdown::wait_for_shutdown_signal() => {},
+    }
+
+    ping_pong.abort();
+    shutdown::shut_down(&shutdown_connections, &shutdown_games).await;
 }
@@ -0,0 +1,235 @@
+use super::{
+    game_task::{send_game_command, GameCommand},
+    handle_message_received,
+};
+use crate::{Connections, GameCodes, Games, SharedGameStore};
+use common::{get_bot_action, get_random_seed, get_timeout_action, BotDifficulty, CTSMsg, STCMsg};
+use futures::future::BoxFuture;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::{
+    sync::{mpsc::UnboundedReceiver, oneshot},
+    task, time,
+};
+use warp::ws::Message;
+
+/// How long bots wait before acting, so that people can follow along
+static BOT_DELAY_MS: u64 = 1_000;
+
+/// What a bot should do after receiving a message from the server
+enum BotTrigger {
+    /// Nothing changed for the bot
+    Ignore,
+    /// The game changed: it might be the bot's turn
+    GameStateChanged,
+    /// The bot's last request was rejected, so it should choose again
+    ActionRejected,
+    /// The bot's game is over or the bot was removed from it
+    Stop,
+}
+
+/// Drives a bot participant for as long as it is in a game.
+///
+/// Bots receive the same messages as users with websocket connections, and every request they
+/// make goes through `handle_message_received`, so they can only do what a user could.
+pub fn run_bot(
+    bot_user_id: String,
+    bot_difficulty: BotDifficulty,
+    mut rx: UnboundedReceiver<Message>,
+    connections: Connections,
+    games: Games,
+    game_codes: GameCodes,
+    game_store: SharedGameStore,
+) -> BoxFuture<'static, ()> {
+    // boxed, since bots are spawned while handling a received message,
+    // and they send their own requests through `handle_message_received`
+    Box::pin(async move {
+        eprintln!("Bot {bot_user_id}: Started");
+
+        // after a rejection, the bot falls back to the same request the server would send
+        // if it ran out of time, which is always allowed
+        let mut should_use_timeout_action = false;
+        let mut sent_timeout_action = false;
+
+        while let Some(message) = rx.recv().await {
+            match handle_message_for_bot(
+                &bot_user_id,
+                message,
+                &connections,
+                &games,
+                &game_codes,
+                &game_store,
+            )
+            .await
+            {
+                BotTrigger::Ignore => continue,
+                BotTrigger::Stop => break,
+                BotTrigger::GameStateChanged => {}
+                BotTrigger::ActionRejected if sent_timeout_action => {
+                    eprintln!("Bot {bot_user_id}: Even the timeout action was rejected");
+                    continue;
+                }
+                BotTrigger::ActionRejected => should_use_timeout_action = true,
+            }
+
+            time::sleep(Duration::from_millis(BOT_DELAY_MS)).await;
+
+            // only act on the latest game state
+            let mut should_stop = false;
+            while let Ok(message) = rx.try_recv() {
+                match handle_message_for_bot(
+                    &bot_user_id,
+                    message,
+                    &connections,
+                    &games,
+                    &game_codes,
+                    &game_store,
+                )
+                .await
+                {
+                    BotTrigger::Stop => {
+                        should_stop = true;
+                        break;
+                    }
+                    BotTrigger::ActionRejected if !sent_timeout_action => {
+                        should_use_timeout_action = true;
+                    }
+                    _ => {}
+                }
+            }
+            if should_stop {
+                break;
+            }
+
+            let read_connections = connections.read().await;
+            let game_id = read_connections
+                .get(&bot_user_id)
+                .and_then(|connection_data| connection_data.game_id.clone());
+            drop(read_connections);
+            let game_id = if let Some(game_id) = game_id {
+                game_id
+            } else {
+                break;
+            };
+
+            // only the game's task can read its state
+            let (reply_tx, reply_rx) = oneshot::channel();
+            let command = GameCommand::GetObservation {
+                user_id: bot_user_id.clone(),
+                reply: reply_tx,
+            };
+            if !send_game_command(&game_id, command, &games).await {
+                break;
+            }
+            let observation = if let Ok(Some(observation)) = reply_rx.await {
+                observation
+            } else {
+                break;
+            };
+
+            // searching for a play can take a while, so keep it off the async runtime's threads
+            let bot_difficulty_clone = bot_difficulty.clone();
+            let use_timeout_action = should_use_timeout_action;
+            let msg = task::spawn_blocking(move || {
+                if use_timeout_action {
+                    get_timeout_action(&observation, get_random_seed())
+                } else {
+                    get_bot_action(&observation, &bot_difficulty_clone, get_random_seed())
+                }
+            })
+            .await;
+            sent_timeout_action = should_use_timeout_action;
+            should_use_timeout_action = false;
+            match msg {
+                Ok(Some(msg)) => {
+                    send_bot_request(
+                        &bot_user_id,
+                        &msg,
+                        &connections,
+                        &games,
+                        &game_codes,
+                        &game_store,
+                    )
+                    .await
+                }
+                Ok(None) => {}
+                Err(err) => eprintln!("Bot {bot_user_id}: Couldn't choose an action: {err}"),
+            }
+        }
+
+        eprintln!("Bot {bot_user_id}: Stopped");
+    })
+}
+
+async fn handle_message_for_bot(
+    bot_user_id: &str,
+    message: Message,
+    connections: &Connections,
+    games: &Games,
+    game_codes: &GameCodes,
+    game_store: &SharedGameStore,
+) -> BotTrigger {
+    if message.is_close() {
+        return BotTrigger::Stop;
+    }
+
+    let msg: STCMsg = if let Ok(msg) = bincode::deserialize(message.as_bytes()) {
+        msg
+    } else {
+        eprintln!("Bot {bot_user_id}: Could not deserialize message from server");
+        return BotTrigger::Ignore;
+    };
+
+    match msg {
+        STCMsg::Ping => {
+            send_bot_request(
+                bot_user_id,
+                &CTSMsg::Pong,
+                connections,
+                games,
+                game_codes,
+                game_store,
+            )
+            .await;
+            BotTrigger::Ignore
+        }
+        STCMsg::GameState(game_state) => {
+            if game_state.is_some() {
+                BotTrigger::GameStateChanged
+            } else {
+                BotTrigger::Stop
+            }
+        }
+        STCMsg::ActionRejected { request, reason } => {
+            eprintln!("Bot {bot_user_id}: Request {request:?} was rejected: {reason}");
+            BotTrigger::ActionRejected
+        }
+        _ => BotTrigger::Ignore,
+    }
+}
+
+async fn send_bot_request(
+    bot_user_id: &str,
+    msg: &CTSMsg,
+    connections: &Connections,
+    games: &Games,
+    game_codes: &GameCodes,
+    game_store: &SharedGameStore,
+) {
+    let msg = if let Ok(msg) = bincode::serialize(msg) {
+        msg
+    } else {
+        eprintln!("Bot {bot_user_id}: Could not serialize message {:?}", msg);
+        return;
+    };
+
+    handle_message_received(
+        bot_user_id.to_string(),
+        Message::binary(msg),
+        Arc::clone(connections),
+        Arc::clone(games),
+        Arc::clone(game_codes),
+        Arc::clone(game_store),
+    )
+    .await;
+}
@@ -1,23 +1,23 @@
-use super::send_ws_message;
+use super::{
+    game_task::{send_game_command, GameCommand},
+    send_ws_message,
+};
 use crate::{Connections, GameCodes, Games};
-use common::{PrivateGameStage, STCMsg};
+use common::{PrivateGameStage, PrivateGameState, STCMsg};
 
 const FUNCTION_NAME: &str = "cleanup_state_after_disconnect";
 
-/// When a user disconnects, clean up their connection state and any game state they're associated with.
+/// When a user disconnects, clean up their connection state and hand them off to their game's task,
+/// which cleans up any game state they're associated with.
 pub async fn cleanup_state_after_disconnect(
     user_id: &str,
     connections: &Connections,
     games: &Games,
-    game_codes: &GameCodes,
 ) {
     eprintln!("{FUNCTION_NAME}: User disconnected: {}", user_id);
 
     let mut write_connections = connections.write().await;
-    let mut write_games = games.write().await;
-    let mut write_game_codes = game_codes.write().await;
 
-    // extract all needed game state
     let game_id_clone = if let Some(connection) = &write_connections.get(user_id) {
         if let Some(game_id) = &connection.game_id {
             game_id.clone()
@@ -32,20 +32,40 @@ pub async fn cleanup_state_after_disconnect(
         eprintln!("{FUNCTION_NAME}: Can't cleanup Connections state for user {}, because user was not found in the Connections HashMap", user_id);
         return;
     };
-    let game_state_clone = match write_games.get(&game_id_clone) {
-        Some(game_state) => game_state.clone(),
-        None => {
-            eprintln!("{FUNCTION_NAME}: Couldn't find associated game for game_id that was saved in Connection object for user {}", user_id);
-            return;
-        }
+
+    drop(write_connections);
+
+    let command = GameCommand::UserDisconnected {
+        user_id: user_id.to_string(),
     };
+    if !send_game_command(&game_id_clone, command, games).await {
+        eprintln!("{FUNCTION_NAME}: Couldn't find associated game for game_id that was saved in Connection object for user {}", user_id);
+        connections.write().await.remove(user_id);
+    }
+}
+
+/// Removes a disconnected user from their game: users are only dropped from the lobby,
+/// everywhere else they're marked as disconnected so that they can come back.
+/// Deletes the game once no users are left.
+pub async fn remove_disconnected_user(
+    user_id: &str,
+    game_state: &mut PrivateGameState,
+    connections: &Connections,
+    games: &Games,
+    game_codes: &GameCodes,
+) {
+    let mut write_connections = connections.write().await;
+
+    let game_state_clone = game_state.clone();
+    let game_id_clone = game_state_clone.game_id.clone();
     let game_code_clone = game_state_clone.game_code.clone();
     let participants_clone = game_state_clone.participants.clone();
 
     // check if any other participants are still connected
     let mut any_other_user_is_still_in_game = false;
+    // bots don't keep a game alive on their own
     for participant in participants_clone.iter() {
-        if participant.user_id != user_id {
+        if participant.user_id != user_id && !participant.is_bot() {
             let participant_connection = match write_connections.get(&participant.user_id) {
                 Some(participant_connection) => participant_connection,
                 // participant not found in ConnectionsMap
@@ -86,52 +106,31 @@ pub async fn cleanup_state_after_disconnect(
                 }
             };
 
-            let game = match write_games.get_mut(&game_id_clone) {
-                Some(game) => game,
-                None => {
-                    eprintln!("{FUNCTION_NAME}: Couldn't remove user_id {} from game state because game was not found via the game_id", user_id);
-                    return;
-                }
-            };
-
             // update game state without the participant there
-            *game = new_game_state.clone();
+            *game_state = new_game_state;
 
             drop(write_connections);
-            drop(write_games);
-            drop(write_game_codes);
 
             // notify remaining participants that user left
             send_ws_message::to_group(
-                &game_id_clone,
+                game_state,
                 STCMsg::UserLeft(user_id.to_string()),
                 connections,
-                games,
-                game_codes,
             )
             .await;
 
             // notify remaining participants that new owner was chosen
             if owner_reassigned {
                 send_ws_message::to_group(
-                    &game_id_clone,
-                    STCMsg::OwnerReassigned(new_game_state.owner_id.clone()),
+                    game_state,
+                    STCMsg::OwnerReassigned(game_state.owner_id.clone()),
                     connections,
-                    games,
-                    game_codes,
                 )
                 .await;
             }
 
             // send updated game state
-            send_ws_message::game_state_to_group(
-                &game_id_clone,
-                &new_game_state,
-                connections,
-                games,
-                game_codes,
-            )
-            .await;
+            send_ws_message::game_state_to_group(game_state, connections).await;
         } else {
             // no other users left in game and user is not in lobby: only mark this user as disconnected
             eprintln!("{FUNCTION_NAME} Marking user {} as not connected", user_id);
@@ -143,28 +142,17 @@ pub async fn cleanup_state_after_disconnect(
             }
 
             drop(write_connections);
-            drop(write_games);
-            drop(write_game_codes);
 
             // notify remaining participants that user was disconnected
             send_ws_message::to_group(
-                &game_id_clone,
+                game_state,
                 STCMsg::UserDisconnected(user_id.to_string()),
                 connections,
-                games,
-                game_codes,
             )
             .await;
 
             // send old game state (no change occurred)
-            send_ws_message::game_state_to_group(
-                &game_id_clone,
-                &game_state_clone,
-                connections,
-                games,
-                game_codes,
-            )
-            .await;
+            send_ws_message::game_state_to_group(game_state, connections).await;
         }
     } else {
         // no users left in game: remove all users from Connections and delete game from Games
@@ -175,7 +163,10 @@ pub async fn cleanup_state_after_disconnect(
         for participant in participants_clone.iter() {
             write_connections.remove(&participant.user_id);
         }
-        write_games.remove(&game_id_clone);
-        write_game_codes.remove(&game_code_clone);
+        drop(write_connections);
+
+        // deleting the game stops its task
+        games.write().await.remove(&game_id_clone);
+        game_codes.write().await.remove(&game_code_clone);
     }
 }
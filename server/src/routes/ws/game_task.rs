@@ -0,0 +1,500 @@
+use super::{
+    bot, cleanup_state_after_disconnect::remove_disconnected_user,
+    handle_message_received::handle_game_request, send_ws_message,
+};
+use crate::{
+    game_store::SavedGame, ConnectionData, Connections, GameCodes, Games, SharedGameStore,
+};
+use common::{
+    get_current_time_ms, get_random_seed, get_timeout_action, CTSMsg, Observation,
+    PrivateGameStage, PrivateGameState, STCMsg,
+};
+use futures::future::BoxFuture;
+use std::future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{
+    mpsc::{self, UnboundedReceiver},
+    oneshot, RwLock,
+};
+use tokio::{task, time};
+
+/// How long users have to reconnect to a restored game before it's deleted (10 minutes)
+static RESTORED_GAME_GRACE_PERIOD_MS: u64 = 600_000;
+
+/// Everything a game's task can be asked to do. Only that task ever touches the game's state.
+#[derive(Debug)]
+pub enum GameCommand {
+    /// A request from a participant, or from a user joining the game
+    Request { user_id: String, msg: CTSMsg },
+
+    /// A participant's websocket reconnected
+    UserReconnected { user_id: String },
+
+    /// A participant's websocket closed
+    UserDisconnected { user_id: String },
+
+    /// Asks for everything a participant has seen of the game, e.g. so that a bot can act on it
+    GetObservation {
+        user_id: String,
+        reply: oneshot::Sender<Option<Observation>>,
+    },
+
+    /// The server is shutting down: save the game and stop, without deleting it from the game store
+    Shutdown { saved: oneshot::Sender<()> },
+
+    /// A restored game's grace period is over: delete it if none of its users came back
+    ExpireIfAbandoned,
+}
+
+/// Sends a command to the task running the given game. Returns false if there is no such game.
+pub async fn send_game_command(game_id: &str, command: GameCommand, games: &Games) -> bool {
+    let read_games = games.read().await;
+    match read_games.get(game_id) {
+        Some(tx) => tx.send(command).is_ok(),
+        None => false,
+    }
+}
+
+/// Runs a game until it is deleted, i.e. until every user has left it.
+///
+/// The task owns the game's state and carries out its commands one at a time, in the order they
+/// arrive, so a busy game never holds up any other. When the rules have a turn time limit,
+/// the task also acts for users who run out of time.
+///
+/// Every change to the game is saved to the game store, and the game is deleted from it
+/// once the task stops.
+pub fn run_game(
+    mut game_state: PrivateGameState,
+    mut rx: UnboundedReceiver<GameCommand>,
+    connections: Connections,
+    games: Games,
+    game_codes: GameCodes,
+    game_store: SharedGameStore,
+) -> BoxFuture<'static, ()> {
+    // boxed, since games are created while handling a received message,
+    // and their bots send requests through `handle_message_received`
+    Box::pin(async move {
+        let game_id = game_state.game_id.clone();
+        eprintln!("Game {game_id}: Started");
+        save_game(&game_state, &connections, &game_store).await;
+        let mut saved_game_state = game_state.clone();
+
+        loop {
+            let turn_deadline = game_state.turn_deadline;
+            tokio::select! {
+                command = rx.recv() => match command {
+                    Some(command) => {
+                        let is_shutdown = matches!(command, GameCommand::Shutdown { .. });
+                        handle_game_command(
+                            command,
+                            &mut game_state,
+                            &connections,
+                            &games,
+                            &game_codes,
+                            &game_store,
+                        )
+                        .await;
+
+                        // keep the game in the game store, so it can be restored after the restart
+                        if is_shutdown {
+                            eprintln!("Game {game_id}: Stopped for shutdown");
+                            return;
+                        }
+                    }
+                    None => break,
+                },
+                _ = sleep_until_turn_deadline(turn_deadline) => {
+                    time_out_users(&mut game_state, &connections, &games, &game_codes, &game_store)
+                        .await
+                }
+            }
+
+            // games are deleted once every user has left
+            if !games.read().await.contains_key(&game_id) {
+                break;
+            }
+
+            start_turn_clock(&mut game_state, &connections).await;
+
+            if game_state != saved_game_state {
+                save_game(&game_state, &connections, &game_store).await;
+                saved_game_state = game_state.clone();
+            }
+        }
+
+        if let Err(err) = task::block_in_place(|| game_store.delete(&game_id)) {
+            eprintln!("Game {game_id}: Couldn't delete game from the game store: {err}");
+        }
+        eprintln!("Game {game_id}: Stopped");
+    })
+}
+
+async fn handle_game_command(
+    command: GameCommand,
+    game_state: &mut PrivateGameState,
+    connections: &Connections,
+    games: &Games,
+    game_codes: &GameCodes,
+    game_store: &SharedGameStore,
+) {
+    match command {
+        GameCommand::Request { user_id, msg } => {
+            handle_game_request(
+                &user_id,
+                msg,
+                game_state,
+                connections,
+                games,
+                game_codes,
+                game_store,
+            )
+            .await
+        }
+        GameCommand::UserReconnected { user_id } => {
+            // notify other participants (if any) that the user reconnected
+            send_ws_message::to_group(
+                game_state,
+                STCMsg::UserReconnected(user_id.clone()),
+                connections,
+            )
+            .await;
+
+            // send the returning participant a state update
+            send_ws_message::to_user(
+                &user_id,
+                STCMsg::GameState(Box::new(game_state.to_public_game_state(&user_id).ok())),
+                connections,
+            )
+            .await;
+        }
+        GameCommand::UserDisconnected { user_id } => {
+            remove_disconnected_user(&user_id, game_state, connections, games, game_codes).await
+        }
+        GameCommand::GetObservation { user_id, reply } => {
+            // the requester may have stopped waiting, which is fine
+            let _ = reply.send(game_state.get_observation(&user_id).ok());
+        }
+        GameCommand::Shutdown { saved } => {
+            save_game(game_state, connections, game_store).await;
+            // the server may have stopped waiting, which is fine
+            let _ = saved.send(());
+        }
+        GameCommand::ExpireIfAbandoned => {
+            delete_game_if_abandoned(game_state, connections, games, game_codes).await
+        }
+    }
+}
+
+/// Deletes a game that none of its users are connected to, along with its users' connections.
+/// Deleting the game stops its task, which then deletes it from the game store.
+async fn delete_game_if_abandoned(
+    game_state: &PrivateGameState,
+    connections: &Connections,
+    games: &Games,
+    game_codes: &GameCodes,
+) {
+    let mut write_connections = connections.write().await;
+
+    // bots don't keep a game alive on their own
+    let any_user_is_connected = game_state.participants.iter().any(|participant| {
+        !participant.is_bot()
+            && write_connections
+                .get(&participant.user_id)
+                .is_some_and(|connection_data| connection_data.connected)
+    });
+    if any_user_is_connected {
+        return;
+    }
+
+    eprintln!(
+        "Game {}: No users came back to the game, so deleting it",
+        game_state.game_id
+    );
+    for participant in game_state.participants.iter() {
+        write_connections.remove(&participant.user_id);
+    }
+    drop(write_connections);
+
+    games.write().await.remove(&game_state.game_id);
+    game_codes.write().await.remove(&game_state.game_code);
+}
+
+/// Waits until the turn deadline passes, or forever if there is none
+async fn sleep_until_turn_deadline(turn_deadline: Option<u64>) {
+    match turn_deadline {
+        Some(turn_deadline) => {
+            let ms_left = turn_deadline.saturating_sub(get_current_time_ms() as u64);
+            time::sleep(Duration::from_millis(ms_left)).await
+        }
+        None => future::pending().await,
+    }
+}
+
+/// Every event clears the turn deadline: whenever the game starts waiting on a new decision,
+/// restart the clock and let the group know
+async fn start_turn_clock(game_state: &mut PrivateGameState, connections: &Connections) {
+    let turn_time_limit_secs = match game_state.rules.turn_time_limit_secs {
+        Some(turn_time_limit_secs) => turn_time_limit_secs,
+        None => return,
+    };
+    let is_timed_stage = matches!(
+        game_state.stage,
+        PrivateGameStage::GrandTichu(_) | PrivateGameStage::Trade(_) | PrivateGameStage::Play(_)
+    );
+    if !is_timed_stage || game_state.turn_deadline.is_some() {
+        return;
+    }
+
+    game_state.turn_deadline = Some(get_current_time_ms() as u64 + turn_time_limit_secs * 1000);
+    send_ws_message::game_state_to_group(game_state, connections).await;
+}
+
+/// Acts for every user the game is still waiting on, once they've run out of time
+async fn time_out_users(
+    game_state: &mut PrivateGameState,
+    connections: &Connections,
+    games: &Games,
+    game_codes: &GameCodes,
+    game_store: &SharedGameStore,
+) {
+    let timeout_actions: Vec<(String, CTSMsg)> = game_state
+        .participants
+        .iter()
+        .filter_map(|participant| {
+            let observation = game_state.get_observation(&participant.user_id).ok()?;
+            get_timeout_action(&observation, get_random_seed())
+                .map(|msg| (participant.user_id.clone(), msg))
+        })
+        .collect();
+
+    for (user_id, msg) in timeout_actions {
+        eprintln!(
+            "Game {}: User {user_id} ran out of time",
+            game_state.game_id
+        );
+        handle_game_request(
+            &user_id,
+            msg,
+            game_state,
+            connections,
+            games,
+            game_codes,
+            game_store,
+        )
+        .await;
+    }
+
+    // accepted requests already cleared the deadline: if none were, give everyone more time
+    game_state.turn_deadline = None;
+}
+
+/// Saves the game along with its users' session tokens, so that they can reconnect after a restart
+async fn save_game(
+    game_state: &PrivateGameState,
+    connections: &Connections,
+    game_store: &SharedGameStore,
+) {
+    let read_connections = connections.read().await;
+    let session_tokens = game_state
+        .participants
+        .iter()
+        .filter_map(|participant| {
+            let connection_data = read_connections.get(&participant.user_id)?;
+            let session_token = connection_data.session_token.clone()?;
+            Some((participant.user_id.clone(), session_token))
+        })
+        .collect();
+    drop(read_connections);
+
+    let saved_game = SavedGame {
+        game_state: game_state.clone(),
+        session_tokens,
+    };
+
+    // writing a snapshot blocks, so let the runtime move other tasks off this thread meanwhile
+    if let Err(err) = task::block_in_place(|| game_store.save(&saved_game)) {
+        eprintln!(
+            "Game {}: Couldn't save game to the game store: {err}",
+            game_state.game_id
+        );
+    }
+}
+
+/// Starts a task for every game in the game store, e.g. after a restart.
+///
+/// Users come back as disconnected participants, so they rejoin their game as soon as they
+/// reconnect with the same `user_id` and session token. Bots pick up where they left off.
+/// Games that none of their users have come back to after a grace period are deleted.
+pub async fn restore_games(
+    connections: &Connections,
+    games: &Games,
+    game_codes: &GameCodes,
+    game_store: &SharedGameStore,
+) {
+    restore_games_with_grace_period(
+        connections,
+        games,
+        game_codes,
+        game_store,
+        Duration::from_millis(RESTORED_GAME_GRACE_PERIOD_MS),
+    )
+    .await
+}
+
+async fn restore_games_with_grace_period(
+    connections: &Connections,
+    games: &Games,
+    game_codes: &GameCodes,
+    game_store: &SharedGameStore,
+    grace_period: Duration,
+) {
+    let saved_games = match game_store.load_all() {
+        Ok(saved_games) => saved_games,
+        Err(err) => {
+            eprintln!("Couldn't load games from the game store: {err}");
+            return;
+        }
+    };
+
+    for SavedGame {
+        mut game_state,
+        session_tokens,
+    } in saved_games
+    {
+        // the clock restarts once the game's task is running
+        game_state.turn_deadline = None;
+
+        let mut write_connections = connections.write().await;
+        for participant in game_state.participants.iter() {
+            let (tx, rx) = mpsc::unbounded_channel();
+            write_connections.insert(
+                participant.user_id.clone(),
+                ConnectionData {
+                    user_id: participant.user_id.clone(),
+                    game_id: Some(game_state.game_id.clone()),
+                    session_token: session_tokens.get(&participant.user_id).cloned(),
+                    is_admin: false,
+                    is_alive: Arc::new(RwLock::new(true)),
+                    connected: participant.is_bot(),
+                    tx,
+                },
+            );
+            if let Some(bot_difficulty) = &participant.bot_difficulty {
+                task::spawn(bot::run_bot(
+                    participant.user_id.clone(),
+                    bot_difficulty.clone(),
+                    rx,
+                    Arc::clone(connections),
+                    Arc::clone(games),
+                    Arc::clone(game_codes),
+                    Arc::clone(game_store),
+                ));
+            }
+        }
+        drop(write_connections);
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        games.write().await.insert(game_state.game_id.clone(), tx);
+        game_codes
+            .write()
+            .await
+            .insert(game_state.game_code.clone(), game_state.game_id.clone());
+
+        eprintln!("Game {}: Restored", game_state.game_id);
+
+        // let any bots know whether it's their turn
+        send_ws_message::game_state_to_group(&game_state, connections).await;
+
+        // the game may have been deleted by then, in which case there's nothing left to expire
+        let game_id = game_state.game_id.clone();
+        let games_clone = Arc::clone(games);
+        task::spawn(async move {
+            time::sleep(grace_period).await;
+            send_game_command(&game_id, GameCommand::ExpireIfAbandoned, &games_clone).await;
+        });
+
+        task::spawn(run_game(
+            game_state,
+            rx,
+            Arc::clone(connections),
+            Arc::clone(games),
+            Arc::clone(game_codes),
+            Arc::clone(game_store),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::restore_games_with_grace_period;
+    use crate::game_store::{InMemoryGameStore, SavedGame};
+    use crate::{Connections, GameCodes, Games, SharedGameStore};
+    use common::PrivateGameState;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::time;
+
+    const GRACE_PERIOD: Duration = Duration::from_millis(50);
+
+    /// Saves a game with one user, restores it, and returns the restored game's `game_id`
+    async fn restore_saved_game(
+        connections: &Connections,
+        games: &Games,
+        game_codes: &GameCodes,
+        game_store: &SharedGameStore,
+    ) -> String {
+        let game_state = PrivateGameState::new("1".into(), "User 1".into(), &HashMap::new());
+        let game_id = game_state.game_id.clone();
+        game_store
+            .save(&SavedGame {
+                game_state,
+                session_tokens: HashMap::from([("1".into(), "token".into())]),
+            })
+            .unwrap();
+
+        restore_games_with_grace_period(connections, games, game_codes, game_store, GRACE_PERIOD)
+            .await;
+        assert!(games.read().await.contains_key(&game_id));
+        game_id
+    }
+
+    // saving and deleting games blocks, which needs a multi-threaded runtime
+    #[tokio::test(flavor = "multi_thread")]
+    async fn it_should_delete_a_restored_game_nobody_came_back_to() {
+        let connections = Connections::default();
+        let games = Games::default();
+        let game_codes = GameCodes::default();
+        let game_store: SharedGameStore = Arc::new(InMemoryGameStore::default());
+        restore_saved_game(&connections, &games, &game_codes, &game_store).await;
+
+        // the game's task deletes the game from the game store last
+        for _ in 0..100 {
+            if game_store.load_all().unwrap().is_empty() {
+                break;
+            }
+            time::sleep(GRACE_PERIOD).await;
+        }
+
+        assert!(game_store.load_all().unwrap().is_empty());
+        assert!(games.read().await.is_empty());
+        assert!(game_codes.read().await.is_empty());
+        assert!(connections.read().await.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn it_should_keep_a_restored_game_a_user_came_back_to() {
+        let connections = Connections::default();
+        let games = Games::default();
+        let game_codes = GameCodes::default();
+        let game_store: SharedGameStore = Arc::new(InMemoryGameStore::default());
+        let game_id = restore_saved_game(&connections, &games, &game_codes, &game_store).await;
+
+        connections.write().await.get_mut("1").unwrap().connected = true;
+        time::sleep(GRACE_PERIOD * 4).await;
+
+        assert!(games.read().await.contains_key(&game_id));
+        assert_eq!(game_store.load_all().unwrap().len(), 1);
+    }
+}
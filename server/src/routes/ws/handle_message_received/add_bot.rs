@@ -0,0 +1,84 @@
+use crate::{
+    routes::ws::{bot, send_ws_message},
+    ConnectionData, Connections, GameCodes, Games, SharedGameStore,
+};
+use common::{BotDifficulty, PrivateGameStage, PrivateGameState, STCMsg, TichuError};
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+
+const FUNCTION_NAME: &str = "add_bot";
+
+pub async fn add_bot(
+    bot_difficulty: BotDifficulty,
+    user_id: &str,
+    game_state: &mut PrivateGameState,
+    connections: &Connections,
+    games: &Games,
+    game_codes: &GameCodes,
+    game_store: &SharedGameStore,
+) -> Result<(), TichuError> {
+    let new_game_state = match game_state.add_bot(user_id, bot_difficulty.clone()) {
+        Ok(updated_game_state) => updated_game_state,
+        Err(err) => {
+            eprintln!("{FUNCTION_NAME}: Rejected request from user {user_id}: {err}");
+            return Err(err);
+        }
+    };
+    *game_state = new_game_state;
+
+    // bots always join last
+    let bot_user_id = game_state
+        .participants
+        .last()
+        .expect("Game should have a participant after adding a bot")
+        .user_id
+        .clone();
+
+    // bots receive messages through a channel, just like users with websockets
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut write_connections = connections.write().await;
+    write_connections.insert(
+        bot_user_id.clone(),
+        ConnectionData {
+            user_id: bot_user_id.clone(),
+            game_id: Some(game_state.game_id.clone()),
+            session_token: None,
+            is_admin: false,
+            is_alive: Arc::new(RwLock::new(true)),
+            connected: true,
+            tx,
+        },
+    );
+
+    drop(write_connections);
+
+    tokio::task::spawn(bot::run_bot(
+        bot_user_id.clone(),
+        bot_difficulty,
+        rx,
+        Arc::clone(connections),
+        Arc::clone(games),
+        Arc::clone(game_codes),
+        Arc::clone(game_store),
+    ));
+
+    eprintln!("{FUNCTION_NAME}: User {user_id} successfully added bot {bot_user_id} to game");
+
+    // User Joined event
+    send_ws_message::to_group(game_state, STCMsg::UserJoined(bot_user_id), connections).await;
+
+    // Game Stage Changed event
+    if let PrivateGameStage::Teams(_) = game_state.stage {
+        send_ws_message::to_group(
+            game_state,
+            STCMsg::GameStageChanged(game_state.stage.clone().into()),
+            connections,
+        )
+        .await;
+    }
+
+    // Game State
+    send_ws_message::game_state_to_group(game_state, connections).await;
+
+    Ok(())
+}
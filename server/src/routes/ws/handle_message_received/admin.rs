@@ -0,0 +1,29 @@
+use crate::{routes::ws::send_ws_message, Connections};
+use common::{AdminCommand, PrivateGameState, TichuError};
+
+const FUNCTION_NAME: &str = "admin";
+
+/// Only reached by admins: non-admins are turned away before their request gets to the game
+pub async fn admin(
+    admin_command: &AdminCommand,
+    user_id: &str,
+    game_state: &mut PrivateGameState,
+    connections: &Connections,
+) -> Result<(), TichuError> {
+    // update game state
+    let new_game_state = match game_state.run_admin_command(admin_command) {
+        Ok(updated_game_state) => updated_game_state,
+        Err(err) => {
+            eprintln!("{FUNCTION_NAME}: Rejected {admin_command:?} from user {user_id}: {err}");
+            return Err(err);
+        }
+    };
+
+    eprintln!("{FUNCTION_NAME}: User {user_id} ran {admin_command:?}");
+    *game_state = new_game_state;
+
+    // send updated game state
+    send_ws_message::game_state_to_group(game_state, connections).await;
+
+    Ok(())
+}
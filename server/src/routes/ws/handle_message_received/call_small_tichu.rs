@@ -1,41 +1,13 @@
-use crate::{routes::ws::send_ws_message, Connections, GameCodes, Games};
-use common::{PrivateGameStage, STCMsg, TichuCallStatus};
+use crate::{routes::ws::send_ws_message, Connections};
+use common::{PrivateGameStage, PrivateGameState, STCMsg, TichuCallStatus, TichuError};
 
 const FUNCTION_NAME: &str = "call_small_tichu";
 
 pub async fn call_small_tichu(
     user_id: &str,
+    game_state: &mut PrivateGameState,
     connections: &Connections,
-    games: &Games,
-    game_codes: &GameCodes,
-) {
-    let read_connections = connections.read().await;
-    let mut write_games = games.write().await;
-
-    let user = match read_connections.get(user_id) {
-        Some(user) => user,
-        None => {
-            eprintln!("{FUNCTION_NAME}: User {user_id} can't call Small Tichu because their user_id could not be found in the Connections HashMap");
-            return;
-        }
-    };
-
-    let game_id = match user.game_id.clone() {
-        Some(game_id) => game_id,
-        None => {
-            eprintln!("{FUNCTION_NAME}: User {user_id} can't call Small Tichu because they have no game_id associated with their Connection state");
-            return;
-        }
-    };
-
-    let game_state = match write_games.get_mut(&game_id) {
-        Some(game_state) => game_state,
-        None => {
-            eprintln!("{FUNCTION_NAME}: User {user_id} can't call Small Tichu because the game_id they are associated with could not be found in the Games HashMap");
-            return;
-        }
-    };
-
+) -> Result<(), TichuError> {
     // game stage cannot be lobby, teams, or scoreboard
     let small_tichus = match &mut game_state.stage {
         PrivateGameStage::Lobby | PrivateGameStage::Teams(_) | PrivateGameStage::Score(_) => {
@@ -43,7 +15,7 @@ pub async fn call_small_tichu(
                 "{FUNCTION_NAME}: Can't call Small Tichu when game is not active. Ignoring request from user {}",
                 user_id
             );
-            return;
+            return Err(TichuError::WrongStage);
         }
         PrivateGameStage::GrandTichu(grand_tichu_state) => &mut grand_tichu_state.small_tichus,
         PrivateGameStage::Trade(trade) => &mut trade.small_tichus,
@@ -56,13 +28,13 @@ pub async fn call_small_tichu(
     match i {
         None => {
             eprintln!("{FUNCTION_NAME}: Couldn't find user's call status in call stage state. Ignoring request to call Small Tichu from user {}", user_id);
-            return;
+            return Err(TichuError::UserNotFound);
         }
         Some(i) => {
             let user_call_status = &small_tichus[i];
             if user_call_status.tichu_call_status != TichuCallStatus::Undecided {
                 eprintln!("{FUNCTION_NAME}: User is not in Undecided state about Small Tichu. Ignoring request to call Small Tichu from user {}", user_id);
-                return;
+                return Err(TichuError::TichuAlreadyDecided);
             }
         }
     }
@@ -75,23 +47,23 @@ pub async fn call_small_tichu(
     // update game state
     let new_game_state = match game_state.call_small_tichu(user_id) {
         Ok(new_game_state) => new_game_state,
-        Err(error) => return eprintln!("{}", error),
+        Err(error) => {
+            eprintln!("{FUNCTION_NAME}: Rejected request from user {user_id}: {error}");
+            return Err(error);
+        }
     };
-    *game_state = new_game_state.clone();
-
-    drop(write_games);
+    *game_state = new_game_state;
 
     // send GrandTichuCalled
     send_ws_message::to_group(
-        &game_id,
+        game_state,
         STCMsg::SmallTichuCalled(user_id.to_string()),
         connections,
-        games,
-        game_codes,
     )
     .await;
 
     // send updated game state
-    send_ws_message::game_state_to_group(&game_id, &new_game_state, connections, games, game_codes)
-        .await;
+    send_ws_message::game_state_to_group(game_state, connections).await;
+
+    Ok(())
 }
@@ -1,52 +1,68 @@
-use super::send_ws_message;
-use crate::{Connections, GameCodes, Games};
-use common::{validate_display_name, CTSMsg, PrivateGameState, STCMsg};
+use crate::routes::ws::{game_task, send_ws_message};
+use crate::{shutdown::is_shutting_down, Connections, GameCodes, Games, SharedGameStore};
+use common::{validate_display_name, CTSMsg, PrivateGameState, STCMsg, TichuError};
+use std::sync::Arc;
+use tokio::sync::mpsc;
 
 const FUNCTION_NAME: &str = "create_game";
 
 pub async fn create_game(
+    user_id: &str,
     create_game_data: CTSMsg,
-    connections: Connections,
+    connections: &Connections,
     games: &Games,
     game_codes: &GameCodes,
-) {
+    game_store: &SharedGameStore,
+) -> Result<(), TichuError> {
     if let CTSMsg::CreateGame {
-        user_id,
+        user_id: requested_user_id,
         display_name,
     } = create_game_data
     {
+        // only the user who opened the connection can create a game with it
+        if requested_user_id != user_id {
+            eprintln!("{FUNCTION_NAME}: User {user_id} can't create game on behalf of user {requested_user_id}");
+            return Err(TichuError::UserIdMismatch);
+        }
+
+        // games started now would be cut off by the restart
+        if is_shutting_down() {
+            eprintln!("{FUNCTION_NAME}: User {user_id} can't create game because the server is shutting down");
+            return Err(TichuError::ServerShuttingDown);
+        }
+
         // bad inputs from client, ignore request
         if validate_display_name(&display_name).is_some() {
             eprintln!("{FUNCTION_NAME}: User {user_id} can't create game because they do not have a valid display name");
-            return;
+            return Err(TichuError::InvalidDisplayName);
         }
 
         // verify that user_id is not already associated with a game before creating a new one
         let mut write_connections = connections.write().await;
-        let connection = if let Some(connection_data) = write_connections.get_mut(&user_id) {
-            eprintln!("{FUNCTION_NAME}: User {user_id} can't create game because ConnectionData could not be found for user_id");
+        let connection = if let Some(connection_data) = write_connections.get_mut(user_id) {
             connection_data
         } else {
-            return;
+            eprintln!("{FUNCTION_NAME}: User {user_id} can't create game because ConnectionData could not be found for user_id");
+            return Err(TichuError::ConnectionNotFound);
         };
 
         // user already associated with a game, no action needed
         if connection.game_id.is_some() {
             eprintln!("{FUNCTION_NAME}: Can't create game for user because user is already associated with a game");
-            return;
+            return Err(TichuError::AlreadyInGame);
         }
 
         // user is NOT associated with a game: create game for user
-        let read_game_codes = game_codes.read().await;
-        let game_state = PrivateGameState::new(user_id.clone(), display_name, &read_game_codes);
-        drop(read_game_codes);
+        let mut write_game_codes = game_codes.write().await;
+        let game_state =
+            PrivateGameState::new(user_id.to_string(), display_name, &write_game_codes);
+        write_game_codes.insert(game_state.game_code.clone(), game_state.game_id.clone());
 
-        // save game to state
+        // every game runs in its own task, which receives commands through a channel
+        let (tx, rx) = mpsc::unbounded_channel();
         let mut write_games = games.write().await;
-        write_games.insert(game_state.game_id.clone(), game_state.clone());
+        write_games.insert(game_state.game_id.clone(), tx);
         let _ = connection.game_id.insert(game_state.game_id.clone());
-        let mut write_game_codes = game_codes.write().await;
-        write_game_codes.insert(game_state.game_code.clone(), game_state.game_id.clone());
 
         let game_created = STCMsg::GameCreated {
             game_code: game_state.game_code.clone(),
@@ -60,6 +76,15 @@ pub async fn create_game(
         drop(write_connections);
         drop(write_game_codes);
 
+        tokio::task::spawn(game_task::run_game(
+            game_state.clone(),
+            rx,
+            Arc::clone(connections),
+            Arc::clone(games),
+            Arc::clone(game_codes),
+            Arc::clone(game_store),
+        ));
+
         // send updated new game state to owner only
         // --no need to iterate through participants, since it's a new game
         eprintln!(
@@ -68,14 +93,16 @@ pub async fn create_game(
         );
 
         // Game Created event
-        send_ws_message::to_user(&user_id, game_created, &connections).await;
+        send_ws_message::to_user(user_id, game_created, connections).await;
 
         // Updated Game State
         send_ws_message::to_user(
-            &user_id,
-            STCMsg::GameState(Box::new(game_state.to_public_game_state(&user_id).ok())),
-            &connections,
+            user_id,
+            STCMsg::GameState(Box::new(game_state.to_public_game_state(user_id).ok())),
+            connections,
         )
         .await;
     }
+
+    Ok(())
 }
@@ -0,0 +1,42 @@
+use crate::{routes::ws::send_ws_message, Connections};
+use common::{PrivateGameState, STCMsg, TichuError};
+
+const FUNCTION_NAME: &str = "give_dragon";
+
+pub async fn give_dragon(
+    user_id: &str,
+    recipient_user_id: &str,
+    game_state: &mut PrivateGameState,
+    connections: &Connections,
+) -> Result<(), TichuError> {
+    // update game state
+    let new_game_state = match game_state.give_dragon(user_id, recipient_user_id) {
+        Ok(updated_game_state) => updated_game_state,
+        Err(err) => {
+            eprintln!("{FUNCTION_NAME}: Rejected request from user {user_id}: {err}");
+            return Err(err);
+        }
+    };
+    *game_state = new_game_state;
+
+    eprintln!(
+        "{FUNCTION_NAME}: User {} successfully gave the Dragon to user {}",
+        user_id, recipient_user_id
+    );
+
+    // send Dragon given event to group
+    send_ws_message::to_group(
+        game_state,
+        STCMsg::PlayerReceivedDragon {
+            from_user_id: user_id.into(),
+            to_user_id: recipient_user_id.into(),
+        },
+        connections,
+    )
+    .await;
+
+    // send updated game state
+    send_ws_message::game_state_to_group(game_state, connections).await;
+
+    Ok(())
+}
@@ -1,37 +1,54 @@
 use crate::{
-    errors::USER_ID_NOT_IN_MAP, routes::ws::send_ws_message, Connections, GameCodes, Games,
+    routes::ws::{
+        game_task::{send_game_command, GameCommand},
+        send_ws_message,
+    },
+    Connections, GameCodes, Games,
+};
+use common::{
+    validate_display_name, validate_game_code, CTSMsg, PrivateGameStage, PrivateGameState, STCMsg,
+    TichuError,
 };
-use common::{validate_display_name, validate_game_code, CTSMsg, PrivateGameStage, STCMsg};
 
 const FUNCTION_NAME: &str = "join_game_with_game_code";
 
+/// Passes a request to join a game on to the task running the game with that game code
 pub async fn join_game_with_game_code(
+    user_id: &str,
     join_game_with_game_code_data: CTSMsg,
-    connections: Connections,
-    games: Games,
-    game_codes: GameCodes,
-) {
+    connections: &Connections,
+    games: &Games,
+    game_codes: &GameCodes,
+) -> Result<(), TichuError> {
     if let CTSMsg::JoinGameWithGameCode {
-        user_id,
+        user_id: requested_user_id,
         display_name,
         game_code,
-    } = join_game_with_game_code_data
+    } = &join_game_with_game_code_data
     {
+        // only the user who opened the connection can join a game with it
+        if requested_user_id != user_id {
+            eprintln!("{FUNCTION_NAME}: User {user_id} can't join game on behalf of user {requested_user_id}");
+            return Err(TichuError::UserIdMismatch);
+        }
+
         // bad inputs from client, ignore request
-        if validate_display_name(&display_name).is_some()
-            || validate_game_code(&game_code).is_some()
-        {
-            eprintln!("{FUNCTION_NAME}: User {user_id} can't join game with game code because they submitted an invalid display name or game code");
-            return;
+        if validate_display_name(display_name).is_some() {
+            eprintln!("{FUNCTION_NAME}: User {user_id} can't join game with game code because they submitted an invalid display name");
+            return Err(TichuError::InvalidDisplayName);
+        }
+        if validate_game_code(game_code).is_some() {
+            eprintln!("{FUNCTION_NAME}: User {user_id} can't join game with game code because they submitted an invalid game code");
+            return Err(TichuError::InvalidGameCode);
         }
 
         // Verify that user isn't already associated with another game first
-        let mut write_connections = connections.write().await;
-        let connection = if let Some(connection_data) = write_connections.get_mut(&user_id) {
+        let read_connections = connections.read().await;
+        let connection = if let Some(connection_data) = read_connections.get(user_id) {
             connection_data
         } else {
             eprintln!("Could not find connection_data for user {}", user_id);
-            return;
+            return Err(TichuError::ConnectionNotFound);
         };
 
         // user already associated with a game, no action needed
@@ -41,8 +58,9 @@ pub async fn join_game_with_game_code(
                 user_id,
                 game_id
             );
-            return;
+            return Err(TichuError::AlreadyInGame);
         }
+        drop(read_connections);
 
         // get game_id from game_code
         let read_game_codes = game_codes.read().await;
@@ -52,44 +70,75 @@ pub async fn join_game_with_game_code(
                 eprintln!(
                     "{FUNCTION_NAME}: User supplied incorrect game_code: ignoring request to join"
                 );
-                return;
+                return Err(TichuError::GameCodeNotFound);
             }
             Some(game_id) => game_id.clone(),
         };
         drop(read_game_codes);
 
-        let mut write_games = games.write().await;
-        let game_state_clone = write_games
-            .get_mut(&cloned_gamed_id)
-            .expect(USER_ID_NOT_IN_MAP);
+        let command = GameCommand::Request {
+            user_id: user_id.to_string(),
+            msg: join_game_with_game_code_data.clone(),
+        };
+        if !send_game_command(&cloned_gamed_id, command, games).await {
+            eprintln!("{FUNCTION_NAME}: User {user_id} can't join game {cloned_gamed_id}, because it could not be found in the Games HashMap");
+            return Err(TichuError::GameNotFound);
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds a user to the game, from the task running it
+pub async fn add_joining_user(
+    user_id: &str,
+    join_game_with_game_code_data: CTSMsg,
+    game_state: &mut PrivateGameState,
+    connections: &Connections,
+) -> Result<(), TichuError> {
+    if let CTSMsg::JoinGameWithGameCode { display_name, .. } = join_game_with_game_code_data {
+        // the user may have joined another game while this request was on its way
+        let mut write_connections = connections.write().await;
+        let connection = if let Some(connection_data) = write_connections.get_mut(user_id) {
+            connection_data
+        } else {
+            eprintln!("Could not find connection_data for user {}", user_id);
+            return Err(TichuError::ConnectionNotFound);
+        };
+        if let Some(game_id) = &connection.game_id {
+            eprintln!(
+                "{FUNCTION_NAME}: Can't Join game with game code for user {}: user is already associated with a game: {}",
+                user_id,
+                game_id
+            );
+            return Err(TichuError::AlreadyInGame);
+        }
 
         // Verify that there are not already 4 users in the game
-        if game_state_clone.participants.len() == 4 {
+        if game_state.participants.len() == 4 {
             eprintln!(
                 "{FUNCTION_NAME}: There are already 4 users in game {}: ignoring request to join from user {}",
-                cloned_gamed_id, user_id
+                game_state.game_id, user_id
             );
-            return;
+            return Err(TichuError::GameFull);
         }
 
-        let new_game_state = match game_state_clone.add_user(user_id.clone(), display_name) {
+        let new_game_state = match game_state.add_user(user_id.to_string(), display_name) {
             Ok(new_game_state) => new_game_state,
-            Err(err) => return eprintln!("{}", err),
+            Err(err) => {
+                eprintln!("{FUNCTION_NAME}: Rejected request from user {user_id}: {err}");
+                return Err(err);
+            }
         };
 
         // save new game state
-        write_games.insert(cloned_gamed_id.clone(), new_game_state.clone());
+        *game_state = new_game_state;
 
         // associate game_id to new user
-        let connection = write_connections
-            .get_mut(&user_id)
-            .expect(USER_ID_NOT_IN_MAP);
-        let _ = connection.game_id.insert(new_game_state.game_id.clone());
-
-        // these must be dropped, or else deadlock occurs, because send_ws_message_to_all_participants
-        // waits for read access, which is never given while these variables have
-        // write access--which only get dropped once message is sent, and so on
-        drop(write_games);
+        let _ = connection.game_id.insert(game_state.game_id.clone());
+
+        // must be dropped, or else deadlock occurs, because send_ws_message::to_group
+        // waits for read access to Connections
         drop(write_connections);
 
         eprintln!("{FUNCTION_NAME}: User {} successfully joined game", user_id);
@@ -97,34 +146,25 @@ pub async fn join_game_with_game_code(
         // Send updates to user
         // User Joined event
         send_ws_message::to_group(
-            &cloned_gamed_id,
-            STCMsg::UserJoined(user_id.clone()),
-            &connections,
-            &games,
-            &game_codes,
+            game_state,
+            STCMsg::UserJoined(user_id.to_string()),
+            connections,
         )
         .await;
 
         // Game Stage Changed event
-        if let PrivateGameStage::Teams(_) = new_game_state.stage {
+        if let PrivateGameStage::Teams(_) = game_state.stage {
             send_ws_message::to_group(
-                &cloned_gamed_id,
-                STCMsg::GameStageChanged(new_game_state.stage.clone().into()),
-                &connections,
-                &games,
-                &game_codes,
+                game_state,
+                STCMsg::GameStageChanged(game_state.stage.clone().into()),
+                connections,
             )
             .await;
         }
 
         // Game State
-        send_ws_message::game_state_to_group(
-            &cloned_gamed_id,
-            &new_game_state,
-            &connections,
-            &games,
-            &game_codes,
-        )
-        .await;
+        send_ws_message::game_state_to_group(game_state, connections).await;
     }
+
+    Ok(())
 }
@@ -1,47 +1,28 @@
 use crate::routes::ws::send_ws_message;
-use crate::ConnectionData;
 use crate::{Connections, GameCodes, Games};
-use common::{PrivateGameStage, STCMsg};
+use common::{PrivateGameStage, PrivateGameState, STCMsg, TichuError};
 
 const FUNCTION_NAME: &str = "leave_game";
 
 pub async fn leave_game(
     user_id: &str,
+    game_state: &mut PrivateGameState,
     connections: &Connections,
     games: &Games,
     game_codes: &GameCodes,
-) {
+) -> Result<(), TichuError> {
     let mut write_connections = connections.write().await;
-    let mut write_games = games.write().await;
-    let mut write_game_codes = game_codes.write().await;
-
-    // extract game_id
-    let game_id_clone = if let Some(ConnectionData {
-        game_id: Some(game_id),
-        ..
-    }) = &write_connections.get(user_id)
-    {
-        game_id.clone()
-    } else {
-        eprintln!("{FUNCTION_NAME}: User {user_id} can't leave game because their user_id could not be found in the Connections HashMap");
-        return;
-    };
-
-    // extract all needed game state
-    let game_state_clone = if let Some(game_state) = write_games.get(&game_id_clone) {
-        game_state.clone()
-    } else {
-        eprintln!("{FUNCTION_NAME}: User {user_id} can't leave game because game_state could not be found in Games HashMap");
-        return;
-    };
 
+    let game_state_clone = game_state.clone();
+    let game_id_clone = game_state_clone.game_id.clone();
     let game_code_clone = game_state_clone.game_code.clone();
     let participants_clone = game_state_clone.participants.clone();
 
     // check if any other participants are still in room
     let mut any_other_user_is_still_in_game = false;
+    // bots don't keep a game alive on their own
     for participant in participants_clone.iter() {
-        if participant.user_id != user_id {
+        if participant.user_id != user_id && !participant.is_bot() {
             // should always be connected in lobby (since users
             // are just removed from the lobby when they disconnect)
             // but it doesn't hurt to check
@@ -73,22 +54,31 @@ pub async fn leave_game(
                 match game_state_clone.remove_user(user_id) {
                     Ok(updated_game_state) => match updated_game_state.reassign_owner() {
                         Ok(updated_game_state) => updated_game_state,
-                        Err(err) => return eprintln!("{}", err),
+                        Err(err) => {
+                            eprintln!(
+                                "{FUNCTION_NAME}: Rejected request from user {user_id}: {err}"
+                            );
+                            return Err(err);
+                        }
                     },
-                    Err(err) => return eprintln!("{}", err),
+                    Err(err) => {
+                        eprintln!("{FUNCTION_NAME}: Rejected request from user {user_id}: {err}");
+                        return Err(err);
+                    }
                 }
             } else {
                 // if not the owner, just remove from state
                 match game_state_clone.remove_user(user_id) {
                     Ok(updated_game_state) => updated_game_state,
-                    Err(err) => return eprintln!("{}", err),
+                    Err(err) => {
+                        eprintln!("{FUNCTION_NAME}: Rejected request from user {user_id}: {err}");
+                        return Err(err);
+                    }
                 }
             };
 
             // update game state
-            if let Some(game_state) = write_games.get_mut(&game_id_clone) {
-                *game_state = new_game_state.clone();
-            }
+            *game_state = new_game_state;
 
             // disassociate user_id with game
             if let Some(connection_data) = write_connections.get_mut(user_id) {
@@ -96,42 +86,29 @@ pub async fn leave_game(
             }
 
             drop(write_connections);
-            drop(write_games);
-            drop(write_game_codes);
 
             eprintln!("{FUNCTION_NAME}: User {} successfully left game. Other users are still in the game so persisting game state", user_id);
 
             // notify remaining participants that user left
             send_ws_message::to_group(
-                &game_id_clone,
+                game_state,
                 STCMsg::UserLeft(user_id.to_string()),
                 connections,
-                games,
-                game_codes,
             )
             .await;
 
             // notify remaining participants that new owner was chosen
             if owner_reassigned {
                 send_ws_message::to_group(
-                    &game_id_clone,
-                    STCMsg::OwnerReassigned(new_game_state.owner_id.clone()),
+                    game_state,
+                    STCMsg::OwnerReassigned(game_state.owner_id.clone()),
                     connections,
-                    games,
-                    game_codes,
                 )
                 .await;
             }
 
             // send updated game state to other participants
-            send_ws_message::game_state_to_group(
-                &game_id_clone,
-                &new_game_state,
-                connections,
-                games,
-                game_codes,
-            )
-            .await;
+            send_ws_message::game_state_to_group(game_state, connections).await;
 
             // send a None game state to current user
             send_ws_message::to_user(user_id, STCMsg::GameState(Box::new(None)), connections).await;
@@ -141,6 +118,7 @@ pub async fn leave_game(
                 "{FUNCTION_NAME}: User {} can't leave game since user is not in Lobby game stage",
                 user_id
             );
+            return Err(TichuError::WrongStage);
         }
     } else {
         // no other users left in game: delete game but keep user connection
@@ -149,19 +127,25 @@ pub async fn leave_game(
             user_id, game_id_clone
         );
 
-        write_games.remove(&game_id_clone);
-        write_game_codes.remove(&game_code_clone);
-
         // disassociate user with game_id
         if let Some(connection_data) = write_connections.get_mut(user_id) {
             connection_data.game_id = None;
         }
 
+        // remove any bots, which stops their tasks
+        for participant in participants_clone.iter().filter(|user| user.is_bot()) {
+            write_connections.remove(&participant.user_id);
+        }
+
         drop(write_connections);
-        drop(write_games);
-        drop(write_game_codes);
+
+        // deleting the game stops its task
+        games.write().await.remove(&game_id_clone);
+        game_codes.write().await.remove(&game_code_clone);
 
         // send a None game state to current user
         send_ws_message::to_user(user_id, STCMsg::GameState(Box::new(None)), connections).await;
     }
+
+    Ok(())
 }
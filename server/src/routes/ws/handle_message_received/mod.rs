@@ -1,7 +1,9 @@
-mod __admin_skip_to_play;
+mod add_bot;
+mod admin;
 mod call_grand_tichu;
 mod call_small_tichu;
 mod create_game;
+mod give_dragon;
 mod join_game_with_game_code;
 mod leave_game;
 mod move_to_team;
@@ -13,12 +15,15 @@ mod rename_team;
 mod start_grand_tichu;
 mod submit_trade;
 mod test;
+mod update_rules;
 
-use __admin_skip_to_play::__admin_skip_to_play;
+use add_bot::add_bot;
+use admin::admin;
 use call_grand_tichu::call_grand_tichu;
 use call_small_tichu::call_small_tichu;
 use create_game::create_game;
-use join_game_with_game_code::join_game_with_game_code;
+use give_dragon::give_dragon;
+use join_game_with_game_code::{add_joining_user, join_game_with_game_code};
 use leave_game::leave_game;
 use move_to_team::move_to_team;
 use pass::pass;
@@ -29,21 +34,31 @@ use rename_team::rename_team;
 use start_grand_tichu::start_grand_tichu;
 use submit_trade::submit_trade;
 use test::test;
+use update_rules::update_rules;
 
-use super::send_ws_message;
-use crate::{Connections, GameCodes, Games};
-use common::CTSMsg;
+use super::{
+    game_task::{send_game_command, GameCommand},
+    send_ws_message,
+};
+use crate::{Connections, GameCodes, Games, SharedGameStore};
+use common::{CTSMsg, CTSMsgKind, PrivateGameStage, PrivateGameState, STCMsg, TichuError};
 use warp::ws::Message;
 
+/// Decodes a websocket message from a user and carries it out.
+///
+/// Requests that concern a game are passed on to the task running that game, so this never
+/// waits on any game's logic.
 pub async fn handle_message_received(
     user_id: String,
     msg: Message,
     connections: Connections,
     games: Games,
     game_codes: GameCodes,
+    game_store: SharedGameStore,
 ) {
     if !msg.is_binary() {
         eprintln!("Text websocket message received: {:?}", &msg);
+        reject_action(&user_id, None, TichuError::InvalidMessage, &connections).await;
         return;
     }
 
@@ -54,30 +69,126 @@ pub async fn handle_message_received(
             "Could not deserialize received message from user {}",
             user_id
         );
+        reject_action(&user_id, None, TichuError::InvalidMessage, &connections).await;
         return;
     };
 
-    match msg {
+    let request = CTSMsgKind::from(&msg);
+    let result = match msg {
         CTSMsg::Test(_) => {
             test(&user_id, &connections).await;
+            Ok(())
         }
         CTSMsg::Ping => {
             ping(&user_id, &connections).await;
+            Ok(())
         }
         CTSMsg::Pong => {
             pong(&user_id, &connections).await;
+            Ok(())
         }
         CTSMsg::CreateGame { .. } => {
-            create_game(msg, connections, &games, &game_codes).await;
+            create_game(
+                &user_id,
+                msg,
+                &connections,
+                &games,
+                &game_codes,
+                &game_store,
+            )
+            .await
+        }
+        CTSMsg::JoinGameWithGameCode { .. } => {
+            join_game_with_game_code(&user_id, msg, &connections, &games, &game_codes).await
+        }
+        msg => send_to_users_game(&user_id, msg, &connections, &games).await,
+    };
+
+    // no locks are held by now, so it's safe to message the user
+    if let Err(reason) = result {
+        reject_action(&user_id, Some(request), reason, &connections).await;
+    }
+}
+
+/// Passes a request on to the task running the game that the user is in
+async fn send_to_users_game(
+    user_id: &str,
+    msg: CTSMsg,
+    connections: &Connections,
+    games: &Games,
+) -> Result<(), TichuError> {
+    let read_connections = connections.read().await;
+    let (game_id, is_admin) = match read_connections.get(user_id) {
+        Some(connection_data) => (connection_data.game_id.clone(), connection_data.is_admin),
+        None => {
+            eprintln!("User {user_id} can't send {msg:?}, because their user_id could not be found in the Connections HashMap");
+            return Err(TichuError::ConnectionNotFound);
+        }
+    };
+    drop(read_connections);
+
+    if matches!(msg, CTSMsg::Admin(_)) && !is_admin {
+        eprintln!("User {user_id} can't send {msg:?}, because they are not an admin");
+        return Err(TichuError::NotAdmin);
+    }
+
+    let game_id = match game_id {
+        Some(game_id) => game_id,
+        None => {
+            eprintln!(
+                "User {user_id} can't send {msg:?}, because they are not associated with a game"
+            );
+            return Err(TichuError::NotInGame);
+        }
+    };
+
+    let command = GameCommand::Request {
+        user_id: user_id.to_string(),
+        msg,
+    };
+    if send_game_command(&game_id, command, games).await {
+        Ok(())
+    } else {
+        eprintln!("User {user_id} can't send a request to game {game_id}, because it could not be found in the Games HashMap");
+        Err(TichuError::GameNotFound)
+    }
+}
+
+/// Carries out a request for the game, from the task running it
+pub async fn handle_game_request(
+    user_id: &str,
+    msg: CTSMsg,
+    game_state: &mut PrivateGameState,
+    connections: &Connections,
+    games: &Games,
+    game_codes: &GameCodes,
+    game_store: &SharedGameStore,
+) {
+    let request = CTSMsgKind::from(&msg);
+    let dragon_winner_user_id = get_dragon_winner_user_id(game_state);
+    let result = match msg {
+        // handled before reaching any game
+        CTSMsg::Test(_) | CTSMsg::Ping | CTSMsg::Pong | CTSMsg::CreateGame { .. } => {
+            Err(TichuError::InvalidMessage)
         }
         CTSMsg::JoinGameWithGameCode { .. } => {
-            join_game_with_game_code(msg, connections, games, game_codes).await;
+            add_joining_user(user_id, msg, game_state, connections).await
         }
-        CTSMsg::LeaveGame => {
-            leave_game(&user_id, &connections, &games, &game_codes).await;
+        CTSMsg::LeaveGame => leave_game(user_id, game_state, connections, games, game_codes).await,
+        CTSMsg::AddBot { difficulty } => {
+            add_bot(
+                difficulty,
+                user_id,
+                game_state,
+                connections,
+                games,
+                game_codes,
+                game_store,
+            )
+            .await
         }
         CTSMsg::MoveToTeam(team_option) => {
-            move_to_team(&team_option, &user_id, &connections, &games, &game_codes).await;
+            move_to_team(&team_option, user_id, game_state, connections).await
         }
         CTSMsg::RenameTeam {
             team_name: new_team_name,
@@ -86,53 +197,80 @@ pub async fn handle_message_received(
             rename_team(
                 &team_option,
                 new_team_name,
-                &user_id,
-                &connections,
-                &games,
-                &game_codes,
+                user_id,
+                game_state,
+                connections,
             )
-            .await;
-        }
-        CTSMsg::StartGrandTichu => {
-            start_grand_tichu(&user_id, &connections, &games, &game_codes).await;
+            .await
         }
+        CTSMsg::UpdateRules(rules) => update_rules(rules, user_id, game_state, connections).await,
+        CTSMsg::StartGrandTichu => start_grand_tichu(user_id, game_state, connections).await,
         CTSMsg::CallGrandTichu(call_grand_tichu_request) => {
-            call_grand_tichu(
-                &call_grand_tichu_request,
-                &user_id,
-                &connections,
-                &games,
-                &game_codes,
-            )
-            .await;
-        }
-        CTSMsg::CallSmallTichu => {
-            call_small_tichu(&user_id, &connections, &games, &game_codes).await;
+            call_grand_tichu(&call_grand_tichu_request, user_id, game_state, connections).await
         }
+        CTSMsg::CallSmallTichu => call_small_tichu(user_id, game_state, connections).await,
         CTSMsg::SubmitTrade(trade_array) => {
-            submit_trade(trade_array, &user_id, &connections, &games, &game_codes).await;
+            submit_trade(trade_array, user_id, game_state, connections).await
         }
         CTSMsg::PlayCards {
             cards,
             wished_for_card_value,
-            user_id_to_give_dragon_to,
         } => {
             play_cards(
-                &user_id,
+                user_id,
                 cards,
                 wished_for_card_value,
-                user_id_to_give_dragon_to,
-                &connections,
-                &games,
-                &game_codes,
+                game_state,
+                connections,
             )
-            .await;
+            .await
         }
-        CTSMsg::Pass => {
-            pass(&user_id, &connections, &games, &game_codes).await;
+        CTSMsg::GiveDragon {
+            user_id: recipient_user_id,
+        } => give_dragon(user_id, &recipient_user_id, game_state, connections).await,
+        CTSMsg::Pass => pass(user_id, game_state, connections).await,
+        CTSMsg::Admin(admin_command) => {
+            admin(&admin_command, user_id, game_state, connections).await
         }
-        CTSMsg::__AdminSkipToPlay => {
-            __admin_skip_to_play(&user_id, &connections, &games, &game_codes).await;
+    };
+
+    if let Err(reason) = result {
+        reject_action(user_id, Some(request), reason, connections).await;
+        return;
+    }
+
+    // whichever request won a trick with the Dragon, the winner must now choose who receives it
+    if let Some(new_dragon_winner_user_id) = get_dragon_winner_user_id(game_state) {
+        if dragon_winner_user_id.as_ref() != Some(&new_dragon_winner_user_id) {
+            send_ws_message::to_group(
+                game_state,
+                STCMsg::DragonTrickWon(new_dragon_winner_user_id),
+                connections,
+            )
+            .await;
         }
     }
 }
+
+/// Set while the winner of a Dragon trick is choosing an opponent to give it to
+fn get_dragon_winner_user_id(game_state: &PrivateGameState) -> Option<String> {
+    match &game_state.stage {
+        PrivateGameStage::Play(play_state) => play_state.dragon_winner_user_id.clone(),
+        _ => None,
+    }
+}
+
+/// Lets the user know that their request was not carried out, and why
+async fn reject_action(
+    user_id: &str,
+    request: Option<CTSMsgKind>,
+    reason: TichuError,
+    connections: &Connections,
+) {
+    send_ws_message::to_user(
+        user_id,
+        STCMsg::ActionRejected { request, reason },
+        connections,
+    )
+    .await;
+}
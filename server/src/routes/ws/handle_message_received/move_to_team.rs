@@ -1,37 +1,15 @@
-use crate::{
-    errors::{GAME_ID_NOT_IN_MAP, USER_ID_NOT_IN_MAP},
-    routes::ws::send_ws_message,
-    Connections, GameCodes, Games,
-};
-use common::{PrivateGameStage, STCMsg, TeamOption};
+use crate::{routes::ws::send_ws_message, Connections};
+use common::{PrivateGameStage, PrivateGameState, STCMsg, TeamOption, TichuError};
 
 const FUNCTION_NAME: &str = "move_to_team";
 
 pub async fn move_to_team(
     team_to_move_to: &TeamOption,
     user_id: &str,
+    game_state: &mut PrivateGameState,
     connections: &Connections,
-    games: &Games,
-    game_codes: &GameCodes,
-) {
-    let read_connections = connections.read().await;
-    let mut write_games = games.write().await;
-
-    let game_id_clone = read_connections
-        .get(user_id)
-        .expect(USER_ID_NOT_IN_MAP)
-        .game_id
-        .clone();
-    let game_id_clone = match game_id_clone {
-        // user is not associated with a game, do nothing
-        None => {
-            eprintln!("{FUNCTION_NAME}: User {user_id} can't move to team {:#?} because the user is not associated with a game", team_to_move_to);
-            return;
-        }
-        Some(game_id_clone) => game_id_clone,
-    };
-    let prev_game_state = write_games.get(&game_id_clone).expect(GAME_ID_NOT_IN_MAP);
-    match &prev_game_state.stage {
+) -> Result<(), TichuError> {
+    match &game_state.stage {
         PrivateGameStage::Teams(teams_state) => {
             let team = match team_to_move_to {
                 TeamOption::TeamA => &teams_state[0],
@@ -43,7 +21,7 @@ pub async fn move_to_team(
                     "{FUNCTION_NAME}: User {} can't move to to team {:?} because is already on team {:?}. Ignoring request",
                     user_id, team_to_move_to, team_to_move_to
                 );
-                return;
+                return Err(TichuError::AlreadyOnTeam);
             }
         }
         // current stage is not Teams, do nothing
@@ -52,7 +30,7 @@ pub async fn move_to_team(
                 "{FUNCTION_NAME}: User {} can't move to to team {:?} because current game stage is not Teams",
                 &user_id, &team_to_move_to
             );
-            return;
+            return Err(TichuError::WrongStage);
         }
     }
 
@@ -62,36 +40,24 @@ pub async fn move_to_team(
     );
 
     // update game state
-    let new_game_state = match prev_game_state.move_to_team(team_to_move_to, user_id) {
+    let new_game_state = match game_state.move_to_team(team_to_move_to, user_id) {
         Ok(updated_game_state) => updated_game_state,
-        Err(err) => return eprintln!("{}", err),
+        Err(err) => {
+            eprintln!("{FUNCTION_NAME}: Rejected request from user {user_id}: {err}");
+            return Err(err);
+        }
     };
-    *write_games
-        .get_mut(&game_id_clone)
-        .expect(GAME_ID_NOT_IN_MAP) = new_game_state.clone();
-    drop(write_games);
+    *game_state = new_game_state;
 
     // send moved teams event
     let moved_teams_event = match *team_to_move_to {
         TeamOption::TeamA => STCMsg::UserMovedToTeamA(user_id.to_string()),
         TeamOption::TeamB => STCMsg::UserMovedToTeamB(user_id.to_string()),
     };
-    send_ws_message::to_group(
-        &game_id_clone,
-        moved_teams_event,
-        connections,
-        games,
-        game_codes,
-    )
-    .await;
+    send_ws_message::to_group(game_state, moved_teams_event, connections).await;
 
     // send updated game state
-    send_ws_message::game_state_to_group(
-        &game_id_clone,
-        &new_game_state,
-        connections,
-        games,
-        game_codes,
-    )
-    .await;
+    send_ws_message::game_state_to_group(game_state, connections).await;
+
+    Ok(())
 }
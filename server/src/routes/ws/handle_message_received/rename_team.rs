@@ -1,10 +1,6 @@
-use common::{PrivateGameStage, STCMsg, TeamOption};
+use common::{PrivateGameStage, PrivateGameState, STCMsg, TeamOption, TichuError};
 
-use crate::{
-    errors::{GAME_ID_NOT_IN_MAP, USER_ID_NOT_IN_MAP},
-    routes::ws::send_ws_message,
-    Connections, GameCodes, Games,
-};
+use crate::{routes::ws::send_ws_message, Connections};
 
 const FUNCTION_NAME: &str = "rename_team";
 
@@ -12,31 +8,10 @@ pub async fn rename_team(
     team_to_rename: &TeamOption,
     new_team_name: String,
     user_id: &str,
+    game_state: &mut PrivateGameState,
     connections: &Connections,
-    games: &Games,
-    game_codes: &GameCodes,
-) {
-    let read_connections = connections.read().await;
-    let mut write_games = games.write().await;
-
-    let game_id_clone = read_connections
-        .get(user_id)
-        .expect(USER_ID_NOT_IN_MAP)
-        .game_id
-        .clone();
-    let game_id_clone = match game_id_clone {
-        // user is not associated with a game, do nothing
-        None => {
-            eprintln!(
-                "{FUNCTION_NAME}: User {} is not associated with a game. Ignoring request to rename {:?}",
-                &user_id, &team_to_rename,
-            );
-            return;
-        }
-        Some(game_id_clone) => game_id_clone,
-    };
-    let prev_game_state = write_games.get(&game_id_clone).expect(GAME_ID_NOT_IN_MAP);
-    match &prev_game_state.stage {
+) -> Result<(), TichuError> {
+    match &game_state.stage {
         PrivateGameStage::Teams(teams_state) => {
             // if user is not on team they want to rename, ignore
             let opposite_team = match team_to_rename {
@@ -49,7 +24,7 @@ pub async fn rename_team(
                     &user_id,
                     &team_to_rename,
                 );
-                return;
+                return Err(TichuError::NotOnTeam);
             }
         }
         // current stage is not Teams, do nothing
@@ -58,20 +33,19 @@ pub async fn rename_team(
                 "{FUNCTION_NAME}: Current stage is not Teams. Ignoring request from user {} to rename team {:?}",
                 &user_id, &team_to_rename,
             );
-            return;
+            return Err(TichuError::WrongStage);
         }
     }
 
     // update game state
-    let new_game_state = match prev_game_state.rename_team(team_to_rename, user_id, &new_team_name)
-    {
+    let new_game_state = match game_state.rename_team(team_to_rename, user_id, &new_team_name) {
         Ok(updated_game_state) => updated_game_state,
-        Err(err) => return eprintln!("{}", err),
+        Err(err) => {
+            eprintln!("{FUNCTION_NAME}: Rejected request from user {user_id}: {err}");
+            return Err(err);
+        }
     };
-    *write_games
-        .get_mut(&game_id_clone)
-        .expect(GAME_ID_NOT_IN_MAP) = new_game_state.clone();
-    drop(write_games);
+    *game_state = new_game_state;
 
     eprintln!(
         "{FUNCTION_NAME}: User {} successfully renamed team {:?} to {}",
@@ -83,22 +57,10 @@ pub async fn rename_team(
         TeamOption::TeamB => STCMsg::TeamBRenamed(new_team_name),
     };
     // send team rename event
-    send_ws_message::to_group(
-        &game_id_clone,
-        team_renamed_event,
-        connections,
-        games,
-        game_codes,
-    )
-    .await;
+    send_ws_message::to_group(game_state, team_renamed_event, connections).await;
 
     // send updated game state
-    send_ws_message::game_state_to_group(
-        &game_id_clone,
-        &new_game_state,
-        connections,
-        games,
-        game_codes,
-    )
-    .await;
+    send_ws_message::game_state_to_group(game_state, connections).await;
+
+    Ok(())
 }
@@ -1,43 +1,19 @@
-use crate::{
-    errors::{GAME_ID_NOT_IN_MAP, USER_ID_NOT_IN_MAP},
-    routes::ws::send_ws_message,
-    Connections, GameCodes, Games,
-};
-use common::{PrivateGameStage, STCMsg};
+use crate::{routes::ws::send_ws_message, Connections};
+use common::{PrivateGameStage, PrivateGameState, STCMsg, TichuError};
 
 const FUNCTION_NAME: &str = "start_grand_tichu";
 
 pub async fn start_grand_tichu(
     user_id: &str,
+    game_state: &mut PrivateGameState,
     connections: &Connections,
-    games: &Games,
-    game_codes: &GameCodes,
-) {
-    // requesting user must be owner
-    let read_connections = connections.read().await;
-    let mut write_games = games.write().await;
-
-    let user = read_connections.get(user_id).expect(USER_ID_NOT_IN_MAP);
-    let game_id_clone = user.game_id.clone();
-
-    let game_id_clone = match &game_id_clone {
-        None => {
-            eprintln!("{FUNCTION_NAME}: User {} can't start game since they are not associated with any game. Ignoring request", user_id);
-            return;
-        }
-        Some(game_id_clone) => game_id_clone,
-    };
-
-    let game_state = write_games
-        .get_mut(game_id_clone)
-        .expect(GAME_ID_NOT_IN_MAP);
-
+) -> Result<(), TichuError> {
     // game stage must be Teams
     let teams_state = match &game_state.stage {
         PrivateGameStage::Teams(teams_state) => teams_state,
         _ => {
             eprintln!("{FUNCTION_NAME}: User {} can't start game because current game stage is not teams. Ignoring request", user_id);
-            return;
+            return Err(TichuError::WrongStage);
         }
     };
 
@@ -47,17 +23,18 @@ pub async fn start_grand_tichu(
             "{FUNCTION_NAME}: User {} can't start game because teams are not even 2v2. Ignoring request",
             user_id
         );
-        return;
+        return Err(TichuError::TeamsNotReady);
     }
 
     // update game state
     let new_game_state = match game_state.start_grand_tichu(user_id) {
         Ok(updated_game_state) => updated_game_state,
-        Err(err) => return eprintln!("{}", err),
+        Err(err) => {
+            eprintln!("{FUNCTION_NAME}: Rejected request from user {user_id}: {err}");
+            return Err(err);
+        }
     };
-    *game_state = new_game_state.clone();
-
-    drop(write_games);
+    *game_state = new_game_state;
 
     eprintln!(
         "{FUNCTION_NAME}: User {} successfully started Grand Tichu",
@@ -66,31 +43,17 @@ pub async fn start_grand_tichu(
 
     // send PrivateGameStage change event to Grand Tichu
     send_ws_message::to_group(
-        game_id_clone,
-        STCMsg::GameStageChanged(new_game_state.stage.clone().into()),
+        game_state,
+        STCMsg::GameStageChanged(game_state.stage.clone().into()),
         connections,
-        games,
-        game_codes,
     )
     .await;
 
     // send FirstCardsDealt event
-    send_ws_message::to_group(
-        game_id_clone,
-        STCMsg::FirstCardsDealt,
-        connections,
-        games,
-        game_codes,
-    )
-    .await;
+    send_ws_message::to_group(game_state, STCMsg::FirstCardsDealt, connections).await;
 
     // send updated game state
-    send_ws_message::game_state_to_group(
-        game_id_clone,
-        &new_game_state,
-        connections,
-        games,
-        game_codes,
-    )
-    .await;
+    send_ws_message::game_state_to_group(game_state, connections).await;
+
+    Ok(())
 }
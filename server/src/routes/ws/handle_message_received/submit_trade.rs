@@ -1,42 +1,14 @@
-use crate::{routes::ws::send_ws_message, Connections, GameCodes, Games};
-use common::{PrivateGameStage, STCMsg, SubmitTrade};
+use crate::{routes::ws::send_ws_message, Connections};
+use common::{PrivateGameStage, PrivateGameState, STCMsg, SubmitTrade, TichuError};
 
 const FUNCTION_NAME: &str = "submit_trade";
 
 pub async fn submit_trade(
     trade_array: SubmitTrade,
     user_id: &str,
+    game_state: &mut PrivateGameState,
     connections: &Connections,
-    games: &Games,
-    game_codes: &GameCodes,
-) {
-    let read_connections = connections.read().await;
-    let mut write_games = games.write().await;
-
-    let user = match read_connections.get(user_id) {
-        Some(user) => user,
-        None => {
-            eprintln!("{FUNCTION_NAME}: User {} can't submit trade, because their user_id could not be found in the Connections HashMap", user_id);
-            return;
-        }
-    };
-
-    let game_id = match user.game_id.clone() {
-        Some(game_id) => game_id,
-        None => {
-            eprintln!("{FUNCTION_NAME}: User {} can't submit trade, because they are not associated with a game_id", user_id);
-            return;
-        }
-    };
-
-    let game_state = match write_games.get_mut(&game_id) {
-        Some(game_state) => game_state,
-        None => {
-            eprintln!("{FUNCTION_NAME}: User {} can't submit trade, because the game they are associated with could not be found in the Games HashMap", user_id);
-            return;
-        }
-    };
-
+) -> Result<(), TichuError> {
     // must be Trade stage
     if let PrivateGameStage::Trade(trade_stage) = &game_state.stage {
         let i = game_state
@@ -48,20 +20,20 @@ pub async fn submit_trade(
             &mut game_state.participants[i]
         } else {
             eprintln!("{FUNCTION_NAME}: couldn't accept traded submitted by user {user_id} because user could not be found in participants");
-            return;
+            return Err(TichuError::UserNotFound);
         };
 
         for trade in &trade_array {
             // User must actually have those cards in their hand
             if !user.hand.contains(&trade.card) {
                 eprintln!("{FUNCTION_NAME}: Couldn't accept traded submitted by user {} because user does {:?}, which they are trying to trade", user_id, trade.card);
-                return;
+                return Err(TichuError::CardNotInHand(trade.card.clone()));
             }
 
             // Trade must not be to self
             if trade.to_user_id == user_id {
                 eprintln!("{FUNCTION_NAME}: Couldn't accept traded submitted by user {} because user is trying to trade to self", user_id);
-                return;
+                return Err(TichuError::TradeToSelf);
             }
 
             // Trade must be to a valid participant who is on a team
@@ -75,7 +47,7 @@ pub async fn submit_trade(
             }
             if !recipient_found_in_teams {
                 eprintln!("{FUNCTION_NAME}: Couldn't accept traded submitted by user {} because the person the user is trying to trade to was not found in the teams", user_id);
-                return;
+                return Err(TichuError::TradeRecipientNotFound);
             }
         }
     } else {
@@ -83,17 +55,18 @@ pub async fn submit_trade(
             "Couldn't accept traded submitted by user {} because Game Stage is not Trade",
             user_id
         );
-        return;
+        return Err(TichuError::WrongStage);
     }
 
     // update game state
     let new_game_state = match game_state.submit_trade(user_id, &trade_array) {
         Ok(updated_game_state) => updated_game_state,
-        Err(err) => return eprintln!("{}", err),
+        Err(err) => {
+            eprintln!("{FUNCTION_NAME}: Rejected request from user {user_id}: {err}");
+            return Err(err);
+        }
     };
-    *game_state = new_game_state.clone();
-
-    drop(write_games);
+    *game_state = new_game_state;
 
     eprintln!(
         "{FUNCTION_NAME}: User {} successfully submitted trade",
@@ -102,30 +75,28 @@ pub async fn submit_trade(
 
     // send Trade submitted
     send_ws_message::to_group(
-        &game_id,
+        game_state,
         STCMsg::TradeSubmitted(user_id.to_string()),
         connections,
-        games,
-        game_codes,
     )
     .await;
 
     // if game stage changed to Play, send GameStageChanged event
-    if let PrivateGameStage::Play(_) = &new_game_state.stage {
+    if let PrivateGameStage::Play(_) = &game_state.stage {
         eprintln!(
-            "{FUNCTION_NAME}: Final trade submitted. Game state moved to Play for game {game_id}"
+            "{FUNCTION_NAME}: Final trade submitted. Game state moved to Play for game {}",
+            game_state.game_id
         );
         send_ws_message::to_group(
-            &game_id,
-            STCMsg::GameStageChanged(new_game_state.stage.clone().into()),
+            game_state,
+            STCMsg::GameStageChanged(game_state.stage.clone().into()),
             connections,
-            games,
-            game_codes,
         )
         .await;
     }
 
     // send updated game state
-    send_ws_message::game_state_to_group(&game_id, &new_game_state, connections, games, game_codes)
-        .await;
+    send_ws_message::game_state_to_group(game_state, connections).await;
+
+    Ok(())
 }
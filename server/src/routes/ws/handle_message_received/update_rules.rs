@@ -0,0 +1,32 @@
+use common::{GameRules, PrivateGameState, STCMsg, TichuError};
+
+use crate::{routes::ws::send_ws_message, Connections};
+
+const FUNCTION_NAME: &str = "update_rules";
+
+pub async fn update_rules(
+    rules: GameRules,
+    user_id: &str,
+    game_state: &mut PrivateGameState,
+    connections: &Connections,
+) -> Result<(), TichuError> {
+    // the engine checks that the user is the owner, the stage, and the rules themselves
+    let new_game_state = match game_state.update_rules(user_id, &rules) {
+        Ok(updated_game_state) => updated_game_state,
+        Err(err) => {
+            eprintln!("{FUNCTION_NAME}: Rejected request from user {user_id}: {err}");
+            return Err(err);
+        }
+    };
+    *game_state = new_game_state;
+
+    eprintln!("{FUNCTION_NAME}: User {user_id} successfully updated the rules to {rules:?}");
+
+    // Rules Updated event
+    send_ws_message::to_group(game_state, STCMsg::RulesUpdated(rules), connections).await;
+
+    // Game State
+    send_ws_message::game_state_to_group(game_state, connections).await;
+
+    Ok(())
+}
@@ -1,10 +1,14 @@
+mod bot;
 mod cleanup_state_after_disconnect;
+mod game_task;
 mod handle_message_received;
 pub mod send_ws_message;
-use crate::{ConnectionData, Connections, GameCodes, Games};
+use crate::{ConnectionData, Connections, GameCodes, Games, SharedGameStore};
 pub use cleanup_state_after_disconnect::cleanup_state_after_disconnect;
-use common::{STCMsg, NO_USER_ID};
+use common::{get_random_seed, STCMsg};
 use futures::{SinkExt, StreamExt};
+use game_task::send_game_command;
+pub use game_task::{restore_games, GameCommand};
 pub use handle_message_received::handle_message_received;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
@@ -12,24 +16,79 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 use uuid::Uuid;
 use warp::ws::{Message, WebSocket};
 
+/// Secret for reconnecting as a user: 128 bits from the operating system's secure random number generator
+fn new_session_token() -> String {
+    format!("{:016x}{:016x}", get_random_seed(), get_random_seed())
+}
+
+/// Compares secrets without stopping at the first differing byte,
+/// so that response times don't reveal how much of a guessed token was right
+pub fn tokens_match(token: &str, expected_token: &str) -> bool {
+    token.len() == expected_token.len()
+        && token
+            .bytes()
+            .zip(expected_token.bytes())
+            .fold(0, |difference, (byte, expected_byte)| {
+                difference | (byte ^ expected_byte)
+            })
+            == 0
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_ws_upgrade(
     ws: WebSocket,
     user_id: String,
+    session_token: Option<String>,
+    is_admin: bool,
     connections: Connections,
     games: Games,
     game_codes: GameCodes,
+    game_store: SharedGameStore,
 ) {
     eprintln!("User {user_id}: Connected");
+    if is_admin {
+        eprintln!("User {user_id}: Connected as an admin");
+    }
 
     let (mut user_ws_tx, mut user_ws_rx) = ws.split();
     // use a channel to send messages to our websocket sink (sender)
     let (tx, rx) = mpsc::unbounded_channel();
     let mut rx = UnboundedReceiverStream::new(rx);
 
+    eprintln!("User {user_id}: Waiting for RWLock to Connections");
+    let mut write_connections = connections.write().await;
+    eprintln!("User {user_id}: Connections RWLock received");
+
+    // `user_id`s are public, so only clients that know the user's session token can reconnect as them
+    let returning_connection_data =
+        write_connections.get(&user_id).filter(|connection_data| {
+            match (&session_token, &connection_data.session_token) {
+                (Some(session_token), Some(expected_session_token)) => {
+                    tokens_match(session_token, expected_session_token)
+                }
+                _ => false,
+            }
+        });
+    let is_a_returning_user = returning_connection_data.is_some();
+    let game_id =
+        returning_connection_data.and_then(|connection_data| connection_data.game_id.clone());
+
+    // everyone else starts over with a new user_id
+    let (user_id, session_token) = match session_token {
+        Some(session_token) if is_a_returning_user => {
+            eprintln!("User {user_id} is a returning user");
+            (user_id, session_token)
+        }
+        _ => {
+            let new_user_id = Uuid::new_v4().to_string();
+            eprintln!("User {user_id}: New user_id: {new_user_id}");
+            (new_user_id, new_session_token())
+        }
+    };
+
     let user_id_clone = user_id.clone();
     let users_clone = Arc::clone(&connections);
     let games_clone = Arc::clone(&games);
-    let game_codes_clone = Arc::clone(&game_codes);
 
     // send any incoming stream messages to client
     tokio::task::spawn(async move {
@@ -40,13 +99,7 @@ pub async fn handle_ws_upgrade(
                 if let Err(e) = result {
                     eprintln!("Error closing websocket {:#?}", e);
                 }
-                cleanup_state_after_disconnect(
-                    &user_id_clone,
-                    &users_clone,
-                    &games_clone,
-                    &game_codes_clone,
-                )
-                .await;
+                cleanup_state_after_disconnect(&user_id_clone, &users_clone, &games_clone).await;
             }
             // take WS from queue and send to client
             else {
@@ -62,40 +115,11 @@ pub async fn handle_ws_upgrade(
         }
     });
 
-    eprintln!("User {user_id}: Waiting for RWLock to Connections");
-    let mut write_connections = connections.write().await;
-    eprintln!("User {user_id}: Connections RWLock received");
-
-    let is_a_returning_user = write_connections.contains_key(&user_id);
-    let mut new_user_id_assigned = false;
-    let mut game_id = None;
-
-    // only reassign user_ids if the user hasn't claimed one yet
-    // they can use whichever one they provide as long as it is unique
-    let user_id = if user_id == NO_USER_ID {
-        new_user_id_assigned = true;
-        let new_user_id = Uuid::new_v4().to_string();
-        eprintln!("User {user_id}: New user_id: {new_user_id}");
-        new_user_id
-    } else {
-        user_id
-    };
-
-    // get associated game_id from returning users
-    if is_a_returning_user {
-        eprintln!("User {user_id} is a returning user");
-
-        if let Some(connection_data) = write_connections.get(&user_id) {
-            game_id = connection_data.game_id.clone();
-        } else {
-            eprintln!("User {user_id}: error: Returning user should be in write_connections");
-            return;
-        }
-    }
-
     let ws = ConnectionData {
         user_id: user_id.clone(),
         game_id: game_id.clone(),
+        session_token: Some(session_token.clone()),
+        is_admin,
         tx,
         is_alive: Arc::new(RwLock::new(true)),
         connected: true,
@@ -106,10 +130,13 @@ pub async fn handle_ws_upgrade(
     drop(write_connections);
 
     // must be saved under new user_id before sending message
-    if new_user_id_assigned {
+    if !is_a_returning_user {
         send_ws_message::to_user(
             &user_id,
-            STCMsg::UserIdAssigned(user_id.clone()),
+            STCMsg::UserIdAssigned {
+                user_id: user_id.clone(),
+                session_token,
+            },
             &connections,
         )
         .await;
@@ -118,29 +145,15 @@ pub async fn handle_ws_upgrade(
     // this user is already saved in Connections
     if is_a_returning_user {
         if let Some(game_id) = game_id {
-            // notify other participants (if any) that the user reconnected
-            send_ws_message::to_group(
-                &game_id,
-                STCMsg::UserReconnected(user_id.to_string()),
-                &connections,
-                &games,
-                &game_codes,
-            )
-            .await;
-
-            // if user is associated with a game_id, send the new participant a state update
-            let read_games = games.read().await;
-            let game_state = read_games
-                .get(&game_id)
-                .and_then(|game_state| game_state.to_public_game_state(&user_id).ok());
-            drop(read_games);
-
-            send_ws_message::to_user(
-                &user_id,
-                STCMsg::GameState(Box::new(game_state)),
-                &connections,
-            )
-            .await;
+            // the game's task notifies the other participants and sends the user a state update
+            let command = GameCommand::UserReconnected {
+                user_id: user_id.clone(),
+            };
+            if !send_game_command(&game_id, command, &games).await {
+                // the game was deleted while the user was away
+                send_ws_message::to_user(&user_id, STCMsg::GameState(Box::new(None)), &connections)
+                    .await;
+            }
         }
     } else {
         // send them a None state update to clear any lingering local state
@@ -162,10 +175,11 @@ pub async fn handle_ws_upgrade(
             Arc::clone(&connections),
             Arc::clone(&games),
             Arc::clone(&game_codes),
+            Arc::clone(&game_store),
         )
         .await;
     }
 
     // handle if user disconnects
-    cleanup_state_after_disconnect(&user_id, &connections, &games, &game_codes).await;
+    cleanup_state_after_disconnect(&user_id, &connections, &games).await;
 }
@@ -1,26 +1,12 @@
-use crate::{Connections, GameCodes, Games};
+use crate::Connections;
 use common::{PrivateGameState, STCMsg};
 use warp::ws::Message;
 
 /// Ensures that each user receives a version of the state that only THEY are allowed to see.
 /// I.e. each user can see everything in the state except for the other user's cards, etc.
-pub async fn game_state_to_group(
-    game_id: &str,
-    private_game_state: &PrivateGameState,
-    connections: &Connections,
-    games: &Games,
-    _: &GameCodes,
-) {
-    let read_games = games.read().await;
-    let game = if let Some(game_state) = read_games.get(game_id) {
-        game_state.clone()
-    } else {
-        eprintln!("game_id {} not found in read_games", game_id);
-        return;
-    };
-    drop(read_games);
-
-    for participant in game.participants.iter() {
+pub async fn game_state_to_group(private_game_state: &PrivateGameState, connections: &Connections) {
+    let read_connections = connections.read().await;
+    for participant in private_game_state.participants.iter() {
         // format state for this user
         let public_game_state = private_game_state.to_public_game_state(&participant.user_id);
 
@@ -35,7 +21,6 @@ pub async fn game_state_to_group(
 
         let msg = Message::binary(msg);
 
-        let read_connections = connections.read().await;
         let connection_data = match read_connections.get(&participant.user_id) {
             Some(participant_connection) => participant_connection,
             None => {
@@ -51,13 +36,11 @@ pub async fn game_state_to_group(
     }
 }
 
-/// Sends any server-to-client websocket message to all participants in the game represented by the given game_id.
+/// Sends any server-to-client websocket message to all participants in the given game.
 pub async fn to_group(
-    game_id: &str,
+    private_game_state: &PrivateGameState,
     stc_msg: STCMsg,
     connections: &Connections,
-    games: &Games,
-    _: &GameCodes,
 ) {
     let msg = if let Ok(msg) = bincode::serialize(&stc_msg) {
         msg
@@ -67,16 +50,8 @@ pub async fn to_group(
     };
 
     let msg = Message::binary(msg);
-    let read_games = games.read().await;
-    let game_state = if let Some(game_state) = read_games.get(game_id) {
-        game_state
-    } else {
-        eprintln!("Could not find game {} in game_state", game_id);
-        return;
-    };
-
-    for participant in game_state.participants.iter() {
-        let read_connections = connections.read().await;
+    let read_connections = connections.read().await;
+    for participant in private_game_state.participants.iter() {
         let ws = match read_connections.get(&participant.user_id) {
             Some(participant_connection) => participant_connection,
             // participant not found in Connections map
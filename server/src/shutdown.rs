@@ -0,0 +1,106 @@
+use crate::{
+    routes::ws::{send_ws_message, GameCommand},
+    Connections, Games,
+};
+use common::STCMsg;
+use futures::future::join_all;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::{signal, sync::oneshot, time};
+use warp::ws::Message;
+
+/// How long clients are asked to wait before reconnecting, to give the server time to come back up
+static RECONNECT_AFTER_MS: u64 = 5_000;
+
+/// How long to wait for every game to be saved before giving up on the slow ones
+static SAVE_GAMES_TIMEOUT_MS: u64 = 5_000;
+
+/// How long to give websockets to deliver the shutdown notice before the process exits
+static NOTIFY_CLIENTS_GRACE_PERIOD_MS: u64 = 1_000;
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Set once a shutdown signal has been received. No new games are started after that.
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// Waits for SIGINT (e.g. Ctrl+C) or SIGTERM (e.g. `docker stop`)
+pub async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut sigterm = signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Could not listen for SIGTERM");
+        tokio::select! {
+            _ = signal::ctrl_c() => {},
+            _ = sigterm.recv() => {},
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        signal::ctrl_c().await.expect("Could not listen for Ctrl+C");
+    }
+
+    eprintln!("Shutdown signal received");
+    SHUTTING_DOWN.store(true, Ordering::SeqCst);
+}
+
+/// Saves and stops every game, then tells every client to reconnect once the server is back.
+///
+/// Games are stopped before anyone is told about the shutdown, so that clients disconnecting
+/// in response can't cause their games to be deleted.
+pub async fn shut_down(connections: &Connections, games: &Games) {
+    let read_games = games.read().await;
+    let saved_receivers: Vec<oneshot::Receiver<()>> = read_games
+        .values()
+        .filter_map(|tx| {
+            let (saved_tx, saved_rx) = oneshot::channel();
+            tx.send(GameCommand::Shutdown { saved: saved_tx })
+                .ok()
+                .map(|_| saved_rx)
+        })
+        .collect();
+    drop(read_games);
+
+    eprintln!("Saving {} games", saved_receivers.len());
+    if time::timeout(
+        Duration::from_millis(SAVE_GAMES_TIMEOUT_MS),
+        join_all(saved_receivers),
+    )
+    .await
+    .is_err()
+    {
+        eprintln!("Timed out waiting for every game to be saved");
+    }
+
+    let read_connections = connections.read().await;
+    let user_ids: Vec<String> = read_connections
+        .values()
+        .filter(|connection_data| connection_data.connected)
+        .map(|connection_data| connection_data.user_id.clone())
+        .collect();
+    drop(read_connections);
+
+    for user_id in user_ids.iter() {
+        send_ws_message::to_user(
+            user_id,
+            STCMsg::ServerShuttingDown {
+                reconnect_after_ms: RECONNECT_AFTER_MS,
+            },
+            connections,
+        )
+        .await;
+    }
+
+    // close every websocket once the notice has been sent
+    let read_connections = connections.read().await;
+    for user_id in user_ids.iter() {
+        if let Some(connection_data) = read_connections.get(user_id) {
+            connection_data.tx.send(Message::close()).ok();
+        }
+    }
+    drop(read_connections);
+
+    time::sleep(Duration::from_millis(NOTIFY_CLIENTS_GRACE_PERIOD_MS)).await;
+    eprintln!("Shut down");
+}